@@ -1,18 +1,116 @@
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use glam::Vec2;
 use winit::application::ApplicationHandler;
-use winit::event::{ElementState, KeyEvent, WindowEvent};
-use winit::event_loop::ActiveEventLoop;
-use winit::window::{Window, WindowId};
+use winit::dpi::PhysicalPosition;
+use winit::event::{DeviceEvent, DeviceId, ElementState, KeyEvent, MouseButton, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow};
+use winit::keyboard::PhysicalKey;
+use winit::window::{CursorGrabMode, Fullscreen, Window, WindowId};
 
-use crate::core::config::KyzuConfig;
+use crate::bake::BakeManager;
+use crate::command::history::CommandHistory;
+use crate::command::recorder::MacroRecorder;
+use crate::command::Command;
+use crate::core::config::{KyzuConfig, MirrorWindowConfig, PresentMode};
+use crate::core::event::{
+  AssetLoaded, BodyPicked, BodySpawned, CameraModeChanged, CameraMoved, EventBus, RegionSelected,
+};
 use crate::core::log::{LogLevel, Logger};
+use crate::core::math;
+use crate::core::notify::{NotificationQueue, NotifyAction, NotifyLevel};
+use crate::core::palette::Palette;
+use crate::core::png;
+use crate::core::task::TaskList;
+use crate::core::tick::SimClock;
 use crate::core::time::TimeState;
-use crate::input::state::InputState;
+use crate::input::binding;
+use crate::input::state::{DragEnd, InputState};
+use crate::mirror::MirrorWindow;
+use crate::render::camera::inertia::Inertia;
+use crate::render::camera::projection;
+use crate::render::camera::CameraSystem;
 use crate::render::kernel::Renderer;
 use crate::render::modules::body_renderer::BodyRenderer;
+use crate::render::modules::lines::LineRenderer;
+use crate::render::raycast;
+use crate::render::shared::{CameraMode, DisplayMode, FieldView};
+use crate::render::still::StillRender;
+use crate::render::watchdog::GpuWatchdog;
+use crate::ui::{
+  cursor, field_legend, log_panel, marquee, menu, overlay, probe_panel, settings, status_bar,
+  toasts, uv_panel, view_bar, UiSystem,
+};
 use crate::world::body::BodyManifest;
+use crate::world::probe::{ProbeLog, ProbePoint};
+use crate::world::registry::BodyRegistry;
+use crate::world::selection::{SelectMode, SelectTool, Selection};
+
+const DEFAULT_BRUSH_RADIUS: f32 = 24.0;
+const MIN_BRUSH_RADIUS: f32 = 4.0;
+const MAX_BRUSH_RADIUS: f32 = 256.0;
+/// Brush radius factor per scroll notch.
+const BRUSH_SCROLL_STEP: f32 = 1.15;
+/// Still render samples per frame; each blocks on a GPU readback.
+const STILL_SAMPLES_PER_FRAME: u32 = 4;
+
+/// How often a hidden window wakes to check on background tasks.
+const HIDDEN_TASK_POLL: Duration = Duration::from_millis(250);
+/// Longest sleep while something spins too slowly to see, so the rotation
+/// shown never falls far behind the sim.
+const MAX_SPIN_WAIT: Duration = Duration::from_secs(10);
+
+/// A --batch run may quit once every task has been reported and no still
+/// is left to save; exiting earlier would drop the script's work.
+fn batch_settled(tasks: &TaskList, still: Option<&StillRender>) -> bool
+{
+  tasks.tasks.is_empty() && still.is_none()
+}
+
+/// CPU-side scene state moved from a lost renderer into its replacement.
+struct CarriedScene
+{
+  registry: BodyRegistry,
+  camera_system: CameraSystem,
+  mode: CameraMode,
+  display_mode: DisplayMode,
+  field: FieldView,
+  show_axes: bool,
+}
+
+impl CarriedScene
+{
+  /// Keep the scene and drop everything else, so the old surface is gone
+  /// before a new one is created for the same window.
+  fn take(renderer: Renderer) -> Self
+  {
+    let Renderer { shared, camera_system, .. } = renderer;
+    Self {
+      registry: shared.body_registry,
+      camera_system,
+      mode: shared.mode,
+      display_mode: shared.display_mode,
+      field: shared.field,
+      show_axes: shared.show_axes,
+    }
+  }
+
+  /// Put the scene into a freshly built renderer. Selection and the
+  /// watchdog state live in App, so they are passed in.
+  fn restore(self, renderer: &mut Renderer, selected: &[usize], degraded: bool)
+  {
+    renderer.shared.body_registry = self.registry;
+    renderer.camera_system = self.camera_system;
+    renderer.shared.mode = self.mode;
+    renderer.shared.display_mode = self.display_mode;
+    renderer.shared.field = self.field;
+    renderer.shared.show_axes = self.show_axes;
+    renderer.shared.selected = selected.to_vec();
+    renderer.shared.degraded = degraded;
+  }
+}
 
 pub struct App
 {
@@ -20,23 +118,1097 @@ pub struct App
   pub logger: Logger,
   pub input: InputState,
   pub time: TimeState,
+  pub sim_clock: SimClock,
+  pub events: EventBus,
+  pub history: CommandHistory,
+  pub recorder: MacroRecorder,
+  pub tasks: TaskList,
+  pub notifications: NotificationQueue,
+  pub watchdog: GpuWatchdog,
+  pub selection: Selection,
+  pub select_tool: SelectTool,
+  pub probe: ProbeLog,
+  /// Still image being accumulated, if any.
+  pub still: Option<StillRender>,
+  /// Paint-select brush radius in physical pixels.
+  pub brush_radius: f32,
   pub window: Option<Arc<Window>>,
   pub renderer: Option<Renderer>,
+  /// Windows drawing the main view on their own devices (see mirror.rs).
+  pub mirrors: Vec<MirrorWindow>,
+  pub ui: Option<UiSystem>,
   pub pending_manifests: Vec<BodyManifest>,
+  /// Commands from --script, run once the renderer is up.
+  pub startup_script: Vec<Command>,
+  /// --batch: hide the window and exit after the startup script.
+  pub batch: bool,
+  /// The batch script has run; exit once its tasks and still are done.
+  exit_pending: bool,
+  /// Something changed since the last frame (input, resize, a command).
+  pub dirty: bool,
+  /// When egui asked for its next frame, if it did.
+  repaint_at: Option<Instant>,
+  /// The loop went to sleep without a frame pending.
+  idle: bool,
+  /// Window minimised (zero-sized) or fully covered; no frames are drawn.
+  minimized: bool,
+  occluded: bool,
 }
 
-impl App
-{
-  pub fn new(config: KyzuConfig, logger: Logger, manifests: Vec<BodyManifest>) -> Self
+impl App
+{
+  pub fn new(config: KyzuConfig, logger: Logger, manifests: Vec<BodyManifest>) -> Self
+  {
+    let sim_clock = SimClock::new(config.app.sim_tick_hz);
+    let watchdog = GpuWatchdog::new(Duration::from_millis(config.app.watchdog_frame_ms));
+
+    Self {
+      config,
+      logger,
+      input: InputState::new(),
+      time: TimeState::new(),
+      sim_clock,
+      events: EventBus::default(),
+      history: CommandHistory::default(),
+      recorder: MacroRecorder::default(),
+      tasks: TaskList::default(),
+      notifications: NotificationQueue::default(),
+      watchdog,
+      selection: Selection::default(),
+      select_tool: SelectTool::Box,
+      probe: ProbeLog::default(),
+      still: None,
+      brush_radius: DEFAULT_BRUSH_RADIUS,
+      window: None,
+      renderer: None,
+      mirrors: Vec::new(),
+      ui: None,
+      pending_manifests: manifests,
+      startup_script: Vec::new(),
+      batch: false,
+      exit_pending: false,
+      dirty: true,
+      repaint_at: None,
+      idle: false,
+      minimized: false,
+      occluded: false,
+    }
+  }
+
+  /// Build the renderer and its modules. `carried` is the scene from a
+  /// renderer whose device was lost; without it the registry is filled
+  /// from the pending manifests.
+  fn create_renderer(
+    &mut self,
+    window: &Arc<Window>,
+    carried: Option<CarriedScene>,
+  ) -> anyhow::Result<Renderer>
+  {
+    let transparent = self.config.app.transparent_window;
+    let wanted = self.config.app.adapter.as_deref();
+    let mut renderer = pollster::block_on(Renderer::new(window.clone(), transparent, wanted))?;
+    self.check_adapter(&renderer);
+    renderer.shared.palette = Palette::from_config(&self.config.app.palette);
+    renderer.camera_system.inertia = Inertia::new(&self.config.app.camera_inertia);
+    self.apply_present_mode(&mut renderer);
+
+    match carried
+    {
+      Some(scene) => scene.restore(&mut renderer, &self.selection.bodies, self.watchdog.tripped),
+      None =>
+      {
+        // Move manifests into the registry before building any GPU resources,
+        // so BodyRenderer can see the full registry in its constructor.
+        let manifests = std::mem::take(&mut self.pending_manifests);
+        for manifest in manifests
+        {
+          let name = manifest.name.clone();
+          let index = renderer.shared.body_registry.spawn(manifest, false);
+          self.events.body_spawned.publish(BodySpawned { index, name });
+        }
+        renderer.shared.body_registry.apply_flags(&self.config.save.body_flags);
+      }
+    }
+
+    let mesh_path = self.add_scene_modules(&mut renderer);
+    self.events.asset_loaded.publish(AssetLoaded { path: mesh_path });
+
+    // Prime the camera and upload initial matrices
+    renderer.camera_system.update(&mut renderer.shared, &mut self.input, 0.016);
+    renderer.shared.camera_gpu.upload(&renderer.queue, &renderer.shared.camera);
+    self.ensure_scene_in_view(&mut renderer);
+
+    Ok(renderer)
+  }
+
+  /// Build the scene modules (bodies, lines) on `renderer`'s device for
+  /// the bodies already in its registry. Returns the base mesh's path.
+  fn add_scene_modules(&mut self, renderer: &mut Renderer) -> PathBuf
+  {
+    // Unset antialiasing follows the adapter; fixed here so the settings
+    // and commands see the choice.
+    let (default_samples, default_fxaa) = renderer.shared.caps.default_antialiasing();
+    let fxaa = *self.config.app.fxaa.get_or_insert(default_fxaa);
+
+    // Pipelines are built for the sample count, so settle it first. A GPU
+    // that already tripped the watchdog gets no MSAA.
+    let mut samples = *self.config.app.msaa_samples.get_or_insert(default_samples);
+    if renderer.shared.degraded
+    {
+      samples = 1;
+    }
+    let samples = renderer.set_msaa(samples);
+    self.logger.emit(LogLevel::Info, &format!("MSAA: {}x", samples));
+    renderer.shared.fxaa = fxaa && !renderer.shared.degraded;
+
+    renderer.shared.filtering = self.config.app.texture_filtering();
+
+    // The icosphere is the base geometry for every body without a mesh bake
+    let mesh_path =
+      PathBuf::from(&self.config.app.data_dir).join("primitives").join("icosahedron.bake");
+
+    let mut body_renderer = BodyRenderer::new(
+      &renderer.device,
+      &renderer.queue,
+      &mut renderer.shared,
+      &mesh_path,
+      &mut self.logger,
+    );
+    let bake_dir = BakeManager::new(&self.config).output_root;
+    body_renderer.load_meshes(&renderer.device, &renderer.shared, &bake_dir, &mut self.logger);
+    body_renderer.load_textures(
+      &renderer.device,
+      &renderer.queue,
+      &mut renderer.shared,
+      &bake_dir,
+      &mut self.logger,
+    );
+    renderer.shared.body_mesh = Some(body_renderer.mesh_bvh());
+    renderer.shared.body_mesh_uvs = Some(body_renderer.mesh_uvs());
+    let extent = body_renderer.height_range();
+    let field = &mut renderer.shared.field;
+    if field.extent != extent
+    {
+      field.extent = extent;
+      field.min = extent[0];
+      field.max = extent[1];
+    }
+    renderer.add_module(body_renderer);
+
+    let lines =
+      LineRenderer::new(&renderer.device, &renderer.shared, self.config.app.line_width_px);
+    renderer.add_module(lines);
+    mesh_path
+  }
+
+  /// Open the configured mirror windows, each with a device on its own
+  /// adapter. One that fails is reported and left out.
+  fn open_mirrors(&mut self, event_loop: &ActiveEventLoop)
+  {
+    for config in self.config.app.mirror_windows.clone()
+    {
+      let mut window_attributes = Window::default_attributes()
+        .with_title(format!("Kyzu mirror {}", self.mirrors.len() + 1))
+        .with_inner_size(winit::dpi::PhysicalSize::new(config.width, config.height));
+      if let Some(index) = config.monitor
+      {
+        match event_loop.available_monitors().nth(index)
+        {
+          Some(monitor) =>
+          {
+            window_attributes =
+              window_attributes.with_fullscreen(Some(Fullscreen::Borderless(Some(monitor))));
+          }
+          None =>
+          {
+            let message = format!("No monitor {} for a mirror window; opening it windowed", index);
+            self.logger.emit(LogLevel::Warning, &message);
+          }
+        }
+      }
+
+      let opened = event_loop
+        .create_window(window_attributes)
+        .map_err(anyhow::Error::from)
+        .and_then(|window| {
+          let window = Arc::new(window);
+          let renderer = self.create_mirror_renderer(&window, &config)?;
+          Ok(MirrorWindow { window, renderer, config })
+        });
+      match opened
+      {
+        Ok(mirror) =>
+        {
+          let info = mirror.renderer.adapter.get_info();
+          let message = format!("Mirror window on {} ({:?})", info.name, info.backend);
+          self.logger.emit(LogLevel::Info, &message);
+          self.mirrors.push(mirror);
+        }
+        Err(e) => self.notify_error(&format!("Could not open a mirror window: {}", e)),
+      }
+    }
+  }
+
+  /// A renderer for a mirror window on the adapter `config` names, with
+  /// its own copy of the main scene.
+  fn create_mirror_renderer(
+    &mut self,
+    window: &Arc<Window>,
+    config: &MirrorWindowConfig,
+  ) -> anyhow::Result<Renderer>
+  {
+    let wanted = config.adapter.as_deref();
+    let mut renderer = pollster::block_on(Renderer::new(window.clone(), false, wanted))?;
+    renderer.shared.palette = Palette::from_config(&self.config.app.palette);
+    // The main window keeps its own vsync; a mirror waiting for a second
+    // display's vblank as well would halve the frame rate.
+    renderer.set_frame_latency(self.config.app.max_frame_latency);
+    renderer.set_present_mode(PresentMode::Mailbox);
+
+    // BodyRenderer sizes itself from the registry, so fill it first.
+    if let Some(main) = &self.renderer
+    {
+      renderer.shared.body_registry = main.shared.body_registry.clone();
+    }
+    self.add_scene_modules(&mut renderer);
+    Ok(renderer)
+  }
+
+  /// An event for a mirror window. Only resizing and closing matter: it
+  /// takes no input and is drawn with the main window.
+  fn mirror_event(&mut self, id: WindowId, event: WindowEvent)
+  {
+    let index = match self.mirrors.iter().position(|mirror| mirror.window.id() == id)
+    {
+      Some(index) => index,
+      None => return,
+    };
+    match event
+    {
+      WindowEvent::Resized(size) =>
+      {
+        self.mirrors[index].renderer.resize(Some(size));
+        self.dirty = true;
+      }
+      WindowEvent::CloseRequested =>
+      {
+        self.mirrors.remove(index);
+      }
+      _ => (),
+    }
+  }
+
+  /// Copy the main scene into every mirror window and draw it. A mirror
+  /// whose device was lost gets a new one; one that cannot be drawn is
+  /// closed so the error is reported once.
+  fn draw_mirrors(&mut self)
+  {
+    let mut index = 0;
+    while index < self.mirrors.len()
+    {
+      if self.mirrors[index].renderer.is_lost()
+      {
+        // The old surface goes before a new one is made for the window.
+        let MirrorWindow { window, config, .. } = self.mirrors.remove(index);
+        match self.create_mirror_renderer(&window, &config)
+        {
+          Ok(renderer) => self.mirrors.insert(index, MirrorWindow { window, renderer, config }),
+          Err(e) =>
+          {
+            self.notify_error(&format!("Mirror window closed after GPU loss: {}", e));
+            continue;
+          }
+        }
+      }
+
+      let main = match &self.renderer
+      {
+        Some(main) => main,
+        None => return,
+      };
+      let mirror = &mut self.mirrors[index];
+      mirror.sync(main);
+      if let Err(e) = mirror.draw()
+      {
+        self.mirrors.remove(index);
+        self.notify_error(&format!("Mirror window closed: {:?}", e));
+        continue;
+      }
+      index += 1;
+    }
+  }
+
+  /// Zoom to fit when the starting camera sees no body, so a bad saved
+  /// view or an oddly sized model never opens on an empty window.
+  fn ensure_scene_in_view(&mut self, renderer: &mut Renderer)
+  {
+    let shared = &renderer.shared;
+    let view_proj = glam::Mat4::from_cols_array_2d(&shared.camera.view_proj);
+    let visible = projection::any_body_visible(
+      &view_proj,
+      &shared.projection,
+      shared.eye_world,
+      shared.screen_height,
+      &shared.body_registry,
+    );
+    if visible
+    {
+      return;
+    }
+
+    let (center, radius) = match projection::scene_bounds(&shared.body_registry)
+    {
+      Some(bounds) => bounds,
+      None => return,
+    };
+    let distance = projection::fit_distance(&shared.projection, radius);
+    let previous = shared.mode;
+    renderer.camera_system.frame_target(&mut renderer.shared, center, distance);
+    if previous != CameraMode::Orbital
+    {
+      self.events.camera_mode_changed.publish(CameraModeChanged { mode: CameraMode::Orbital });
+    }
+    self.notifications.info("Nothing was in view; zoomed to fit the scene");
+  }
+
+  /// Warn when the configured adapter was not found, listing the ones
+  /// that were.
+  fn check_adapter(&mut self, renderer: &Renderer)
+  {
+    let wanted = match &self.config.app.adapter
+    {
+      Some(name) => name.clone(),
+      None => return,
+    };
+    if renderer.adapter.get_info().name.to_lowercase().contains(&wanted.to_lowercase())
+    {
+      return;
+    }
+
+    let available = renderer.adapter_names().join(", ");
+    let message = format!("No usable adapter matches '{}'; available: {}", wanted, available);
+    self.logger.emit(LogLevel::Warning, &message);
+  }
+
+  /// The GPU device was lost (driver reset, adapter unplugged). Build a
+  /// new renderer around the same scene.
+  fn recover_lost_device(&mut self)
+  {
+    let reason = match &self.renderer
+    {
+      Some(r) => r.lost_reason().unwrap_or_default(),
+      None => return,
+    };
+    self.logger.emit(LogLevel::Warning, &format!("GPU device lost ({}); rebuilding", reason));
+
+    match self.rebuild_renderer()
+    {
+      Ok(()) => self.notifications.warn("The GPU was reset; rendering has been restored"),
+      Err(e) => self.notify_error(&format!("Could not recover from GPU loss: {}", e)),
+    }
+  }
+
+  /// Drop the renderer, including its surface, and build a new one around
+  /// the same scene. GPU resources are all rebuilt from CPU-side state.
+  fn rebuild_renderer(&mut self) -> anyhow::Result<()>
+  {
+    let (old, window) = match (self.renderer.take(), self.window.clone())
+    {
+      (Some(r), Some(w)) => (r, w),
+      _ => return Ok(()),
+    };
+
+    let scene = CarriedScene::take(old);
+    let renderer = self.create_renderer(&window, Some(scene))?;
+    if let Some(ui) = &mut self.ui
+    {
+      ui.rebuild_gpu(&renderer.device, renderer.config.format, &window);
+    }
+    self.logger.emit(LogLevel::Info, &renderer.shared.caps.summary());
+    self.renderer = Some(renderer);
+    Ok(())
+  }
+
+  /// Run the --script commands. In batch mode the app quits once the work
+  /// they started has finished (see about_to_wait).
+  fn run_startup_script(&mut self, event_loop: &ActiveEventLoop)
+  {
+    let commands = std::mem::take(&mut self.startup_script);
+    if !commands.is_empty()
+    {
+      self.logger.emit(LogLevel::Info, &format!("Running script ({} commands)", commands.len()));
+    }
+    for command in commands
+    {
+      self.execute_command(event_loop, command);
+    }
+
+    self.exit_pending = self.batch;
+  }
+
+  /// Drive a pending batch exit. The window is never shown, so frames may
+  /// not arrive: the still is advanced here instead of on redraw.
+  fn settle_batch(&mut self, event_loop: &ActiveEventLoop)
+  {
+    self.advance_still();
+    self.report_finished_tasks();
+    if batch_settled(&self.tasks, self.still.as_ref())
+    {
+      self.exit_pending = false;
+      self.execute_command(event_loop, Command::Exit);
+      return;
+    }
+
+    let mut control_flow = ControlFlow::WaitUntil(Instant::now() + HIDDEN_TASK_POLL);
+    if self.still.is_some()
+    {
+      control_flow = ControlFlow::Poll;
+    }
+    event_loop.set_control_flow(control_flow);
+  }
+
+  /// Carry the world across time the loop slept through. Spins keep real
+  /// time while idle (spin_due wakes the loop to show them), but the gap
+  /// is jumped rather than stepped.
+  fn skip_simulation(&mut self, dt: f64)
+  {
+    if self.still.is_some()
+    {
+      return;
+    }
+    self.sim_clock.skip(dt);
+    if let Some(renderer) = &mut self.renderer
+    {
+      renderer.shared.body_registry.skip(dt);
+    }
+  }
+
+  /// Run however many fixed sim ticks this frame's real time covers, then
+  /// hand the leftover fraction to the renderer for interpolation.
+  fn step_simulation(&mut self)
+  {
+    // Hold the scene still while a still render samples it.
+    if self.still.is_some()
+    {
+      return;
+    }
+
+    let ticks = self.sim_clock.advance(self.time.delta.as_secs_f64());
+
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    for _ in 0..ticks
+    {
+      renderer.shared.body_registry.tick(self.sim_clock.tick_dt);
+    }
+
+    renderer.shared.sim_alpha = self.sim_clock.alpha;
+  }
+
+  /// Logging subscriber — reports last frame's events to the logger.
+  /// CameraMoved is too chatty to log; restart_still_on_camera_move reads it.
+  fn log_events(&mut self)
+  {
+    for event in self.events.body_spawned.read()
+    {
+      self.logger.emit(LogLevel::Info, &format!("Spawned body #{}: {}", event.index, event.name));
+    }
+
+    for event in self.events.asset_loaded.read()
+    {
+      self.logger.emit(LogLevel::Info, &format!("Asset loaded: {}", event.path.display()));
+    }
+
+    for event in self.events.camera_mode_changed.read()
+    {
+      self.logger.emit(LogLevel::Info, &format!("Camera mode: {:?}", event.mode));
+    }
+
+    for event in self.events.body_picked.read()
+    {
+      let name = self.body_name(event.index);
+      let mut message = format!("Picked: {}", name);
+      if let Some(point) = event.point
+      {
+        message = format!("{} at ({:.0}, {:.0}, {:.0}) m", message, point.x, point.y, point.z);
+      }
+      self.logger.emit(LogLevel::Info, &message);
+    }
+  }
+
+  /// Selection subscriber — applies last frame's clicks and hands the
+  /// result to the renderer for highlighting.
+  fn apply_picks(&mut self)
+  {
+    let before = self.selection.clone();
+
+    for event in self.events.body_picked.read()
+    {
+      match (event.mode, event.index)
+      {
+        (SelectMode::Replace, index) => self.selection.select_only(index),
+        (SelectMode::Add, Some(index)) => self.selection.toggle(index),
+        (SelectMode::Subtract, Some(index)) => self.selection.apply(SelectMode::Subtract, &[index]),
+        (_, None) => (),
+      }
+    }
+
+    for event in self.events.region_selected.read()
+    {
+      self.selection.apply(event.mode, &event.indices);
+    }
+
+    if self.selection == before
+    {
+      return;
+    }
+    if let Some(renderer) = &mut self.renderer
+    {
+      renderer.shared.selected.clone_from(&self.selection.bodies);
+    }
+  }
+
+  /// Registry name for a picked index, or "nothing".
+  fn body_name(&self, index: Option<usize>) -> String
+  {
+    if let (Some(renderer), Some(i)) = (&self.renderer, index)
+    {
+      if let Some(body) = renderer.shared.body_registry.bodies.get(i)
+      {
+        return body.manifest.name.clone();
+      }
+    }
+    "nothing".to_string()
+  }
+
+  /// Run the GPU ID pass and a CPU ray cast under the cursor and publish
+  /// what was hit.
+  fn pick_at_cursor(&mut self)
+  {
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let pixel = self.input.mouse_pos;
+    let point = renderer.shared.raycast(pixel).map(|hit| hit.point);
+    let mode = self.input.select_mode();
+    match renderer.pick(pixel, self.config.app.pick_tolerance_px)
+    {
+      Ok(index) => self.events.body_picked.publish(BodyPicked { index, pixel, point, mode }),
+      Err(e) => self.notify_error(&format!("Pick failed: {}", e)),
+    }
+  }
+
+  /// Record the mesh vertex nearest the surface point under the cursor.
+  fn probe_at_cursor(&mut self)
+  {
+    let renderer = match &self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+    let shared = &renderer.shared;
+    let (hit, mesh) = match (shared.raycast(self.input.mouse_pos), &shared.body_mesh)
+    {
+      (Some(hit), Some(mesh)) => (hit, mesh),
+      _ => return,
+    };
+
+    let position = raycast::snap_to_vertex(&hit, &shared.body_registry, mesh, shared.sim_alpha);
+    let body = &shared.body_registry.bodies[hit.body];
+    let inv_rotation = body.orientation(shared.sim_alpha).inverse();
+    let local = (inv_rotation * (position - body.world_pos)).normalize();
+
+    self.probe.points.push(ProbePoint {
+      body: body.manifest.name.clone(),
+      position,
+      lat_deg: local.y.asin().to_degrees(),
+      lon_deg: local.z.atan2(local.x).to_degrees(),
+    });
+  }
+
+  /// Publish every body whose centre projects inside the marquee, grown
+  /// by the pick tolerance.
+  fn box_select(&mut self, min: Vec2, max: Vec2)
+  {
+    let reach = Vec2::splat(self.config.app.pick_tolerance_px);
+    let (min, max) = (min - reach, max + reach);
+
+    let renderer = match &self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let mut indices = Vec::new();
+    for (index, body) in renderer.shared.body_registry.bodies.iter().enumerate()
+    {
+      if body.flags.no_pick
+      {
+        continue;
+      }
+      if let Some(screen) = renderer.shared.project(body.world_pos)
+      {
+        let pixel = screen.truncate();
+        if pixel.cmpge(min).all() && pixel.cmple(max).all()
+        {
+          indices.push(index);
+        }
+      }
+    }
+
+    let mode = self.input.select_mode();
+    self.events.region_selected.publish(RegionSelected { indices, mode });
+  }
+
+  /// Publish every body visible inside the lasso, read from the ID buffer.
+  fn lasso_select(&mut self)
+  {
+    let path = std::mem::take(&mut self.input.drag_path);
+    if path.len() < 3
+    {
+      return;
+    }
+
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let mut min = path[0];
+    let mut max = path[0];
+    for p in &path
+    {
+      min = min.min(*p);
+      max = max.max(*p);
+    }
+
+    let region = match renderer.pick_region(min, max)
+    {
+      Ok(region) => region,
+      Err(e) =>
+      {
+        self.notify_error(&format!("Lasso select failed: {}", e));
+        return;
+      }
+    };
+
+    let inside = |x: u32, y: u32| {
+      let centre = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+      math::point_in_polygon(centre, &path)
+    };
+    let indices = region.indices_where(inside);
+
+    let mode = self.input.select_mode();
+    self.events.region_selected.publish(RegionSelected { indices, mode });
+  }
+
+  /// While painting: scroll resizes the brush (instead of zooming), and
+  /// every cursor move adds the bodies under the brush. Ctrl paints
+  /// them out of the selection instead.
+  fn paint_select(&mut self)
+  {
+    if self.select_tool != SelectTool::Paint || self.input.marquee().is_none()
+    {
+      return;
+    }
+
+    if self.input.scroll_delta != 0.0
+    {
+      let scale = BRUSH_SCROLL_STEP.powf(self.input.scroll_delta);
+      self.brush_radius = (self.brush_radius * scale).clamp(MIN_BRUSH_RADIUS, MAX_BRUSH_RADIUS);
+      self.input.scroll_delta = 0.0;
+    }
+
+    if self.input.mouse_delta == Vec2::ZERO
+    {
+      return;
+    }
+
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let centre = self.input.mouse_pos;
+    let radius = self.brush_radius + self.config.app.pick_tolerance_px;
+    let reach = Vec2::splat(radius);
+    let region = match renderer.pick_region(centre - reach, centre + reach)
+    {
+      Ok(region) => region,
+      Err(e) =>
+      {
+        self.notify_error(&format!("Paint select failed: {}", e));
+        return;
+      }
+    };
+
+    let radius_sq = radius * radius;
+    let indices = region.indices_where(|x, y| {
+      let pixel = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+      pixel.distance_squared(centre) <= radius_sq
+    });
+
+    let mut mode = SelectMode::Add;
+    if self.input.select_mode() == SelectMode::Subtract
+    {
+      mode = SelectMode::Subtract;
+    }
+    self.events.region_selected.publish(RegionSelected { indices, mode });
+  }
+
+  /// Pass a window event to egui first. Returns true if egui claimed it.
+  fn feed_ui(&mut self, event: &WindowEvent) -> bool
+  {
+    match (&mut self.ui, &self.window)
+    {
+      (Some(ui), Some(window)) => ui.on_window_event(window, event),
+      _ => false,
+    }
+  }
+
+  /// Build this frame's UI. Painted later by Renderer::render.
+  /// Returns a command triggered from the UI (e.g. a toast action).
+  fn draw_ui(&mut self) -> Option<Command>
   {
-    Self {
-      config,
-      logger,
-      input: InputState::new(),
-      time: TimeState::new(),
-      window: None,
-      renderer: None,
-      pending_manifests: manifests,
+    let (ui, window) = match (&mut self.ui, &self.window)
+    {
+      (Some(ui), Some(window)) => (ui, window),
+      _ => return None,
+    };
+
+    self.notifications.expire();
+
+    ui.begin_frame(window);
+    let mut custom_cursor = None;
+    status_bar::draw(&ui.context, &self.tasks);
+    if ui.show_log
+    {
+      log_panel::draw(&ui.context, &self.logger, &mut ui.show_log);
+    }
+    if let Some(renderer) = &self.renderer
+    {
+      let orbiting = renderer.shared.mode == CameraMode::Orbital
+        && self.input.mouse_buttons_down.contains(&MouseButton::Right);
+      let pivot = renderer.camera_system.orbital_controller.target;
+
+      ui.pivot_marker.update(orbiting, self.time.delta_f32);
+      let palette = &renderer.shared.palette;
+      ui.pivot_marker.draw(&ui.context, renderer.shared.project(pivot), palette.marker);
+      custom_cursor =
+        cursor::apply(&ui.context, renderer.shared.mode, &self.input, ui.cursors.as_ref());
+      marquee::draw(&ui.context, self.select_tool, &self.input, self.brush_radius, palette);
+    }
+    if ui.show_telemetry
+    {
+      if let Some(renderer) = &self.renderer
+      {
+        overlay::draw(&ui.context, &renderer.shared, &self.time, &mut ui.show_telemetry);
+      }
+    }
+    let mut command = None;
+    if let Some(renderer) = &self.renderer
+    {
+      command = ui.search.draw(&ui.context, &renderer.shared.body_registry);
+      if ui.show_uv
+      {
+        if let Some(uv_command) = uv_panel::draw(&ui.context, &renderer.shared, &mut ui.show_uv)
+        {
+          command = Some(uv_command);
+        }
+      }
+    }
+    if let Some(renderer) = &self.renderer
+    {
+      if renderer.shared.display_mode == DisplayMode::Field
+      {
+        if let Some(legend_command) = field_legend::draw(&ui.context, &renderer.shared)
+        {
+          command = Some(legend_command);
+        }
+      }
+    }
+    if self.probe.active
+    {
+      let export_path = self.config.save_dir.join("probe_points.csv");
+      let path = export_path.to_string_lossy();
+      if let Some(probe_command) = probe_panel::draw(&ui.context, &self.probe, &path)
+      {
+        command = Some(probe_command);
+      }
+    }
+    if ui.show_settings
+    {
+      if let Some(settings_command) =
+        settings::draw(&ui.context, &self.config.app, &mut ui.still, &mut ui.show_settings)
+      {
+        command = Some(settings_command);
+      }
+    }
+    if let Some(view_command) = view_bar::draw(&ui.context)
+    {
+      command = Some(view_command);
+    }
+    if let Some(insert_command) = menu::draw(&ui.context)
+    {
+      command = Some(insert_command);
+    }
+    if let Some(toast_command) = toasts::draw(&ui.context, &mut self.notifications)
+    {
+      command = Some(toast_command);
+    }
+    ui.end_frame(window);
+    // After egui's output, which only touches the cursor when its icon changes.
+    if let Some(custom_cursor) = custom_cursor
+    {
+      window.set_cursor(custom_cursor);
+    }
+
+    command
+  }
+
+  /// Configure the surface for the configured present mode and frame
+  /// latency, warning if the surface does not offer the mode.
+  pub fn apply_present_mode(&mut self, renderer: &mut Renderer)
+  {
+    renderer.set_frame_latency(self.config.app.max_frame_latency);
+    let wanted = self.config.app.present_mode();
+    if !renderer.set_present_mode(wanted)
+    {
+      let message = format!("Present mode {} not supported; using fifo", wanted.name());
+      self.logger.emit(LogLevel::Warning, &message);
+    }
+  }
+
+  /// When the next frame may start under the fps cap, if one is set.
+  fn next_frame_due(&self) -> Option<Instant>
+  {
+    if self.config.app.fps_cap == 0
+    {
+      return None;
+    }
+    let interval = Duration::from_secs_f64(1.0 / self.config.app.fps_cap as f64);
+    Some(self.time.last_frame + interval)
+  }
+
+  /// Whether another frame is needed now. Anything that moves on its own
+  /// (camera animation, held keys, progress bars, toasts) keeps frames
+  /// coming, and spins once they have moved a pixel; otherwise the loop
+  /// sleeps until an event.
+  fn needs_redraw(&self) -> bool
+  {
+    if self.is_hidden()
+    {
+      return false;
+    }
+
+    let mut moving = false;
+    if let Some(renderer) = &self.renderer
+    {
+      moving = renderer.camera_system.is_moving();
+    }
+    let now = Instant::now();
+    let ui_due = self.repaint_at.is_some_and(|at| now >= at);
+    let spin_due = self.spin_due().is_some_and(|at| now >= at);
+
+    self.dirty
+      || ui_due
+      || spin_due
+      || moving
+      || self.input.is_held()
+      || !self.tasks.tasks.is_empty()
+      || !self.notifications.items.is_empty()
+  }
+
+  /// When spinning bodies will have moved a pixel since the last frame, so
+  /// a slow spin wakes the loop now and then instead of keeping it busy.
+  /// A fast one is due every frame. None when nothing spins.
+  fn spin_due(&self) -> Option<Instant>
+  {
+    let renderer = self.renderer.as_ref()?;
+    let shared = &renderer.shared;
+    let pixels_per_radian = shared.screen_height as f64 / shared.projection.fov_y_rad as f64;
+    let speed = shared.body_registry.spin_pixels_per_second(shared.eye_world, pixels_per_radian);
+    if speed <= 0.0
+    {
+      return None;
+    }
+    let wait = (1.0 / speed).min(MAX_SPIN_WAIT.as_secs_f64());
+    Some(self.time.last_frame + Duration::from_secs_f64(wait))
+  }
+
+  /// Nothing of the window can be seen, so frames are skipped and the
+  /// surface is left alone until it is shown again.
+  fn is_hidden(&self) -> bool
+  {
+    self.minimized || self.occluded
+  }
+
+  /// Lock (or failing that, confine) the pointer for a right-drag so the
+  /// view keeps turning past the window edge. Look reads raw motion.
+  fn grab_pointer(&mut self)
+  {
+    let window = match &self.window
+    {
+      Some(w) => w,
+      None => return,
+    };
+
+    self.input.look_grab = Some(self.input.mouse_pos);
+    if window.set_cursor_grab(CursorGrabMode::Locked).is_err()
+    {
+      let _ = window.set_cursor_grab(CursorGrabMode::Confined);
+    }
+  }
+
+  /// Undo grab_pointer and put the cursor back where the drag started.
+  fn release_pointer(&mut self)
+  {
+    let (origin, window) = match (self.input.look_grab.take(), &self.window)
+    {
+      (Some(origin), Some(window)) => (origin, window),
+      _ => return,
+    };
+
+    let _ = window.set_cursor_grab(CursorGrabMode::None);
+    let _ = window.set_cursor_position(PhysicalPosition::new(origin.x, origin.y));
+  }
+
+  /// Log an error and show it to the player as a toast.
+  pub fn notify_error(&mut self, message: &str)
+  {
+    self.logger.emit(LogLevel::Error, message);
+    self.notifications.push(
+      NotifyLevel::Error,
+      message,
+      Some(NotifyAction { label: "Show log".to_string(), command: Command::ToggleLogPanel }),
+    );
+  }
+
+  /// Feed the frame time to the GPU watchdog; on a trip, degrade the
+  /// renderer, log diagnostics and warn the player.
+  fn check_watchdog(&mut self)
+  {
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let reason = match self.watchdog.check(self.time.delta, renderer.surface_timeouts)
+    {
+      Some(reason) => reason,
+      None => return,
+    };
+
+    let diagnostics = renderer.degrade();
+    let multisampled = renderer.shared.sample_count > 1;
+    self.logger.emit(LogLevel::Warning, &format!("GPU watchdog tripped: {}", reason));
+    self.logger.emit(LogLevel::Warning, &diagnostics);
+
+    // Pipelines are built for the sample count, so dropping MSAA means
+    // rebuilding the renderer around the same scene.
+    if multisampled
+    {
+      if let Err(e) = self.rebuild_renderer()
+      {
+        self.notify_error(&format!("Could not switch to reduced quality: {}", e));
+        return;
+      }
+    }
+    self.notifications.warn("GPU is struggling; switched to reduced quality");
+  }
+
+  /// CameraMoved subscriber: every sample of a still must come from one
+  /// view, so moving the camera while it accumulates starts it over.
+  fn restart_still_on_camera_move(&mut self)
+  {
+    if self.events.camera_moved.read().is_empty()
+    {
+      return;
+    }
+    if let Some(still) = &mut self.still
+    {
+      still.restart();
+    }
+  }
+
+  /// Render the next few samples of the still, saving it after the last.
+  fn advance_still(&mut self)
+  {
+    let (still, renderer) = match (&mut self.still, &mut self.renderer)
+    {
+      (Some(s), Some(r)) => (s, r),
+      _ => return,
+    };
+
+    for _ in 0..STILL_SAMPLES_PER_FRAME
+    {
+      if still.is_done() || still.task.is_cancelled()
+      {
+        break;
+      }
+      let texture = renderer.render_jittered(still.width, still.height, still.next_jitter());
+      match renderer.read_texture(&texture)
+      {
+        Ok(rgba) => still.accumulate(&rgba),
+        Err(e) =>
+        {
+          still.task.fail(&format!("{}", e));
+          break;
+        }
+      }
+    }
+
+    let stopped = still.task.is_cancelled() || still.task.has_failed();
+    if !still.is_done() && !stopped
+    {
+      return;
+    }
+
+    if !stopped
+    {
+      let rgba = still.resolve();
+      match png::write_rgba(&still.output, still.width, still.height, &rgba)
+      {
+        Ok(()) => still.task.set_progress(1.0, &format!("saved {}", still.output.display())),
+        Err(e) => still.task.fail(&format!("{}: {}", still.output.display(), e)),
+      }
+    }
+    still.task.finish();
+    self.still = None;
+  }
+
+  fn report_finished_tasks(&mut self)
+  {
+    for task in self.tasks.take_finished()
+    {
+      let msg = format!("Task '{}' finished: {}", task.name(), task.message());
+      self.logger.emit(LogLevel::Info, &msg);
+
+      if task.is_cancelled()
+      {
+        self.notifications.warn(&format!("{} cancelled", task.name()));
+      }
+      else if task.has_failed()
+      {
+        let retry = task
+          .retry()
+          .map(|command| NotifyAction { label: "Retry".to_string(), command: command.clone() });
+        self.notifications.push(NotifyLevel::Error, &msg, retry);
+      }
+      else
+      {
+        self.notifications.info(&format!("{} complete", task.name()));
+      }
     }
   }
 }
@@ -47,38 +1219,32 @@ impl ApplicationHandler for App
   {
     if self.window.is_none()
     {
-      let window_attributes = Window::default_attributes().with_title("Kyzu").with_inner_size(
-        winit::dpi::LogicalSize::new(self.config.app.window_width, self.config.app.window_height),
-      );
+      let transparent = self.config.app.transparent_window;
+      let window_attributes = Window::default_attributes()
+        .with_title("Kyzu")
+        .with_inner_size(winit::dpi::LogicalSize::new(
+          self.config.app.window_width,
+          self.config.app.window_height,
+        ))
+        .with_transparent(transparent)
+        .with_visible(!self.batch);
 
       let window =
         Arc::new(event_loop.create_window(window_attributes).expect("Failed to create window"));
 
-      let mut renderer = pollster::block_on(Renderer::new(window.clone()))
-        .expect("Failed to initialize GPU renderer");
+      let renderer =
+        self.create_renderer(&window, None).expect("Failed to initialize GPU renderer");
 
-      // Move manifests into the registry before building any GPU resources,
-      // so BodyRenderer can see the full registry in its constructor.
-      let manifests = std::mem::take(&mut self.pending_manifests);
-      for manifest in manifests
+      let mut ui = UiSystem::new(&renderer.device, renderer.config.format, &window);
+      match cursor::CustomCursors::new(event_loop)
       {
-        let name = manifest.name.clone();
-        let kind = format!("{:?}", manifest.kind);
-        renderer.shared.body_registry.spawn(manifest, false);
-        self.logger.emit(LogLevel::Info, &format!("Spawned body: {} ({})", name, kind));
+        Ok(cursors) => ui.cursors = Some(cursors),
+        Err(e) =>
+        {
+          self.logger.emit(LogLevel::Warning, &format!("Custom cursors unavailable: {}", e))
+        }
       }
-
-      // Resolve the icosphere mesh path used as the base geometry for all bodies
-      let mesh_path =
-        PathBuf::from(&self.config.app.data_dir).join("primitives").join("icosahedron.bake");
-
-      let body_renderer =
-        BodyRenderer::new(&renderer.device, &renderer.shared, &mesh_path, &mut self.logger);
-      renderer.add_module(body_renderer);
-
-      // Prime the camera and upload initial matrices
-      renderer.camera_system.update(&mut renderer.shared, &mut self.input, 0.016);
-      renderer.shared.camera_gpu.upload(&renderer.queue, &renderer.shared.camera);
+      self.ui = Some(ui);
 
       self.renderer = Some(renderer);
       self.window = Some(window);
@@ -87,60 +1253,119 @@ impl ApplicationHandler for App
       {
         let mode_msg = format!("Initial camera mode: {:?}", renderer.shared.mode);
         self.logger.emit(LogLevel::Info, &mode_msg);
+        self.logger.emit(LogLevel::Info, &renderer.shared.caps.summary());
+      }
+      if !self.batch
+      {
+        self.open_mirrors(event_loop);
       }
       self.logger.emit(LogLevel::Info, "Kyzu engine initialised");
+
+      self.run_startup_script(event_loop);
     }
   }
 
-  fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent)
+  fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent)
   {
-    self.input.process_event(&event);
+    if self.window.as_ref().is_some_and(|window| window.id() != id)
+    {
+      self.mirror_event(id, event);
+      return;
+    }
+
+    if !matches!(event, WindowEvent::RedrawRequested)
+    {
+      self.dirty = true;
+    }
+
+    let ui_claimed = self.feed_ui(&event);
+    if !ui_claimed
+    {
+      self.input.process_event(&event);
+    }
 
     match event
     {
       WindowEvent::CloseRequested =>
       {
-        self.logger.emit(LogLevel::Info, "Exit requested.");
-        self.renderer = None;
-        event_loop.exit();
+        self.execute_command(event_loop, Command::Exit);
       }
 
       WindowEvent::KeyboardInput {
-        event: KeyEvent { logical_key: key, state: ElementState::Pressed, .. },
+        event:
+          KeyEvent {
+            physical_key: PhysicalKey::Code(code),
+            state: ElementState::Pressed,
+            repeat: false,
+            ..
+          },
         ..
       } =>
       {
-        use winit::keyboard::{Key, NamedKey};
+        if ui_claimed
+        {
+          return;
+        }
 
-        match key
+        if let Some(command) = binding::command_for_key(code, &self.input)
         {
-          Key::Named(NamedKey::Escape) =>
-          {
-            self.logger.emit(LogLevel::Info, "Exit requested via Escape.");
-            self.renderer = None;
-            event_loop.exit();
-          }
+          self.execute_command(event_loop, command);
+        }
+      }
 
-          Key::Named(NamedKey::Tab) =>
-          {
-            if let Some(renderer) = &mut self.renderer
-            {
-              use crate::render::shared::CameraMode;
-              renderer.shared.mode = match renderer.shared.mode
-              {
-                CameraMode::Free => CameraMode::Orbital,
-                CameraMode::Orbital => CameraMode::Free,
-              };
-              self.logger.emit(LogLevel::Info, &format!("Camera mode: {:?}", renderer.shared.mode));
-            }
-          }
+      WindowEvent::MouseInput { state: ElementState::Pressed, button, .. } =>
+      {
+        if ui_claimed
+        {
+          return;
+        }
+
+        if button == MouseButton::Right
+        {
+          self.grab_pointer();
+        }
+        if let Some(command) = binding::command_for_mouse_button(button)
+        {
+          self.execute_command(event_loop, command);
+        }
+      }
 
-          _ => (),
+      WindowEvent::MouseInput {
+        state: ElementState::Released, button: MouseButton::Right, ..
+      } => self.release_pointer(),
+
+      WindowEvent::Focused(false) => self.release_pointer(),
+
+      // Selection acts on release, so a press can grow into a marquee drag.
+      WindowEvent::MouseInput {
+        state: ElementState::Released, button: MouseButton::Left, ..
+      } =>
+      {
+        if ui_claimed
+        {
+          return;
+        }
+
+        match self.input.finish_drag()
+        {
+          Some(DragEnd::Click) if self.probe.active => self.probe_at_cursor(),
+          Some(DragEnd::Click) => self.pick_at_cursor(),
+          Some(DragEnd::Marquee(min, max)) => match self.select_tool
+          {
+            SelectTool::Box => self.box_select(min, max),
+            SelectTool::Lasso => self.lasso_select(),
+            // Paint applies continuously while dragging.
+            SelectTool::Paint => (),
+          },
+          None => (),
         }
       }
 
+      WindowEvent::Occluded(occluded) => self.occluded = occluded,
+
       WindowEvent::Resized(physical_size) =>
       {
+        self.minimized = physical_size.width == 0 || physical_size.height == 0;
         if let Some(renderer) = &mut self.renderer
         {
           renderer.resize(Some(physical_size));
@@ -149,31 +1374,84 @@ impl ApplicationHandler for App
 
       WindowEvent::RedrawRequested =>
       {
+        if self.is_hidden()
+        {
+          return;
+        }
+
+        if self.renderer.as_ref().is_some_and(|r| r.is_lost())
+        {
+          self.recover_lost_device();
+        }
+
+        if self.idle
+        {
+          self.skip_simulation(self.time.last_frame.elapsed().as_secs_f64());
+          self.time.restart();
+          self.idle = false;
+        }
+        self.dirty = false;
+
         self.time.update();
         let dt = self.time.delta_f32;
 
+        self.step_simulation();
+        self.paint_select();
+        let ui_command = self.draw_ui();
+
+        let mut frame_error = None;
+
         if let Some(renderer) = &mut self.renderer
         {
+          let eye_before = renderer.shared.eye_world;
+
           if let Err(e) = renderer.update(&mut self.input, dt)
           {
-            eprintln!("Update error: {:?}", e);
+            frame_error = Some(format!("Update error: {:?}", e));
+          }
+
+          if renderer.shared.eye_world != eye_before
+          {
+            self.events.camera_moved.publish(CameraMoved { eye_world: renderer.shared.eye_world });
           }
 
-          if let Err(e) = renderer.render()
+          if let Err(e) = renderer.render(self.ui.as_mut())
           {
             let err_str = format!("{:?}", e);
             if !err_str.contains("reconfigured")
             {
-              eprintln!("Render error: {}", err_str);
+              frame_error = Some(format!("Render error: {}", err_str));
             }
           }
+          if let Some(at) = self.input.input_at.take()
+          {
+            self.time.input_latency = at.elapsed();
+          }
+        }
+
+        self.draw_mirrors();
+        if let Some(message) = frame_error
+        {
+          self.notify_error(&message);
+        }
+        self.restart_still_on_camera_move();
+        self.advance_still();
+
+        if let Some(command) = ui_command
+        {
+          self.execute_command(event_loop, command);
         }
 
+        self.check_watchdog();
         self.input.tick();
+        self.log_events();
+        self.apply_picks();
+        self.events.swap();
 
-        if let Some(window) = &self.window
+        self.repaint_at = None;
+        if let Some(ui) = &self.ui
         {
-          window.request_redraw();
+          self.repaint_at = Instant::now().checked_add(ui.repaint_delay);
         }
       }
 
@@ -181,11 +1459,134 @@ impl ApplicationHandler for App
     }
   }
 
-  fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop)
+  fn device_event(&mut self, _event_loop: &ActiveEventLoop, _id: DeviceId, event: DeviceEvent)
+  {
+    self.input.process_device_event(&event);
+  }
+
+  fn about_to_wait(&mut self, event_loop: &ActiveEventLoop)
   {
-    if let Some(window) = &self.window
+    if self.exit_pending
+    {
+      self.settle_batch(event_loop);
+      return;
+    }
+
+    // Polled here rather than per frame: a hidden window draws nothing but
+    // its tasks still finish and need reporting.
+    self.report_finished_tasks();
+
+    let mut control_flow = ControlFlow::Wait;
+    if self.needs_redraw()
     {
-      window.request_redraw();
+      match self.next_frame_due().filter(|at| *at > Instant::now())
+      {
+        // Ahead of the fps cap: sleep until the frame is due, then ask again.
+        Some(at) => control_flow = ControlFlow::WaitUntil(at),
+        None =>
+        {
+          if let Some(window) = &self.window
+          {
+            window.request_redraw();
+          }
+        }
+      }
+    }
+    else
+    {
+      self.idle = true;
+      let wake = [self.repaint_at, self.spin_due()].into_iter().flatten().min();
+      if let Some(at) = wake.filter(|_| !self.is_hidden())
+      {
+        control_flow = ControlFlow::WaitUntil(at);
+      }
+      if self.is_hidden() && !self.tasks.tasks.is_empty()
+      {
+        control_flow = ControlFlow::WaitUntil(Instant::now() + HIDDEN_TASK_POLL);
+      }
+    }
+    event_loop.set_control_flow(control_flow);
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+  use crate::render::kernel::test_renderer;
+  use crate::render::still::StillSettings;
+  use crate::world::body::{BodyKind, BodyManifest};
+
+  fn manifest(name: &str) -> BodyManifest
+  {
+    BodyManifest {
+      name: name.to_string(),
+      kind: BodyKind::SmallBody { base_color: [0.5; 3] },
+      radius_m: 1000.0,
+      lod_max: 0,
+      position_at_epoch: glam::DVec3::ZERO,
+      orbital_elements: None,
+      axial_tilt_rad: 0.0,
+      rotation_period_s: 3600.0,
     }
   }
+
+  #[test]
+  fn batch_exit_waits_for_tasks_and_still()
+  {
+    let mut tasks = TaskList::default();
+    assert!(batch_settled(&tasks, None));
+
+    let bake = tasks.start("Bake", None);
+    assert!(!batch_settled(&tasks, None));
+
+    // Finished but not yet reported: still counts as outstanding.
+    bake.finish();
+    assert!(!batch_settled(&tasks, None));
+    tasks.take_finished();
+    assert!(batch_settled(&tasks, None));
+
+    let task = tasks.start("Still", None);
+    let still = StillRender::new(
+      StillSettings { width: 4, height: 4, samples: 2 },
+      PathBuf::from("still.png"),
+      task.clone(),
+    );
+    assert!(!batch_settled(&tasks, Some(&still)));
+    task.finish();
+    tasks.take_finished();
+    assert!(!batch_settled(&tasks, Some(&still)));
+    assert!(batch_settled(&tasks, None));
+  }
+
+  #[test]
+  fn lost_device_scene_carries_into_the_new_renderer()
+  {
+    let (mut old, mut new) = match (test_renderer(), test_renderer())
+    {
+      (Some(old), Some(new)) => (old, new),
+      _ => return,
+    };
+
+    old.shared.body_registry.spawn(manifest("ceres"), false);
+    old.shared.body_registry.spawn(manifest("vesta"), false);
+    old.shared.mode = CameraMode::Free;
+    old.shared.display_mode = DisplayMode::Checker;
+    old.shared.field.min = -5.0;
+    old.shared.show_axes = false;
+    old.camera_system.orbital_controller.lat = 42.0;
+
+    CarriedScene::take(old).restore(&mut new, &[1], true);
+
+    let names: Vec<&str> =
+      new.shared.body_registry.bodies.iter().map(|b| b.manifest.name.as_str()).collect();
+    assert_eq!(names, ["ceres", "vesta"]);
+    assert_eq!(new.shared.mode, CameraMode::Free);
+    assert_eq!(new.shared.display_mode, DisplayMode::Checker);
+    assert_eq!(new.shared.field.min, -5.0);
+    assert!(!new.shared.show_axes);
+    assert_eq!(new.camera_system.orbital_controller.lat, 42.0);
+    assert_eq!(new.shared.selected, [1]);
+    assert!(new.shared.degraded);
+  }
 }
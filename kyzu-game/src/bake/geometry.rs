@@ -1,4 +1,5 @@
 use std::f32::consts::PI;
+use std::path::Path;
 
 use bytemuck::{Pod, Zeroable};
 use glam::Vec3;
@@ -19,6 +20,23 @@ pub struct BakedVertex
   pub barycentric: [f32; 3],
 }
 
+/// Vertices of a `.bake` file as written by BakeManager::save_bake_to_disk:
+/// a u32 vertex count, the vertices, then an index list the renderer
+/// doesn't use (every bake is unwelded).
+pub fn load_bake(path: &Path) -> anyhow::Result<Vec<BakedVertex>>
+{
+  let data = std::fs::read(path)
+    .map_err(|e| anyhow::anyhow!("Could not read bake {}: {}", path.display(), e))?;
+
+  let truncated = || anyhow::anyhow!("Bake {} is truncated", path.display());
+  let header: [u8; 4] = data.get(0..4).ok_or_else(truncated)?.try_into()?;
+  let count = u32::from_le_bytes(header) as usize;
+  let end = 4 + count * std::mem::size_of::<BakedVertex>();
+  let bytes = data.get(4..end).ok_or_else(truncated)?;
+
+  Ok(bytemuck::pod_collect_to_vec(bytes))
+}
+
 pub struct SphericalMapper;
 
 impl SphericalMapper
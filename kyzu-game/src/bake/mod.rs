@@ -1,4 +1,5 @@
 pub mod geometry;
+pub mod obj_reader;
 pub mod registry;
 pub mod subdivider;
 pub mod tiff_reader;
@@ -11,8 +12,11 @@ use crate::bake::subdivider::Subdivider;
 use crate::bake::tiff_reader::EtopoTiff;
 use crate::core::config::KyzuConfig;
 use crate::core::log::{LogLevel, Logger};
+use crate::core::task::TaskHandle;
+use crate::render::primitives::{self, Primitive};
 use crate::world::body::BodyManifest;
 
+#[derive(Clone)]
 pub struct BakeManager
 {
   /// Root data directory from AppConfig
@@ -46,34 +50,49 @@ impl BakeManager
     Self { data_dir, world_root, output_root, primitives_root, source_assets }
   }
 
-  pub fn start_bake(&self, logger: &mut Logger)
+  /// Run the full bake, reporting progress to `task` and stopping between
+  /// bodies if it is cancelled. The task is always marked finished on return.
+  pub fn start_bake(&self, logger: &mut Logger, task: &TaskHandle)
   {
     logger.emit(LogLevel::Info, "Starting system-wide bake...");
     let _ = fs::create_dir_all(&self.output_root);
     let _ = fs::create_dir_all(&self.primitives_root);
 
-    if let Err(e) = self.cook_all(logger)
+    match self.cook_all(logger, task)
     {
-      logger.emit(LogLevel::Error, &format!("Bake failed: {}", e));
+      Ok(()) => task.set_progress(1.0, "Done"),
+      Err(e) =>
+      {
+        logger.emit(LogLevel::Error, &format!("Bake failed: {}", e));
+        task.fail(&format!("Failed: {}", e));
+      }
     }
+
+    task.finish();
   }
 
-  fn cook_all(&self, logger: &mut Logger) -> anyhow::Result<()>
+  fn cook_all(&self, logger: &mut Logger, task: &TaskHandle) -> anyhow::Result<()>
   {
-    // 1. Bake the reference icosahedron to the primitives directory
-    let (v_raw, i_raw) = geometry::get_base_icosahedron();
-    let base_indices: Vec<u32> = i_raw.into_iter().map(|i| i as u32).collect();
+    task.set_progress(0.0, "Primitives");
 
-    let iso_path = self.primitives_root.join("icosahedron.bake");
-    self.save_bake_to_disk(iso_path.to_str().unwrap(), &v_raw, &base_indices)?;
-    logger.emit(LogLevel::Info, &format!("Baked Reference Icosahedron to {:?}", iso_path));
+    // 1. Bake the primitive library to the primitives directory
+    self.cook_primitives(logger)?;
 
     // 2. Load the bodies registry relative to the world root
     let registry_path = self.world_root.join("bodies.json");
     let registry = load_bodies(&registry_path)?;
 
-    for body in &registry.bodies
+    let body_count = registry.bodies.len().max(1) as f32;
+
+    for (index, body) in registry.bodies.iter().enumerate()
     {
+      if task.is_cancelled()
+      {
+        logger.emit(LogLevel::Warning, "Bake cancelled");
+        return Err(anyhow::anyhow!("cancelled"));
+      }
+
+      task.set_progress(index as f32 / body_count, &body.name);
       self.cook_body(body, logger)?;
     }
     Ok(())
@@ -83,6 +102,11 @@ impl BakeManager
   {
     logger.emit(LogLevel::Info, &format!("Baking Body: {}", body.name));
 
+    if let Some(mesh_file) = &body.mesh_path
+    {
+      return self.cook_mesh_body(body, mesh_file, logger);
+    }
+
     let mut tiff_reader = None;
     if body.use_real_data && body.elevation_map_path.is_some()
     {
@@ -123,23 +147,7 @@ impl BakeManager
     // 3. Unweld & Assign Barycentrics (Non-Indexed Mode)
     // This allows for the "Kyzu" wireframe look by giving each triangle unique vertices.
     logger.emit(LogLevel::Info, "Unwelding vertices for barycentric wireframes...");
-    let mut flat_vertices = Vec::with_capacity(indices.len());
-
-    for (i, &idx) in indices.iter().enumerate()
-    {
-      let mut v = vertices[idx as usize].clone();
-
-      // Assign [1,0,0], [0,1,0], or [0,0,1] based on the corner of the triangle
-      let corner = i % 3;
-      v.barycentric = match corner
-      {
-        0 => [1.0, 0.0, 0.0],
-        1 => [0.0, 1.0, 0.0],
-        _ => [0.0, 0.0, 1.0],
-      };
-
-      flat_vertices.push(v);
-    }
+    let flat_vertices = primitives::unweld(&vertices, &indices);
 
     // 4. Save to Disk
     let file_name = format!("{}.bake", body.name.to_lowercase());
@@ -158,6 +166,72 @@ impl BakeManager
     Ok(())
   }
 
+  /// The base icosahedron and every Primitive, unwelded so BodyRenderer
+  /// can draw any of them as a body mesh.
+  fn cook_primitives(&self, logger: &mut Logger) -> anyhow::Result<()>
+  {
+    let (ico_v, ico_i) = geometry::get_base_icosahedron();
+    let ico_i: Vec<u32> = ico_i.into_iter().map(|i| i as u32).collect();
+
+    let mut library: Vec<(&str, primitives::Mesh)> = vec![("icosahedron", (ico_v, ico_i))];
+    for primitive in Primitive::ALL
+    {
+      library.push((primitive.name(), primitive.mesh()));
+    }
+
+    for (name, (vertices, indices)) in library
+    {
+      let flat = primitives::unweld(&vertices, &indices);
+      let path = self.primitives_root.join(format!("{}.bake", name));
+      self.save_bake_to_disk(path.to_str().unwrap(), &flat, &[])?;
+      logger.emit(
+        LogLevel::Info,
+        &format!("Baked primitive {} ({} triangles) to {:?}", name, flat.len() / 3, path),
+      );
+    }
+
+    Ok(())
+  }
+
+  /// Manmade bodies: import the OBJ as-is instead of subdividing a sphere.
+  /// Material colours go to a JSON sidecar, indexed by each vertex's hex_id.
+  /// The first PNG diffuse map is copied next to the manifest as
+  /// `<name>.png`, where BodyRenderer::load_textures looks for it.
+  fn cook_mesh_body(
+    &self,
+    body: &BodyConfig,
+    mesh_file: &str,
+    logger: &mut Logger,
+  ) -> anyhow::Result<()>
+  {
+    let obj_path = self.source_assets.join(body.name.to_lowercase()).join(mesh_file);
+    let mesh = obj_reader::load(&obj_path, logger)?;
+
+    let stem = body.name.to_lowercase();
+    let output_path = self.output_root.join(format!("{}.bake", stem));
+    self.save_bake_to_disk(output_path.to_str().unwrap(), &mesh.vertices, &[])?;
+
+    let materials_path = self.output_root.join(format!("{}.materials.json", stem));
+    fs::write(&materials_path, serde_json::to_string_pretty(&mesh.materials)?)?;
+
+    let texture = mesh.materials.iter().filter_map(|m| m.texture.as_ref()).next();
+    if let Some(file) = texture.filter(|f| f.to_lowercase().ends_with(".png"))
+    {
+      let texture_path = self.output_root.join(format!("{}.png", stem));
+      fs::copy(obj_path.with_file_name(file), &texture_path)
+        .map_err(|e| anyhow::anyhow!("Could not copy texture {}: {}", file, e))?;
+    }
+
+    self.write_manifest(body, logger)?;
+
+    logger.emit(
+      LogLevel::Info,
+      &format!("[DONE] Baked {} ({} triangles)", body.name, mesh.vertices.len() / 3),
+    );
+
+    Ok(())
+  }
+
   fn save_bake_to_disk(
     &self,
     path: &str,
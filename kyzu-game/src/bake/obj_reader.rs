@@ -0,0 +1,483 @@
+// ──────────────────────────────────────────────────────────────
+//   Wavefront OBJ / MTL reader for Manmade body meshes
+// ──────────────────────────────────────────────────────────────
+
+use std::fs;
+use std::path::Path;
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::bake::geometry::BakedVertex;
+use crate::core::log::{LogLevel, Logger};
+
+/// Diffuse colour of one `newmtl` entry. Meshes refer to it by index,
+/// stored in each vertex's hex_id slot (Manmade meshes have no hex grid).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjMaterial
+{
+  pub name: String,
+  pub diffuse: [f32; 3],
+  /// `map_Kd` image, relative to the MTL file.
+  pub texture: Option<String>,
+}
+
+pub struct ObjMesh
+{
+  /// Non-indexed triangle list, scaled so the furthest vertex is at 1.0
+  /// (same convention as the sphere bakes: multiply by body radius).
+  pub vertices: Vec<BakedVertex>,
+  pub materials: Vec<ObjMaterial>,
+}
+
+/// One face corner: indices into the position / uv / normal lists.
+#[derive(Clone, Copy)]
+struct Corner
+{
+  pos: usize,
+  uv: Option<usize>,
+  normal: Option<usize>,
+}
+
+struct Face
+{
+  corners: Vec<Corner>,
+  material: u32,
+}
+
+/// Load an OBJ file plus any MTL libraries it references (resolved next
+/// to the OBJ). Quads and ngons are fan-triangulated; missing normals are
+/// generated by averaging the normals of the faces sharing each position.
+pub fn load(path: &Path, logger: &mut Logger) -> anyhow::Result<ObjMesh>
+{
+  let text = fs::read_to_string(path)
+    .map_err(|e| anyhow::anyhow!("Could not read OBJ {}: {}", path.display(), e))?;
+
+  let mut positions: Vec<Vec3> = Vec::new();
+  let mut uvs: Vec<Vec2> = Vec::new();
+  let mut normals: Vec<Vec3> = Vec::new();
+  let mut faces: Vec<Face> = Vec::new();
+  let mut materials: Vec<ObjMaterial> = Vec::new();
+  let mut current_material = 0u32;
+
+  for (line_no, raw) in text.lines().enumerate()
+  {
+    let line = raw.trim();
+    let mut parts = line.split_whitespace();
+    let keyword = match parts.next()
+    {
+      Some(k) => k,
+      None => continue,
+    };
+    let rest: Vec<&str> = parts.collect();
+    let at = || format!("{}:{}", path.display(), line_no + 1);
+
+    match keyword
+    {
+      "v" =>
+      {
+        positions.push(parse_vec3(&rest).ok_or_else(|| anyhow::anyhow!("Bad vertex at {}", at()))?)
+      }
+      "vt" => uvs.push(parse_vec2(&rest).ok_or_else(|| anyhow::anyhow!("Bad uv at {}", at()))?),
+      "vn" =>
+      {
+        normals.push(parse_vec3(&rest).ok_or_else(|| anyhow::anyhow!("Bad normal at {}", at()))?)
+      }
+      "f" =>
+      {
+        let mut corners = Vec::with_capacity(rest.len());
+        for token in &rest
+        {
+          let corner = parse_corner(token, positions.len(), uvs.len(), normals.len())
+            .ok_or_else(|| anyhow::anyhow!("Bad face corner '{}' at {}", token, at()))?;
+          corners.push(corner);
+        }
+        if corners.len() < 3
+        {
+          return Err(anyhow::anyhow!("Face with fewer than 3 corners at {}", at()));
+        }
+        faces.push(Face { corners, material: current_material });
+      }
+      "mtllib" =>
+      {
+        for name in &rest
+        {
+          let mtl_path = path.with_file_name(name);
+          match load_mtl(&mtl_path)
+          {
+            Ok(mut loaded) => materials.append(&mut loaded),
+            Err(e) =>
+            {
+              logger.emit(LogLevel::Warning, &format!("OBJ: skipping material library: {}", e))
+            }
+          }
+        }
+      }
+      "usemtl" =>
+      {
+        let name = rest.first().copied().unwrap_or("");
+        current_material = material_index(&mut materials, name);
+      }
+      // Groups, smoothing groups and object names don't affect the bake.
+      _ => (),
+    }
+  }
+
+  if faces.is_empty()
+  {
+    return Err(anyhow::anyhow!("OBJ {} has no faces", path.display()));
+  }
+
+  let generated = generate_normals(&positions, &faces);
+  let scale = 1.0 / bounding_radius(&positions).max(f32::EPSILON);
+  let vertices = triangulate(&positions, &uvs, &normals, &generated, &faces, scale);
+
+  logger.emit(
+    LogLevel::Info,
+    &format!(
+      "OBJ: {} ({} faces -> {} triangles, {} materials)",
+      path.display(),
+      faces.len(),
+      vertices.len() / 3,
+      materials.len()
+    ),
+  );
+
+  Ok(ObjMesh { vertices, materials })
+}
+
+/// Parse `newmtl` entries with their `Kd` colour and `map_Kd` image.
+/// Other MTL statements are ignored.
+fn load_mtl(path: &Path) -> anyhow::Result<Vec<ObjMaterial>>
+{
+  let text = fs::read_to_string(path)
+    .map_err(|e| anyhow::anyhow!("Could not read MTL {}: {}", path.display(), e))?;
+
+  let mut materials: Vec<ObjMaterial> = Vec::new();
+
+  for raw in text.lines()
+  {
+    let mut parts = raw.split_whitespace();
+    let keyword = parts.next();
+    let rest: Vec<&str> = parts.collect();
+
+    match keyword
+    {
+      Some("newmtl") =>
+      {
+        let name = rest.first().copied().unwrap_or("").to_string();
+        materials.push(ObjMaterial { name, diffuse: [0.8, 0.8, 0.8], texture: None });
+      }
+      Some("map_Kd") =>
+      {
+        // Options such as -s or -bm come first; the file name is last.
+        if let (Some(material), Some(file)) = (materials.last_mut(), rest.last())
+        {
+          material.texture = Some(file.to_string());
+        }
+      }
+      Some("Kd") =>
+      {
+        if let (Some(material), Some(colour)) = (materials.last_mut(), parse_vec3(&rest))
+        {
+          material.diffuse = colour.to_array();
+        }
+      }
+      _ => (),
+    }
+  }
+
+  Ok(materials)
+}
+
+/// Index of `name` in the material list, adding a grey default if the
+/// MTL file didn't define it.
+fn material_index(materials: &mut Vec<ObjMaterial>, name: &str) -> u32
+{
+  for (index, material) in materials.iter().enumerate()
+  {
+    if material.name == name
+    {
+      return index as u32;
+    }
+  }
+
+  materials.push(ObjMaterial { name: name.to_string(), diffuse: [0.8, 0.8, 0.8], texture: None });
+  (materials.len() - 1) as u32
+}
+
+fn parse_vec3(parts: &[&str]) -> Option<Vec3>
+{
+  if parts.len() < 3
+  {
+    return None;
+  }
+  Some(Vec3::new(parts[0].parse().ok()?, parts[1].parse().ok()?, parts[2].parse().ok()?))
+}
+
+fn parse_vec2(parts: &[&str]) -> Option<Vec2>
+{
+  if parts.len() < 2
+  {
+    return None;
+  }
+  Some(Vec2::new(parts[0].parse().ok()?, parts[1].parse().ok()?))
+}
+
+/// "v", "v/vt", "v//vn" or "v/vt/vn". OBJ indices are 1-based; negative
+/// indices count back from the most recent element.
+fn parse_corner(
+  token: &str,
+  pos_count: usize,
+  uv_count: usize,
+  normal_count: usize,
+) -> Option<Corner>
+{
+  let mut fields = token.split('/');
+
+  let pos = resolve_index(fields.next()?, pos_count)?;
+
+  let mut uv = None;
+  if let Some(field) = fields.next()
+  {
+    if !field.is_empty()
+    {
+      uv = Some(resolve_index(field, uv_count)?);
+    }
+  }
+
+  let mut normal = None;
+  if let Some(field) = fields.next()
+  {
+    if !field.is_empty()
+    {
+      normal = Some(resolve_index(field, normal_count)?);
+    }
+  }
+
+  Some(Corner { pos, uv, normal })
+}
+
+fn resolve_index(field: &str, count: usize) -> Option<usize>
+{
+  let raw: i64 = field.parse().ok()?;
+
+  let index = match raw
+  {
+    r if r > 0 => r - 1,
+    r if r < 0 => count as i64 + r,
+    _ => return None,
+  };
+
+  if index < 0 || index as usize >= count
+  {
+    return None;
+  }
+  Some(index as usize)
+}
+
+/// Area-weighted vertex normals, one per position, for corners without `vn`.
+fn generate_normals(positions: &[Vec3], faces: &[Face]) -> Vec<Vec3>
+{
+  let mut accumulated = vec![Vec3::ZERO; positions.len()];
+
+  for face in faces
+  {
+    // Cross product length is twice the triangle area: larger faces count
+    // more. Summed over the fan first so every corner of a quad or ngon
+    // gets the whole face, whichever triangles it ends up in.
+    let first = positions[face.corners[0].pos];
+    let mut face_normal = Vec3::ZERO;
+    for i in 1..face.corners.len() - 1
+    {
+      let b = positions[face.corners[i].pos];
+      let c = positions[face.corners[i + 1].pos];
+      face_normal += (b - first).cross(c - first);
+    }
+
+    for corner in &face.corners
+    {
+      accumulated[corner.pos] += face_normal;
+    }
+  }
+
+  accumulated.into_iter().map(|n| n.normalize_or(Vec3::Y)).collect()
+}
+
+fn bounding_radius(positions: &[Vec3]) -> f32
+{
+  let mut radius = 0.0f32;
+  for p in positions
+  {
+    radius = radius.max(p.length());
+  }
+  radius
+}
+
+/// Fan-triangulate every face into a flat vertex list with barycentrics
+/// for the wireframe shader, matching the unwelded sphere bakes.
+fn triangulate(
+  positions: &[Vec3],
+  uvs: &[Vec2],
+  normals: &[Vec3],
+  generated: &[Vec3],
+  faces: &[Face],
+  scale: f32,
+) -> Vec<BakedVertex>
+{
+  let mut vertices = Vec::new();
+  let barycentrics = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+  for face in faces
+  {
+    for i in 1..face.corners.len() - 1
+    {
+      let triangle = [face.corners[0], face.corners[i], face.corners[i + 1]];
+
+      for (corner, barycentric) in triangle.iter().zip(barycentrics)
+      {
+        let mut normal = generated[corner.pos];
+        if let Some(n) = corner.normal
+        {
+          normal = normals[n].normalize_or(normal);
+        }
+
+        let mut uv = [0.0, 0.0];
+        if let Some(t) = corner.uv
+        {
+          // OBJ v runs bottom-up; textures here are sampled top-down.
+          uv = [uvs[t].x, 1.0 - uvs[t].y];
+        }
+
+        vertices.push(BakedVertex {
+          pos: (positions[corner.pos] * scale).to_array(),
+          normal: normal.to_array(),
+          uv,
+          height: 0.0,
+          hex_id: face.material,
+          barycentric,
+        });
+      }
+    }
+  }
+
+  vertices
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  /// Write `obj` (and `mtl`, as shapes.mtl) to a scratch folder and load it.
+  fn load_text(name: &str, obj: &str, mtl: Option<&str>) -> anyhow::Result<ObjMesh>
+  {
+    let dir = std::env::temp_dir().join(format!("kyzu_obj_{}", name));
+    fs::create_dir_all(&dir)?;
+    if let Some(mtl) = mtl
+    {
+      fs::write(dir.join("shapes.mtl"), mtl)?;
+    }
+    let path = dir.join("mesh.obj");
+    fs::write(&path, obj)?;
+
+    let mut logger = Logger::new(dir.join("test.log").to_str().unwrap());
+    load(&path, &mut logger)
+  }
+
+  fn close(a: [f32; 3], b: Vec3) -> bool
+  {
+    Vec3::from(a).distance(b) < 1e-5
+  }
+
+  #[test]
+  fn indices_are_one_based_or_relative()
+  {
+    assert_eq!(resolve_index("1", 3), Some(0));
+    assert_eq!(resolve_index("3", 3), Some(2));
+    assert_eq!(resolve_index("4", 3), None);
+    assert_eq!(resolve_index("-1", 3), Some(2));
+    assert_eq!(resolve_index("-3", 3), Some(0));
+    assert_eq!(resolve_index("-4", 3), None);
+    assert_eq!(resolve_index("0", 3), None);
+    assert_eq!(resolve_index("x", 3), None);
+  }
+
+  #[test]
+  fn ngons_are_fanned_from_the_first_corner()
+  {
+    let obj = "v 1 0 0\nv 0 1 0\nv -1 0 0\nv 0 -1 0\nv 0.5 -0.5 0\nf 1 2 3 4 5\n";
+    let mesh = load_text("fan", obj, None).unwrap();
+
+    let corners: Vec<[f32; 3]> = mesh.vertices.iter().map(|v| v.pos).collect();
+    let expected = [[0, 1, 2], [0, 2, 3], [0, 3, 4]];
+    let points = [Vec3::X, Vec3::Y, Vec3::NEG_X, Vec3::NEG_Y, Vec3::new(0.5, -0.5, 0.0)];
+    assert_eq!(corners.len(), 9);
+    for (triangle, indices) in corners.chunks(3).zip(expected)
+    {
+      for (corner, index) in triangle.iter().zip(indices)
+      {
+        assert!(close(*corner, points[index]), "{:?} != {:?}", corner, points[index]);
+      }
+    }
+    assert_eq!(mesh.vertices[1].barycentric, [0.0, 1.0, 0.0]);
+  }
+
+  #[test]
+  fn cube_normals_are_generated_outward()
+  {
+    let obj = "v -1 -1 -1\nv 1 -1 -1\nv 1 1 -1\nv -1 1 -1\n\
+               v -1 -1 1\nv 1 -1 1\nv 1 1 1\nv -1 1 1\n\
+               f 1 4 3 2\nf 5 6 7 8\nf 1 2 6 5\nf 4 8 7 3\nf 1 5 8 4\nf 2 3 7 6\n";
+    let mesh = load_text("cube", obj, None).unwrap();
+
+    assert_eq!(mesh.vertices.len(), 36);
+    for vertex in &mesh.vertices
+    {
+      // Each corner is shared by three equal faces: the average points
+      // straight out along the corner's diagonal.
+      assert!(close(vertex.normal, Vec3::from(vertex.pos).normalize()));
+    }
+  }
+
+  #[test]
+  fn corners_may_skip_the_uv()
+  {
+    let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 2\nf 1//1 2//1 3//1\n";
+    let mesh = load_text("no_uv", obj, None).unwrap();
+
+    for vertex in &mesh.vertices
+    {
+      assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+      assert_eq!(vertex.uv, [0.0, 0.0]);
+    }
+  }
+
+  #[test]
+  fn materials_index_the_mtl_entries()
+  {
+    let mtl =
+      "newmtl hull\nKd 0.1 0.2 0.3\nnewmtl panel\nKd 0.9 0.8 0.7\nmap_Kd -s 1 1 1 panel.png\n";
+    let obj = "mtllib shapes.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\n\
+               usemtl panel\nf 1 2 3\nusemtl hull\nf 1 2 3\n";
+    let mesh = load_text("materials", obj, Some(mtl)).unwrap();
+
+    assert_eq!(mesh.materials.len(), 2);
+    assert_eq!(mesh.materials[0].diffuse, [0.1, 0.2, 0.3]);
+    assert_eq!(mesh.materials[1].texture.as_deref(), Some("panel.png"));
+    assert_eq!(mesh.vertices[0].hex_id, 1);
+    assert_eq!(mesh.vertices[3].hex_id, 0);
+  }
+
+  #[test]
+  fn missing_mtllib_falls_back_to_grey()
+  {
+    let obj = "mtllib nowhere.mtl\nusemtl steel\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    let mesh = load_text("no_mtl", obj, None).unwrap();
+
+    assert_eq!(mesh.materials.len(), 1);
+    assert_eq!(mesh.materials[0].name, "steel");
+    assert_eq!(mesh.materials[0].diffuse, [0.8, 0.8, 0.8]);
+    assert_eq!(mesh.vertices[0].hex_id, 0);
+  }
+}
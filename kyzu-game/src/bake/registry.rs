@@ -30,6 +30,9 @@ pub struct BodyConfig
   pub use_real_data: bool,
   #[serde(default)]
   pub elevation_map_path: Option<String>,
+  /// Wavefront OBJ for Manmade bodies, relative to the body's asset folder.
+  #[serde(default)]
+  pub mesh_path: Option<String>,
   #[serde(default)]
   pub land_cover_map_path: Option<String>,
   #[serde(default)]
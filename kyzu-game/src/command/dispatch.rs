@@ -0,0 +1,558 @@
+use winit::event_loop::ActiveEventLoop;
+
+use crate::app::App;
+use crate::bake::BakeManager;
+use crate::command::history::UndoEntry;
+use crate::command::Command;
+use crate::core::config;
+use crate::core::event::{BodySpawned, CameraModeChanged};
+use crate::core::log::{LogLevel, Logger};
+use crate::render::modules::body_renderer::BodyRenderer;
+use crate::render::primitives::Primitive;
+use crate::render::shared::CameraMode;
+use crate::render::still::{StillRender, StillSettings};
+use crate::world::body::{BodyKind, BodyManifest};
+use crate::world::registry::{BodyFlag, RenderFlags};
+
+/// Orbit distance when focusing a body, in body radii.
+const FOCUS_RADII: f64 = 3.0;
+/// Inserted bodies go this fraction of the way to the camera's focus...
+const INSERT_DISTANCE: f64 = 0.5;
+/// ...with a radius of this fraction of their distance from the eye.
+const INSERT_SIZE: f64 = 0.1;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Command dispatch
+//
+//  The one place Commands touch App state. Undoable commands return their
+//  inverse from apply_command(); execute_command() records it in history.
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl App
+{
+  pub fn execute_command(&mut self, event_loop: &ActiveEventLoop, command: Command)
+  {
+    self.recorder.record(&command);
+    self.dirty = true;
+
+    match command
+    {
+      Command::Undo => self.undo(event_loop),
+      Command::Redo => self.redo(event_loop),
+      Command::ToggleMacroRecording => self.toggle_macro_recording(),
+      Command::PlayMacro => self.play_macro(event_loop),
+      other =>
+      {
+        let recorded = !other.is_camera() || self.config.app.undo_camera;
+        if let Some(inverse) = self.apply_command(event_loop, &other).filter(|_| recorded)
+        {
+          self.history.push(UndoEntry { command: other, inverse });
+        }
+      }
+    }
+  }
+
+  /// Apply a plain command. Returns the inverse command if it can be undone.
+  fn apply_command(&mut self, event_loop: &ActiveEventLoop, command: &Command) -> Option<Command>
+  {
+    match command
+    {
+      Command::Exit =>
+      {
+        self.logger.emit(LogLevel::Info, "Exit requested.");
+        self.renderer = None;
+        event_loop.exit();
+        None
+      }
+      Command::ToggleCameraMode =>
+      {
+        let current = self.renderer.as_ref()?.shared.mode;
+        let next = match current
+        {
+          CameraMode::Free => CameraMode::Orbital,
+          CameraMode::Orbital => CameraMode::Free,
+        };
+        self.set_camera_mode(next)
+      }
+      Command::SetCameraMode(mode) => self.set_camera_mode(*mode),
+      Command::LevelHorizon =>
+      {
+        let renderer = self.renderer.as_mut()?;
+        renderer.camera_system.free_controller.leveling = true;
+        renderer.camera_system.orbital_controller.leveling = true;
+        None
+      }
+      Command::SetSelectTool(tool) =>
+      {
+        let previous = self.select_tool;
+        self.select_tool = *tool;
+        self.logger.emit(LogLevel::Info, &format!("Select tool: {:?}", tool));
+        Some(Command::SetSelectTool(previous))
+      }
+      Command::ToggleProbe =>
+      {
+        self.probe.active = !self.probe.active;
+        None
+      }
+      Command::ClearProbe =>
+      {
+        self.probe.points.clear();
+        None
+      }
+      Command::ExportProbe(path) =>
+      {
+        match self.probe.export(std::path::Path::new(path))
+        {
+          Ok(()) =>
+          {
+            let count = self.probe.points.len();
+            self.notifications.info(&format!("Exported {} points to {}", count, path));
+          }
+          Err(e) => self.notify_error(&format!("Probe export failed: {}", e)),
+        }
+        None
+      }
+      Command::SetBodyFlag { body, flag, on } => self.set_body_flag(body, *flag, *on),
+      Command::InsertPrimitive(primitive) =>
+      {
+        self.insert_primitive(*primitive);
+        None
+      }
+      Command::SetView(preset) =>
+      {
+        let renderer = self.renderer.as_mut()?;
+        let previous = renderer.shared.mode;
+        renderer.camera_system.set_view(&mut renderer.shared, *preset);
+        if previous != CameraMode::Orbital
+        {
+          self.events.camera_mode_changed.publish(CameraModeChanged { mode: CameraMode::Orbital });
+        }
+        None
+      }
+      Command::ResetView =>
+      {
+        let renderer = self.renderer.as_mut()?;
+        let previous = renderer.shared.mode;
+        renderer.camera_system.reset_view(&mut renderer.shared);
+        if previous != CameraMode::Orbital
+        {
+          self.events.camera_mode_changed.publish(CameraModeChanged { mode: CameraMode::Orbital });
+        }
+        None
+      }
+      Command::ViewBack =>
+      {
+        self.step_view(false);
+        None
+      }
+      Command::ViewForward =>
+      {
+        self.step_view(true);
+        None
+      }
+      Command::StartBake =>
+      {
+        self.start_background_bake();
+        None
+      }
+      Command::ToggleLogPanel =>
+      {
+        if let Some(ui) = &mut self.ui
+        {
+          ui.show_log = !ui.show_log;
+        }
+        None
+      }
+      Command::OpenSearch =>
+      {
+        if let Some(ui) = &mut self.ui
+        {
+          ui.search.toggle();
+        }
+        None
+      }
+      Command::FocusBody(name) =>
+      {
+        self.focus_body(name);
+        None
+      }
+      Command::SetDisplayMode(mode) =>
+      {
+        let renderer = self.renderer.as_mut()?;
+        let previous = renderer.shared.display_mode;
+        renderer.shared.display_mode = *mode;
+        Some(Command::SetDisplayMode(previous))
+      }
+      Command::SetColorMap(map) =>
+      {
+        let renderer = self.renderer.as_mut()?;
+        let previous = renderer.shared.field.map;
+        renderer.shared.field.map = *map;
+        Some(Command::SetColorMap(previous))
+      }
+      Command::SetFieldRange { min, max } =>
+      {
+        // Dragged continuously from the legend, so not recorded for undo.
+        let renderer = self.renderer.as_mut()?;
+        renderer.shared.field.min = min.min(*max);
+        renderer.shared.field.max = max.max(*min);
+        None
+      }
+      Command::ToggleAxes =>
+      {
+        let renderer = self.renderer.as_mut()?;
+        renderer.shared.show_axes = !renderer.shared.show_axes;
+        Some(Command::ToggleAxes)
+      }
+      Command::SetPresentMode(mode) =>
+      {
+        let previous = self.config.app.present_mode();
+        self.config.app.present_mode = Some(*mode);
+
+        let mut renderer = self.renderer.take()?;
+        self.apply_present_mode(&mut renderer);
+        self.renderer = Some(renderer);
+        Some(Command::SetPresentMode(previous))
+      }
+      Command::SetFpsCap(fps) =>
+      {
+        // Dragged continuously from the settings slider, so not recorded for undo.
+        self.config.app.fps_cap = *fps;
+        None
+      }
+      Command::SetFrameLatency(frames) =>
+      {
+        let previous = self.config.app.max_frame_latency;
+        self.config.app.max_frame_latency = (*frames).clamp(1, 3);
+
+        let mut renderer = self.renderer.take()?;
+        self.apply_present_mode(&mut renderer);
+        self.renderer = Some(renderer);
+        Some(Command::SetFrameLatency(previous))
+      }
+      Command::SetFxaa(on) =>
+      {
+        let previous = self.config.app.fxaa.unwrap_or(false);
+        self.config.app.fxaa = Some(*on);
+        if let Some(renderer) = &mut self.renderer
+        {
+          // Kept as the preference, but a GPU that tripped the watchdog
+          // stays on reduced quality.
+          renderer.shared.fxaa = *on && !renderer.shared.degraded;
+          if *on && renderer.shared.degraded
+          {
+            self.logger.emit(LogLevel::Warning, "FXAA stays off while quality is reduced");
+          }
+        }
+        Some(Command::SetFxaa(previous))
+      }
+      Command::SetUndoCamera(on) =>
+      {
+        // A preference about history, so not itself recorded in it.
+        self.config.app.undo_camera = *on;
+        None
+      }
+      Command::RenderStill { width, height, samples } =>
+      {
+        let settings = StillSettings { width: *width, height: *height, samples: *samples };
+        self.start_still(settings);
+        None
+      }
+      Command::ToggleSettings =>
+      {
+        if let Some(ui) = &mut self.ui
+        {
+          ui.show_settings = !ui.show_settings;
+        }
+        None
+      }
+      Command::ToggleUvPanel =>
+      {
+        if let Some(ui) = &mut self.ui
+        {
+          ui.show_uv = !ui.show_uv;
+        }
+        None
+      }
+      Command::ToggleTelemetry =>
+      {
+        if let Some(ui) = &mut self.ui
+        {
+          ui.show_telemetry = !ui.show_telemetry;
+        }
+        None
+      }
+      _ => None,
+    }
+  }
+
+  /// Returns the previous mode as an inverse command.
+  fn set_camera_mode(&mut self, mode: CameraMode) -> Option<Command>
+  {
+    let renderer = self.renderer.as_mut()?;
+    let previous = renderer.shared.mode;
+
+    renderer.shared.mode = mode;
+    self.events.camera_mode_changed.publish(CameraModeChanged { mode });
+
+    Some(Command::SetCameraMode(previous))
+  }
+
+  /// Glide to the previous (or next) view in the camera history.
+  fn step_view(&mut self, forward: bool)
+  {
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let previous = renderer.shared.mode;
+    let moved = match forward
+    {
+      true => renderer.camera_system.go_forward(&mut renderer.shared),
+      false => renderer.camera_system.go_back(&mut renderer.shared),
+    };
+
+    if !moved
+    {
+      self.logger.emit(LogLevel::Info, "No further views in history");
+      return;
+    }
+
+    let mode = renderer.shared.mode;
+    if mode != previous
+    {
+      self.events.camera_mode_changed.publish(CameraModeChanged { mode });
+    }
+  }
+
+  /// Returns the flag's previous value as an inverse command.
+  fn set_body_flag(&mut self, name: &str, flag: BodyFlag, on: bool) -> Option<Command>
+  {
+    let renderer = self.renderer.as_mut()?;
+    let registry = &mut renderer.shared.body_registry;
+    let index = match registry.find(name)
+    {
+      Some(i) => i,
+      None =>
+      {
+        self.logger.emit(LogLevel::Warning, &format!("No body named '{}'", name));
+        return None;
+      }
+    };
+
+    let body = &mut registry.bodies[index];
+    let previous = body.flags.get(flag);
+    body.flags.set(flag, on);
+    let (saved_name, flags) = (body.manifest.name.clone(), body.flags);
+
+    self.logger.emit(LogLevel::Info, &format!("{} {}: {}", name, flag.name(), on));
+
+    let save = &mut self.config.save;
+    save.body_flags.remove(&saved_name);
+    if flags != RenderFlags::default()
+    {
+      save.body_flags.insert(saved_name, flags);
+    }
+    if let Err(e) = config::write_save(&self.config.save_dir, &self.config.save)
+    {
+      self.notify_error(&format!("Body flags not saved: {}", e));
+    }
+    Some(Command::SetBodyFlag { body: name.to_string(), flag, on: previous })
+  }
+
+  /// Add a generated body halfway to what the camera is looking at, sized
+  /// to a tenth of that distance. Bodies are never removed, so this has
+  /// no inverse.
+  fn insert_primitive(&mut self, primitive: Primitive)
+  {
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let shared = &renderer.shared;
+    let distance = shared.focus_distance.max(1.0) * INSERT_DISTANCE;
+    let centre = glam::Vec2::new(shared.screen_width as f32, shared.screen_height as f32) * 0.5;
+    let position = shared.ray_through(centre).at(distance);
+
+    // "Torus", then "Torus 2", "Torus 3", ... so names stay unique.
+    let registry = &shared.body_registry;
+    let mut name = primitive.label().to_string();
+    let mut number = 1;
+    while registry.find(&name).is_some()
+    {
+      number += 1;
+      name = format!("{} {}", primitive.label(), number);
+    }
+
+    let manifest = BodyManifest {
+      name: name.clone(),
+      kind: BodyKind::Manmade,
+      radius_m: distance * INSERT_SIZE,
+      lod_max: 0,
+      position_at_epoch: position,
+      orbital_elements: None,
+      axial_tilt_rad: 0.0,
+      rotation_period_s: 0.0,
+    };
+    let registry = &mut renderer.shared.body_registry;
+    let index = registry.spawn(manifest, false);
+    registry.bodies[index].primitive = Some(primitive);
+    renderer
+      .with_module(|bodies: &mut BodyRenderer, device, shared| bodies.add_bodies(device, shared));
+
+    self.logger.emit(LogLevel::Info, &format!("Inserted {}", name));
+    self.events.body_spawned.publish(BodySpawned { index, name });
+  }
+
+  /// Switch to the orbital camera around the named body, backed off to a
+  /// few radii so the whole body is in view.
+  fn focus_body(&mut self, name: &str)
+  {
+    let renderer = match &mut self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+
+    let mut found = None;
+    for body in &renderer.shared.body_registry.bodies
+    {
+      if body.manifest.name.eq_ignore_ascii_case(name)
+      {
+        found = Some((body.world_pos, body.manifest.radius_m));
+        break;
+      }
+    }
+
+    let (position, radius) = match found
+    {
+      Some(f) => f,
+      None =>
+      {
+        self.logger.emit(LogLevel::Warning, &format!("No body named '{}'", name));
+        return;
+      }
+    };
+
+    let previous = renderer.shared.mode;
+    renderer.camera_system.frame_target(&mut renderer.shared, position, radius * FOCUS_RADII);
+
+    if previous != CameraMode::Orbital
+    {
+      self.events.camera_mode_changed.publish(CameraModeChanged { mode: CameraMode::Orbital });
+    }
+    self.logger.emit(LogLevel::Info, &format!("Focused {}", name));
+  }
+
+  /// Bake on a worker thread with its own file logger; progress and cancel
+  /// go through the task list. New output is picked up on next launch.
+  fn start_background_bake(&mut self)
+  {
+    for task in &self.tasks.tasks
+    {
+      if task.name() == "Bake"
+      {
+        self.logger.emit(LogLevel::Warning, "A bake is already running");
+        return;
+      }
+    }
+
+    let task = self.tasks.start("Bake", Some(Command::StartBake));
+    let manager = BakeManager::new(&self.config);
+    let log_path = self.config.app.log_filename.clone();
+
+    std::thread::spawn(move || {
+      let mut logger = Logger::new(&log_path);
+      manager.start_bake(&mut logger, &task);
+    });
+
+    self.logger.emit(LogLevel::Info, "Bake started in background (restart to load results)");
+  }
+
+  /// Begin accumulating a still image; App::advance_still renders the
+  /// samples over the following frames.
+  fn start_still(&mut self, mut settings: StillSettings)
+  {
+    if self.still.is_some()
+    {
+      self.logger.emit(LogLevel::Warning, "A still render is already running");
+      return;
+    }
+
+    let renderer = match &self.renderer
+    {
+      Some(r) => r,
+      None => return,
+    };
+    let max_size = renderer.device.limits().max_texture_dimension_2d;
+    settings.width = settings.width.min(max_size);
+    settings.height = settings.height.min(max_size);
+
+    let output = self.config.save_dir.join("still.png");
+    let retry = Command::RenderStill {
+      width: settings.width,
+      height: settings.height,
+      samples: settings.samples,
+    };
+    let task = self.tasks.start("Still render", Some(retry));
+    self.still = Some(StillRender::new(settings, output, task));
+    self.logger.emit(
+      LogLevel::Info,
+      &format!(
+        "Still render started: {}x{}, {} samples",
+        settings.width, settings.height, settings.samples
+      ),
+    );
+  }
+
+  fn undo(&mut self, event_loop: &ActiveEventLoop)
+  {
+    let inverse = match self.history.pop_undo()
+    {
+      Some(entry) => entry.inverse.clone(),
+      None => return,
+    };
+    self.logger.emit(LogLevel::Info, &format!("Undo: {}", inverse.to_line()));
+    self.apply_command(event_loop, &inverse);
+  }
+
+  fn redo(&mut self, event_loop: &ActiveEventLoop)
+  {
+    let command = match self.history.pop_redo()
+    {
+      Some(entry) => entry.command.clone(),
+      None => return,
+    };
+    self.logger.emit(LogLevel::Info, &format!("Redo: {}", command.to_line()));
+    self.apply_command(event_loop, &command);
+  }
+
+  fn toggle_macro_recording(&mut self)
+  {
+    if !self.recorder.recording
+    {
+      self.recorder.start();
+      self.logger.emit(LogLevel::Info, "Macro recording started");
+      return;
+    }
+
+    let count = self.recorder.stop();
+    let text = self.recorder.to_text();
+    self.logger.emit(LogLevel::Info, &format!("Macro recorded ({} commands):\n{}", count, text));
+  }
+
+  fn play_macro(&mut self, event_loop: &ActiveEventLoop)
+  {
+    let commands = self.recorder.last_macro().to_vec();
+    self.logger.emit(LogLevel::Info, &format!("Playing macro ({} commands)", commands.len()));
+
+    for command in commands
+    {
+      self.execute_command(event_loop, command);
+    }
+  }
+}
@@ -0,0 +1,54 @@
+use crate::command::Command;
+
+/// Oldest entries are dropped past this depth.
+const MAX_HISTORY: usize = 100;
+
+/// A command that was applied, plus the command that reverses it.
+pub struct UndoEntry
+{
+  pub command: Command,
+  pub inverse: Command,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  CommandHistory
+//
+//  Classic undo/redo stacks. Only commands that can produce an inverse are
+//  pushed; applying any new command clears the redo stack.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+pub struct CommandHistory
+{
+  undo_stack: Vec<UndoEntry>,
+  redo_stack: Vec<UndoEntry>,
+}
+
+impl CommandHistory
+{
+  pub fn push(&mut self, entry: UndoEntry)
+  {
+    if self.undo_stack.len() >= MAX_HISTORY
+    {
+      self.undo_stack.remove(0);
+    }
+    self.undo_stack.push(entry);
+    self.redo_stack.clear();
+  }
+
+  /// Take the most recent entry for undoing. The entry moves to the redo stack.
+  pub fn pop_undo(&mut self) -> Option<&UndoEntry>
+  {
+    let entry = self.undo_stack.pop()?;
+    self.redo_stack.push(entry);
+    self.redo_stack.last()
+  }
+
+  /// Take the most recently undone entry for redoing. Moves back to undo.
+  pub fn pop_redo(&mut self) -> Option<&UndoEntry>
+  {
+    let entry = self.redo_stack.pop()?;
+    self.undo_stack.push(entry);
+    self.undo_stack.last()
+  }
+}
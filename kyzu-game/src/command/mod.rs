@@ -0,0 +1,543 @@
+pub mod dispatch;
+pub mod history;
+pub mod recorder;
+pub mod script;
+
+use crate::core::config::PresentMode;
+use crate::core::palette::ColorMap;
+use crate::render::camera::ViewPreset;
+use crate::render::primitives::Primitive;
+use crate::render::shared::{CameraMode, DisplayMode};
+use crate::world::registry::BodyFlag;
+use crate::world::selection::SelectTool;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Command
+//
+//  Every user action is a named, parameterised Command. Key bindings, macro
+//  playback and scripts all produce Commands; App::execute_command is the
+//  single place they are applied.
+//
+//  Text form is "<name> [args]", e.g. "camera.set_mode orbital". parse() and
+//  to_line() round-trip so macros can be saved and replayed as text. Body
+//  names and file paths take the remaining words, so they may hold spaces
+//  ("camera.focus halley's comet").
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command
+{
+  Exit,
+  ToggleCameraMode,
+  SetCameraMode(CameraMode),
+  LevelHorizon,
+  FocusBody(String),
+  SetView(ViewPreset),
+  ViewBack,
+  ViewForward,
+  ResetView,
+  SetSelectTool(SelectTool),
+  ToggleProbe,
+  ClearProbe,
+  ExportProbe(String),
+  SetBodyFlag
+  {
+    body: String,
+    flag: BodyFlag,
+    on: bool,
+  },
+  InsertPrimitive(Primitive),
+  SetDisplayMode(DisplayMode),
+  SetColorMap(ColorMap),
+  SetFieldRange
+  {
+    min: f32,
+    max: f32,
+  },
+  ToggleAxes,
+  SetPresentMode(PresentMode),
+  SetFpsCap(u32),
+  SetFrameLatency(u32),
+  SetFxaa(bool),
+  SetUndoCamera(bool),
+  RenderStill
+  {
+    width: u32,
+    height: u32,
+    samples: u32,
+  },
+  OpenSearch,
+  Undo,
+  Redo,
+  ToggleMacroRecording,
+  PlayMacro,
+  StartBake,
+  ToggleLogPanel,
+  ToggleTelemetry,
+  ToggleUvPanel,
+  ToggleSettings,
+}
+
+pub struct CommandInfo
+{
+  pub name: &'static str,
+  pub args: &'static str,
+  pub description: &'static str,
+}
+
+/// Every command the registry knows about, for listing and lookup.
+pub const COMMAND_TABLE: &[CommandInfo] = &[
+  CommandInfo { name: "app.exit", args: "", description: "Quit Kyzu" },
+  CommandInfo {
+    name: "camera.toggle_mode",
+    args: "",
+    description: "Switch between free and orbital camera",
+  },
+  CommandInfo {
+    name: "camera.set_mode",
+    args: "free|orbital",
+    description: "Select a camera mode",
+  },
+  CommandInfo {
+    name: "camera.level_horizon",
+    args: "",
+    description: "Ease the camera's roll back to level",
+  },
+  CommandInfo {
+    name: "camera.focus",
+    args: "<body>",
+    description: "Orbit and frame a body by name",
+  },
+  CommandInfo {
+    name: "camera.view",
+    args: "top|bottom|front|back|left|right|iso",
+    description: "Look along an axis or from the isometric corner",
+  },
+  CommandInfo { name: "camera.back", args: "", description: "Return to the previous view" },
+  CommandInfo { name: "camera.forward", args: "", description: "Go forward to the next view" },
+  CommandInfo {
+    name: "camera.reset",
+    args: "",
+    description: "Return the orbit camera to its starting view",
+  },
+  CommandInfo {
+    name: "select.tool",
+    args: "box|lasso|paint",
+    description: "Choose what a left drag selects with",
+  },
+  CommandInfo {
+    name: "probe.toggle",
+    args: "",
+    description: "Record clicked surface points instead of picking",
+  },
+  CommandInfo { name: "probe.clear", args: "", description: "Forget recorded probe points" },
+  CommandInfo {
+    name: "probe.export",
+    args: "<file>",
+    description: "Write recorded probe points to a CSV file",
+  },
+  CommandInfo {
+    name: "body.flag",
+    args: "<body> on_top|no_pick|no_shadow|no_export on|off",
+    description: "Draw a body over everything, or hide it from picking",
+  },
+  CommandInfo {
+    name: "body.insert",
+    args: "ico_sphere|uv_sphere|cylinder|cone|torus|plane|box",
+    description: "Add a generated shape in front of the camera",
+  },
+  CommandInfo {
+    name: "render.display",
+    args: "shaded|checker|field",
+    description: "Colour bodies normally, with a UV checker or by elevation",
+  },
+  CommandInfo {
+    name: "render.colormap",
+    args: "heat|viridis|jet|coolwarm",
+    description: "Colour map for the elevation field",
+  },
+  CommandInfo {
+    name: "render.field_range",
+    args: "<min> <max>",
+    description: "Clamp the elevation field's colour map to a range in metres",
+  },
+  CommandInfo {
+    name: "render.toggle_axes",
+    args: "",
+    description: "Show or hide the axes at the orbit pivot",
+  },
+  CommandInfo {
+    name: "render.present",
+    args: "fifo|mailbox|immediate",
+    description: "Choose vsync (fifo), mailbox or immediate presentation",
+  },
+  CommandInfo {
+    name: "render.fps_cap",
+    args: "<fps>",
+    description: "Limit the frame rate; 0 removes the limit",
+  },
+  CommandInfo {
+    name: "render.frame_latency",
+    args: "1|2|3",
+    description: "Frames the GPU may queue ahead; 1 for the lowest latency",
+  },
+  CommandInfo {
+    name: "render.fxaa",
+    args: "on|off",
+    description: "Antialias the scene with a fullscreen FXAA pass",
+  },
+  CommandInfo {
+    name: "render.still",
+    args: "<width> <height> <samples>",
+    description: "Render an antialiased still image to the save folder",
+  },
+  CommandInfo { name: "edit.undo", args: "", description: "Undo the last command" },
+  CommandInfo {
+    name: "edit.undo_camera",
+    args: "on|off",
+    description: "Choose whether camera mode switches can be undone",
+  },
+  CommandInfo { name: "edit.redo", args: "", description: "Redo the last undone command" },
+  CommandInfo {
+    name: "macro.toggle_record",
+    args: "",
+    description: "Start or stop recording a macro",
+  },
+  CommandInfo { name: "macro.play", args: "", description: "Replay the last recorded macro" },
+  CommandInfo {
+    name: "bake.start",
+    args: "",
+    description: "Bake the selected world in the background",
+  },
+  CommandInfo { name: "ui.toggle_log", args: "", description: "Show or hide the log window" },
+  CommandInfo {
+    name: "ui.toggle_telemetry",
+    args: "",
+    description: "Show or hide frame and camera telemetry",
+  },
+  CommandInfo { name: "ui.toggle_uv", args: "", description: "Show or hide the UV layout panel" },
+  CommandInfo {
+    name: "ui.toggle_settings",
+    args: "",
+    description: "Show or hide the display settings",
+  },
+  CommandInfo { name: "ui.search", args: "", description: "Open the go-to-body search" },
+];
+
+impl Command
+{
+  pub fn name(&self) -> &'static str
+  {
+    match self
+    {
+      Command::Exit => "app.exit",
+      Command::ToggleCameraMode => "camera.toggle_mode",
+      Command::SetCameraMode(_) => "camera.set_mode",
+      Command::LevelHorizon => "camera.level_horizon",
+      Command::FocusBody(_) => "camera.focus",
+      Command::SetView(_) => "camera.view",
+      Command::ViewBack => "camera.back",
+      Command::ViewForward => "camera.forward",
+      Command::ResetView => "camera.reset",
+      Command::SetSelectTool(_) => "select.tool",
+      Command::ToggleProbe => "probe.toggle",
+      Command::ClearProbe => "probe.clear",
+      Command::ExportProbe(_) => "probe.export",
+      Command::SetBodyFlag { .. } => "body.flag",
+      Command::InsertPrimitive(_) => "body.insert",
+      Command::SetDisplayMode(_) => "render.display",
+      Command::SetColorMap(_) => "render.colormap",
+      Command::SetFieldRange { .. } => "render.field_range",
+      Command::ToggleAxes => "render.toggle_axes",
+      Command::SetPresentMode(_) => "render.present",
+      Command::SetFpsCap(_) => "render.fps_cap",
+      Command::SetFrameLatency(_) => "render.frame_latency",
+      Command::SetFxaa(_) => "render.fxaa",
+      Command::SetUndoCamera(_) => "edit.undo_camera",
+      Command::RenderStill { .. } => "render.still",
+      Command::Undo => "edit.undo",
+      Command::Redo => "edit.redo",
+      Command::ToggleMacroRecording => "macro.toggle_record",
+      Command::PlayMacro => "macro.play",
+      Command::StartBake => "bake.start",
+      Command::ToggleLogPanel => "ui.toggle_log",
+      Command::ToggleTelemetry => "ui.toggle_telemetry",
+      Command::ToggleUvPanel => "ui.toggle_uv",
+      Command::ToggleSettings => "ui.toggle_settings",
+      Command::OpenSearch => "ui.search",
+    }
+  }
+
+  /// Text form, suitable for macro files and the log.
+  pub fn to_line(&self) -> String
+  {
+    match self
+    {
+      Command::SetCameraMode(mode) => format!("{} {}", self.name(), camera_mode_arg(*mode)),
+      Command::FocusBody(body) => format!("{} {}", self.name(), body),
+      Command::ExportProbe(path) => format!("{} {}", self.name(), path),
+      Command::SetView(preset) => format!("{} {}", self.name(), preset.name()),
+      Command::InsertPrimitive(primitive) => format!("{} {}", self.name(), primitive.name()),
+      Command::SetDisplayMode(mode) => format!("{} {}", self.name(), mode.name()),
+      Command::SetColorMap(map) => format!("{} {}", self.name(), map.name()),
+      Command::SetFieldRange { min, max } => format!("{} {} {}", self.name(), min, max),
+      Command::SetPresentMode(mode) => format!("{} {}", self.name(), mode.name()),
+      Command::SetFpsCap(fps) => format!("{} {}", self.name(), fps),
+      Command::SetFrameLatency(frames) => format!("{} {}", self.name(), frames),
+      Command::SetFxaa(on) => format!("{} {}", self.name(), on_off_arg(*on)),
+      Command::SetUndoCamera(on) => format!("{} {}", self.name(), on_off_arg(*on)),
+      Command::RenderStill { width, height, samples } =>
+      {
+        format!("{} {} {} {}", self.name(), width, height, samples)
+      }
+      Command::SetSelectTool(tool) => format!("{} {}", self.name(), select_tool_arg(*tool)),
+      Command::SetBodyFlag { body, flag, on } =>
+      {
+        format!("{} {} {} {}", self.name(), body, flag.name(), on_off_arg(*on))
+      }
+      _ => self.name().to_string(),
+    }
+  }
+
+  /// Parse one line of text. Returns None for unknown names or bad args.
+  pub fn parse(line: &str) -> Option<Command>
+  {
+    let mut parts = line.split_whitespace();
+    let name = parts.next()?;
+    let args: Vec<&str> = parts.collect();
+    let arg = args.first().copied();
+
+    match name
+    {
+      "app.exit" => Some(Command::Exit),
+      "camera.toggle_mode" => Some(Command::ToggleCameraMode),
+      "camera.set_mode" => parse_camera_mode(arg?).map(Command::SetCameraMode),
+      "camera.level_horizon" => Some(Command::LevelHorizon),
+      "camera.focus" => join_words(&args).map(Command::FocusBody),
+      "camera.view" => ViewPreset::from_name(arg?).map(Command::SetView),
+      "camera.back" => Some(Command::ViewBack),
+      "camera.forward" => Some(Command::ViewForward),
+      "camera.reset" => Some(Command::ResetView),
+      "select.tool" => parse_select_tool(arg?).map(Command::SetSelectTool),
+      "probe.toggle" => Some(Command::ToggleProbe),
+      "probe.clear" => Some(Command::ClearProbe),
+      "probe.export" => join_words(&args).map(Command::ExportProbe),
+      "body.flag" =>
+      {
+        // The flag and its state are the last two words; the body the rest.
+        let (body, tail) = args.split_at(args.len().checked_sub(2)?);
+        let flag = BodyFlag::from_name(tail[0])?;
+        let on = parse_on_off(tail[1])?;
+        Some(Command::SetBodyFlag { body: join_words(body)?, flag, on })
+      }
+      "body.insert" => Primitive::from_name(arg?).map(Command::InsertPrimitive),
+      "edit.undo" => Some(Command::Undo),
+      "edit.redo" => Some(Command::Redo),
+      "edit.undo_camera" => parse_on_off(arg?).map(Command::SetUndoCamera),
+      "macro.toggle_record" => Some(Command::ToggleMacroRecording),
+      "macro.play" => Some(Command::PlayMacro),
+      "bake.start" => Some(Command::StartBake),
+      "ui.toggle_log" => Some(Command::ToggleLogPanel),
+      "ui.toggle_telemetry" => Some(Command::ToggleTelemetry),
+      "ui.search" => Some(Command::OpenSearch),
+      "ui.toggle_uv" => Some(Command::ToggleUvPanel),
+      "ui.toggle_settings" => Some(Command::ToggleSettings),
+      "render.display" => DisplayMode::from_name(arg?).map(Command::SetDisplayMode),
+      "render.colormap" => ColorMap::from_name(arg?).map(Command::SetColorMap),
+      "render.field_range" =>
+      {
+        let min = arg?.parse().ok()?;
+        let max = args.get(1)?.parse().ok()?;
+        Some(Command::SetFieldRange { min, max })
+      }
+      "render.toggle_axes" => Some(Command::ToggleAxes),
+      "render.present" => PresentMode::from_name(arg?).map(Command::SetPresentMode),
+      "render.fps_cap" => arg?.parse().ok().map(Command::SetFpsCap),
+      "render.frame_latency" => arg?.parse().ok().map(Command::SetFrameLatency),
+      "render.fxaa" => parse_on_off(arg?).map(Command::SetFxaa),
+      "render.still" =>
+      {
+        let width = arg?.parse().ok()?;
+        let height = args.get(1)?.parse().ok()?;
+        let samples = args.get(2)?.parse().ok()?;
+        Some(Command::RenderStill { width, height, samples })
+      }
+      _ => None,
+    }
+  }
+
+  /// Camera commands, kept out of undo history unless undo_camera is set
+  /// so Ctrl+Z only ever reverts edits.
+  pub fn is_camera(&self) -> bool
+  {
+    matches!(self, Command::ToggleCameraMode | Command::SetCameraMode(_))
+  }
+
+  /// Macro control and history navigation are never captured in a macro
+  /// (replaying them would recurse or depend on unrelated history).
+  pub fn is_recordable(&self) -> bool
+  {
+    !matches!(
+      self,
+      Command::Undo | Command::Redo | Command::ToggleMacroRecording | Command::PlayMacro
+    )
+  }
+}
+
+/// A multi-word argument such as a body name, or None if there are no words.
+fn join_words(words: &[&str]) -> Option<String>
+{
+  if words.is_empty()
+  {
+    return None;
+  }
+  Some(words.join(" "))
+}
+
+fn camera_mode_arg(mode: CameraMode) -> &'static str
+{
+  match mode
+  {
+    CameraMode::Free => "free",
+    CameraMode::Orbital => "orbital",
+  }
+}
+
+fn parse_camera_mode(arg: &str) -> Option<CameraMode>
+{
+  match arg
+  {
+    "free" => Some(CameraMode::Free),
+    "orbital" => Some(CameraMode::Orbital),
+    _ => None,
+  }
+}
+
+fn on_off_arg(on: bool) -> &'static str
+{
+  let mut arg = "off";
+  if on
+  {
+    arg = "on";
+  }
+  arg
+}
+
+fn parse_on_off(arg: &str) -> Option<bool>
+{
+  match arg
+  {
+    "on" => Some(true),
+    "off" => Some(false),
+    _ => None,
+  }
+}
+
+fn select_tool_arg(tool: SelectTool) -> &'static str
+{
+  match tool
+  {
+    SelectTool::Box => "box",
+    SelectTool::Lasso => "lasso",
+    SelectTool::Paint => "paint",
+  }
+}
+
+fn parse_select_tool(arg: &str) -> Option<SelectTool>
+{
+  match arg
+  {
+    "box" => Some(SelectTool::Box),
+    "lasso" => Some(SelectTool::Lasso),
+    "paint" => Some(SelectTool::Paint),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  /// One instance of every command, with multi-word names where they're allowed.
+  fn every_command() -> Vec<Command>
+  {
+    vec![
+      Command::Exit,
+      Command::ToggleCameraMode,
+      Command::SetCameraMode(CameraMode::Free),
+      Command::LevelHorizon,
+      Command::FocusBody("Halley's Comet".to_string()),
+      Command::SetView(ViewPreset::Iso),
+      Command::ViewBack,
+      Command::ViewForward,
+      Command::ResetView,
+      Command::SetSelectTool(SelectTool::Lasso),
+      Command::ToggleProbe,
+      Command::ClearProbe,
+      Command::ExportProbe("probe points.csv".to_string()),
+      Command::SetBodyFlag {
+        body: "Space Station One".to_string(),
+        flag: BodyFlag::NoPick,
+        on: true,
+      },
+      Command::InsertPrimitive(Primitive::Torus),
+      Command::SetDisplayMode(DisplayMode::Checker),
+      Command::SetColorMap(ColorMap::Viridis),
+      Command::SetFieldRange { min: -8500.5, max: 9000.0 },
+      Command::ToggleAxes,
+      Command::SetPresentMode(PresentMode::Mailbox),
+      Command::SetFpsCap(144),
+      Command::SetFrameLatency(1),
+      Command::SetFxaa(false),
+      Command::SetUndoCamera(true),
+      Command::RenderStill { width: 3840, height: 2160, samples: 16 },
+      Command::OpenSearch,
+      Command::Undo,
+      Command::Redo,
+      Command::ToggleMacroRecording,
+      Command::PlayMacro,
+      Command::StartBake,
+      Command::ToggleLogPanel,
+      Command::ToggleTelemetry,
+      Command::ToggleUvPanel,
+      Command::ToggleSettings,
+    ]
+  }
+
+  #[test]
+  fn every_table_entry_round_trips()
+  {
+    let commands = every_command();
+    for info in COMMAND_TABLE
+    {
+      let command = commands.iter().find(|c| c.name() == info.name);
+      let command = command.unwrap_or_else(|| panic!("no sample for {}", info.name));
+      assert_eq!(Command::parse(&command.to_line()).as_ref(), Some(command), "{}", info.name);
+    }
+    assert_eq!(commands.len(), COMMAND_TABLE.len());
+  }
+
+  #[test]
+  fn body_names_keep_their_spaces()
+  {
+    assert_eq!(
+      Command::parse("camera.focus  Halley's   Comet "),
+      Some(Command::FocusBody("Halley's Comet".to_string()))
+    );
+    assert_eq!(
+      Command::parse("body.flag Space Station on_top off"),
+      Some(Command::SetBodyFlag {
+        body: "Space Station".to_string(),
+        flag: BodyFlag::AlwaysOnTop,
+        on: false,
+      })
+    );
+    assert_eq!(
+      Command::parse("body.flag Moon no_export on"),
+      Some(Command::SetBodyFlag { body: "Moon".to_string(), flag: BodyFlag::NoExport, on: true })
+    );
+    assert_eq!(Command::parse("camera.focus"), None);
+    assert_eq!(Command::parse("body.flag on_top off"), None);
+  }
+}
@@ -0,0 +1,59 @@
+use crate::command::Command;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  MacroRecorder
+//
+//  While recording, every recordable command that passes through
+//  App::execute_command is appended. Stopping keeps the sequence as the
+//  "last macro" for playback.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+pub struct MacroRecorder
+{
+  pub recording: bool,
+  current: Vec<Command>,
+  last_macro: Vec<Command>,
+}
+
+impl MacroRecorder
+{
+  pub fn start(&mut self)
+  {
+    self.current.clear();
+    self.recording = true;
+  }
+
+  /// Stop recording and keep what was captured. Returns the command count.
+  pub fn stop(&mut self) -> usize
+  {
+    self.recording = false;
+    self.last_macro = std::mem::take(&mut self.current);
+    self.last_macro.len()
+  }
+
+  pub fn record(&mut self, command: &Command)
+  {
+    if self.recording && command.is_recordable()
+    {
+      self.current.push(command.clone());
+    }
+  }
+
+  pub fn last_macro(&self) -> &[Command]
+  {
+    &self.last_macro
+  }
+
+  /// The last macro as text, one command per line.
+  pub fn to_text(&self) -> String
+  {
+    let mut text = String::new();
+    for command in &self.last_macro
+    {
+      text.push_str(&command.to_line());
+      text.push('\n');
+    }
+    text
+  }
+}
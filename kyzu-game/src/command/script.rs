@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use crate::command::Command;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Startup scripts
+//
+//  A script is a text file of command lines in the same form macros use
+//  ("camera.focus earth"). Blank lines and lines starting with '#' are
+//  skipped. Passed with --script; --batch quits once the tasks and still
+//  it started have finished.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn load(path: &Path) -> anyhow::Result<Vec<Command>>
+{
+  let text = std::fs::read_to_string(path)
+    .map_err(|e| anyhow::anyhow!("Could not read script {}: {}", path.display(), e))?;
+  parse(&text).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+}
+
+/// Parse every line, failing on the first one that isn't a known command.
+pub fn parse(text: &str) -> anyhow::Result<Vec<Command>>
+{
+  let mut commands = Vec::new();
+  for (number, line) in text.lines().enumerate()
+  {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#')
+    {
+      continue;
+    }
+    match Command::parse(line)
+    {
+      Some(command) => commands.push(command),
+      None => anyhow::bail!("line {}: unknown command '{}'", number + 1, line),
+    }
+  }
+  Ok(commands)
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+  use crate::render::camera::ViewPreset;
+
+  #[test]
+  fn skips_blanks_and_comments()
+  {
+    let commands = parse("# frame the earth\n\ncamera.focus earth\n  camera.view top  \n").unwrap();
+    assert_eq!(
+      commands,
+      vec![Command::FocusBody("earth".to_string()), Command::SetView(ViewPreset::Top)]
+    );
+  }
+
+  #[test]
+  fn reports_the_bad_line()
+  {
+    let error = parse("camera.back\ncamera.view sideways\n").unwrap_err();
+    assert!(error.to_string().starts_with("line 2:"));
+  }
+}
@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::palette::PaletteConfig;
+use crate::render::texture::TextureFiltering;
+use crate::world::registry::RenderFlags;
+
 // The only hardcoded bootstrap path allowed in the source
 const BOOTSTRAP_PATH: &str = "C:\\dev\\kyzu_data\\engine_config.json";
 
@@ -29,6 +34,205 @@ pub struct AppConfig
   pub test_mesh: String,
   pub saves_subdir: String,
   pub active_save: String,
+  /// Fixed simulation rate, independent of the display refresh rate.
+  #[serde(default = "default_sim_tick_hz")]
+  pub sim_tick_hz: f64,
+  /// A frame slower than this counts towards the GPU watchdog tripping.
+  #[serde(default = "default_watchdog_frame_ms")]
+  pub watchdog_frame_ms: u64,
+  /// Let the desktop show through empty space, where the surface allows it.
+  #[serde(default)]
+  pub transparent_window: bool,
+  /// Colour scheme (standard, deuteranopia, protanopia) and overrides.
+  #[serde(default)]
+  pub palette: PaletteConfig,
+  /// Momentum for right-drag orbit and look, and for orbital panning.
+  #[serde(default)]
+  pub camera_inertia: InertiaConfig,
+  /// GPU to render on when there are several: part of its name, e.g.
+  /// "nvidia". Absent picks the default high-performance adapter.
+  #[serde(default)]
+  pub adapter: Option<String>,
+  /// Swapchain present mode. When absent, vsync_enabled picks fifo or immediate.
+  #[serde(default)]
+  pub present_mode: Option<PresentMode>,
+  /// Frames the GPU may queue ahead of the display (1-3). 1 gives the
+  /// lowest input latency, 3 the smoothest frame rate under load.
+  #[serde(default = "default_max_frame_latency")]
+  pub max_frame_latency: u32,
+  /// Frames per second limit, for battery-powered machines. 0 = no cap.
+  #[serde(default)]
+  pub fps_cap: u32,
+  /// Samples per pixel for the 3D scene (1, 2, 4 or 8); 1 turns MSAA off.
+  /// Unset picks one for the adapter (GpuCapabilities::default_antialiasing).
+  #[serde(default)]
+  pub msaa_samples: Option<u32>,
+  /// Fullscreen FXAA after the scene; cheaper than MSAA on weak GPUs.
+  /// Unset turns it on for downlevel adapters only.
+  #[serde(default)]
+  pub fxaa: Option<bool>,
+  /// How far from the cursor a click or selection tool still hits
+  /// something, in physical pixels.
+  #[serde(default = "default_pick_tolerance_px")]
+  pub pick_tolerance_px: f32,
+  /// Record camera mode switches in undo history. Views always go to the
+  /// separate view history (Alt+Left / Alt+Right) either way.
+  #[serde(default)]
+  pub undo_camera: bool,
+  /// Width of axis and debug lines, in physical pixels.
+  #[serde(default = "default_line_width_px")]
+  pub line_width_px: f32,
+  /// GPU memory the render farm may use across all its workers, in MB.
+  #[serde(default = "default_farm_memory_mb")]
+  pub farm_memory_mb: u64,
+  /// Anisotropic filtering for body textures, 1 (off) to 16. Only applies
+  /// with trilinear filtering on.
+  #[serde(default = "default_anisotropy")]
+  pub anisotropy: u16,
+  /// Blend between mip levels when sampling body textures; off uses the
+  /// nearest level, which is sharper but shimmers in motion.
+  #[serde(default = "default_trilinear")]
+  pub trilinear: bool,
+  /// Further windows showing the main view, each on its own GPU device,
+  /// e.g. a wall display driven by a second graphics card.
+  #[serde(default)]
+  pub mirror_windows: Vec<MirrorWindowConfig>,
+}
+
+impl AppConfig
+{
+  pub fn present_mode(&self) -> PresentMode
+  {
+    match self.present_mode
+    {
+      Some(mode) => mode,
+      None if self.vsync_enabled => PresentMode::Fifo,
+      None => PresentMode::Immediate,
+    }
+  }
+
+  pub fn texture_filtering(&self) -> TextureFiltering
+  {
+    TextureFiltering { anisotropy: self.anisotropy, trilinear: self.trilinear }
+  }
+}
+
+/// How frames reach the screen. Fifo waits for vblank (vsync); Mailbox
+/// replaces the queued frame without tearing; Immediate may tear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresentMode
+{
+  Fifo,
+  Mailbox,
+  Immediate,
+}
+
+impl PresentMode
+{
+  pub const ALL: [PresentMode; 3] =
+    [PresentMode::Fifo, PresentMode::Mailbox, PresentMode::Immediate];
+
+  pub fn name(self) -> &'static str
+  {
+    match self
+    {
+      PresentMode::Fifo => "fifo",
+      PresentMode::Mailbox => "mailbox",
+      PresentMode::Immediate => "immediate",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<PresentMode>
+  {
+    PresentMode::ALL.into_iter().find(|mode| mode.name() == name)
+  }
+}
+
+fn default_sim_tick_hz() -> f64
+{
+  60.0
+}
+
+fn default_watchdog_frame_ms() -> u64
+{
+  1000
+}
+
+fn default_max_frame_latency() -> u32
+{
+  2
+}
+
+fn default_pick_tolerance_px() -> f32
+{
+  4.0
+}
+
+fn default_line_width_px() -> f32
+{
+  2.0
+}
+
+fn default_farm_memory_mb() -> u64
+{
+  1024
+}
+
+fn default_anisotropy() -> u16
+{
+  8
+}
+
+fn default_trilinear() -> bool
+{
+  true
+}
+
+fn default_mirror_width() -> u32
+{
+  1280
+}
+
+fn default_mirror_height() -> u32
+{
+  720
+}
+
+/// One mirror window. The scene reaches it through the CPU, so it may
+/// render on a different adapter from the main window.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MirrorWindowConfig
+{
+  /// GPU to draw it on, matched like AppConfig::adapter. Absent picks the
+  /// default high-performance adapter.
+  #[serde(default)]
+  pub adapter: Option<String>,
+  #[serde(default = "default_mirror_width")]
+  pub width: u32,
+  #[serde(default = "default_mirror_height")]
+  pub height: u32,
+  /// Borderless fullscreen on this monitor, counted from 0 in the order
+  /// the system lists them. Absent opens an ordinary window.
+  #[serde(default)]
+  pub monitor: Option<usize>,
+}
+
+/// Off by default; decay_seconds is how quickly a flick slows down.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct InertiaConfig
+{
+  pub enabled: bool,
+  pub decay_seconds: f32,
+}
+
+impl Default for InertiaConfig
+{
+  fn default() -> Self
+  {
+    Self { enabled: false, decay_seconds: 0.25 }
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -50,6 +254,10 @@ pub struct SaveConfig
   pub game_time_seconds: f64,
   pub autosave_interval_seconds: u32,
   pub player_start_body: String,
+  /// body.flag settings by body name; bodies left at the defaults are
+  /// not listed.
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub body_flags: BTreeMap<String, RenderFlags>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -95,6 +303,16 @@ pub fn load() -> Result<KyzuConfig, String>
   Ok(KyzuConfig { app, world, save, save_dir })
 }
 
+/// Write `save` back to game.json in `save_dir`.
+pub fn write_save(save_dir: &Path, save: &SaveConfig) -> Result<(), String>
+{
+  let game_json_path = save_dir.join("game.json");
+  let content =
+    serde_json::to_string_pretty(save).map_err(|e| format!("game.json encode error: {}", e))?;
+  fs::write(&game_json_path, content)
+    .map_err(|e| format!("Could not write game.json at {:?}: {}", game_json_path, e))
+}
+
 fn load_or_create_save(save_dir: &PathBuf, world_name: &str) -> Result<SaveConfig, String>
 {
   let game_json_path = save_dir.join("game.json");
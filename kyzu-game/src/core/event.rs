@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use glam::{DVec3, Vec2};
+
+use crate::render::shared::CameraMode;
+use crate::world::selection::SelectMode;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  EventChannel
+//
+//  One typed, double-buffered queue. Events published during frame N become
+//  readable during frame N+1, by any number of readers, without anyone
+//  consuming them. swap() at the end of each frame rotates the buffers.
+//
+//  Keeps subsystems from reaching into each other's state: the producer
+//  publishes, the consumers read, neither knows about the other.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct EventChannel<T>
+{
+  readable: Vec<T>,
+  pending: Vec<T>,
+}
+
+impl<T> Default for EventChannel<T>
+{
+  fn default() -> Self
+  {
+    Self { readable: Vec::new(), pending: Vec::new() }
+  }
+}
+
+impl<T> EventChannel<T>
+{
+  pub fn publish(&mut self, event: T)
+  {
+    self.pending.push(event);
+  }
+
+  /// Events published during the previous frame.
+  pub fn read(&self) -> &[T]
+  {
+    &self.readable
+  }
+
+  /// Drop last frame's events and make this frame's events readable.
+  pub fn swap(&mut self)
+  {
+    self.readable.clear();
+    std::mem::swap(&mut self.readable, &mut self.pending);
+  }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Event payloads
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct CameraModeChanged
+{
+  pub mode: CameraMode,
+}
+
+pub struct CameraMoved
+{
+  /// New eye position in world metres.
+  pub eye_world: DVec3,
+}
+
+pub struct BodySpawned
+{
+  /// Stable index into BodyRegistry::bodies.
+  pub index: usize,
+  pub name: String,
+}
+
+pub struct AssetLoaded
+{
+  pub path: PathBuf,
+}
+
+/// Result of a click in the viewport, from the GPU ID pass and CPU ray cast.
+pub struct BodyPicked
+{
+  /// Index into BodyRegistry::bodies, or None for empty space.
+  pub index: Option<usize>,
+  /// Cursor position in physical pixels.
+  pub pixel: Vec2,
+  /// Surface point from the CPU ray cast, in world metres.
+  pub point: Option<DVec3>,
+  /// Modifier held at the click.
+  pub mode: SelectMode,
+}
+
+/// Bodies caught by a marquee, lasso or paint stroke.
+pub struct RegionSelected
+{
+  pub indices: Vec<usize>,
+  pub mode: SelectMode,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  EventBus
+//
+//  One channel per event type. Owned by App and swapped once per frame.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+pub struct EventBus
+{
+  pub camera_mode_changed: EventChannel<CameraModeChanged>,
+  pub camera_moved: EventChannel<CameraMoved>,
+  pub body_spawned: EventChannel<BodySpawned>,
+  pub asset_loaded: EventChannel<AssetLoaded>,
+  pub body_picked: EventChannel<BodyPicked>,
+  pub region_selected: EventChannel<RegionSelected>,
+}
+
+impl EventBus
+{
+  /// Call once at the end of every frame.
+  pub fn swap(&mut self)
+  {
+    self.camera_mode_changed.swap();
+    self.camera_moved.swap();
+    self.body_spawned.swap();
+    self.asset_loaded.swap();
+    self.body_picked.swap();
+    self.region_selected.swap();
+  }
+}
@@ -32,3 +32,46 @@ pub fn get_aspect_ratio(viewport: &Viewport) -> f32
   }
   1.0
 }
+
+/// Even-odd test: is `point` inside the closed polygon through `vertices`?
+pub fn point_in_polygon(point: glam::Vec2, vertices: &[glam::Vec2]) -> bool
+{
+  let mut inside = false;
+  let mut previous = match vertices.last()
+  {
+    Some(v) => *v,
+    None => return false,
+  };
+
+  for &current in vertices
+  {
+    let crosses = (current.y > point.y) != (previous.y > point.y);
+    if crosses
+    {
+      let t = (point.y - current.y) / (previous.y - current.y);
+      if point.x < current.x + t * (previous.x - current.x)
+      {
+        inside = !inside;
+      }
+    }
+    previous = current;
+  }
+  inside
+}
+
+/// Human-readable length: metres, kilometres or astronomical units.
+pub fn format_distance(metres: f64) -> String
+{
+  const AU: f64 = 149_597_870_700.0;
+
+  let abs = metres.abs();
+  if abs >= AU * 0.1
+  {
+    return format!("{:.3} AU", metres / AU);
+  }
+  if abs >= 10_000.0
+  {
+    return format!("{:.1} km", metres / 1000.0);
+  }
+  format!("{:.2} m", metres)
+}
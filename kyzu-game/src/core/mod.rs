@@ -1,5 +1,11 @@
 pub mod config;
 pub mod error;
+pub mod event;
 pub mod log;
 pub mod math;
+pub mod notify;
+pub mod palette;
+pub mod png;
+pub mod task;
+pub mod tick;
 pub mod time;
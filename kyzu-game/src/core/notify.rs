@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use crate::command::Command;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  NotificationQueue
+//
+//  Non-blocking, user-facing messages (toasts). Anything the player should
+//  see — a failed bake, a renderer error — goes here as well as the log.
+//  An optional action turns into a button that executes a Command.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotifyLevel
+{
+  Info,
+  Warning,
+  Error,
+}
+
+pub struct NotifyAction
+{
+  pub label: String,
+  pub command: Command,
+}
+
+pub struct Notification
+{
+  pub level: NotifyLevel,
+  pub message: String,
+  pub action: Option<NotifyAction>,
+  /// How many times this same message was pushed while still on screen.
+  pub repeat_count: u32,
+  shown_at: Instant,
+}
+
+#[derive(Default)]
+pub struct NotificationQueue
+{
+  pub items: Vec<Notification>,
+}
+
+fn lifetime(level: NotifyLevel) -> Duration
+{
+  match level
+  {
+    NotifyLevel::Info => Duration::from_secs(4),
+    NotifyLevel::Warning => Duration::from_secs(8),
+    NotifyLevel::Error => Duration::from_secs(20),
+  }
+}
+
+impl NotificationQueue
+{
+  /// Add a toast. A message identical to one already showing restarts its
+  /// timer and bumps repeat_count instead of stacking a duplicate.
+  pub fn push(&mut self, level: NotifyLevel, message: &str, action: Option<NotifyAction>)
+  {
+    for item in &mut self.items
+    {
+      if item.level == level && item.message == message
+      {
+        item.repeat_count += 1;
+        item.shown_at = Instant::now();
+        return;
+      }
+    }
+
+    self.items.push(Notification {
+      level,
+      message: message.to_string(),
+      action,
+      repeat_count: 1,
+      shown_at: Instant::now(),
+    });
+  }
+
+  pub fn info(&mut self, message: &str)
+  {
+    self.push(NotifyLevel::Info, message, None);
+  }
+
+  pub fn warn(&mut self, message: &str)
+  {
+    self.push(NotifyLevel::Warning, message, None);
+  }
+
+  pub fn error(&mut self, message: &str)
+  {
+    self.push(NotifyLevel::Error, message, None);
+  }
+
+  pub fn dismiss(&mut self, index: usize)
+  {
+    if index < self.items.len()
+    {
+      self.items.remove(index);
+    }
+  }
+
+  /// Drop toasts whose time is up. Call once per frame.
+  pub fn expire(&mut self)
+  {
+    let now = Instant::now();
+    self.items.retain(|item| now.duration_since(item.shown_at) < lifetime(item.level));
+  }
+}
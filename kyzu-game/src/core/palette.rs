@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Palette
+//
+//  Every UI and render colour that carries meaning (axes, selection,
+//  markers, heat maps) comes from here rather than per-file constants, so
+//  a colour-blind safe scheme or a user override applies everywhere.
+//  Body base colours are data, not meaning, and stay with the renderer.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub type Rgb = [f32; 3];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteScheme
+{
+  #[default]
+  Standard,
+  /// Red-green safe, tuned for reduced green sensitivity.
+  Deuteranopia,
+  /// Red-green safe, avoiding reds that read as dark for protanopes.
+  Protanopia,
+}
+
+/// `palette` block of the app config: a scheme plus optional overrides.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaletteConfig
+{
+  #[serde(default)]
+  pub scheme: PaletteScheme,
+  #[serde(default)]
+  pub axis_x: Option<Rgb>,
+  #[serde(default)]
+  pub axis_y: Option<Rgb>,
+  #[serde(default)]
+  pub axis_z: Option<Rgb>,
+  #[serde(default)]
+  pub selection: Option<Rgb>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette
+{
+  pub axis_x: Rgb,
+  pub axis_y: Rgb,
+  pub axis_z: Rgb,
+  /// Selection tint, outline and marquee.
+  pub selection: Rgb,
+  /// Pivot rings, clash and warning markers.
+  pub marker: Rgb,
+  /// Heat map stops from low to high, evenly spaced.
+  pub heat: [Rgb; 5],
+}
+
+impl Default for Palette
+{
+  fn default() -> Self
+  {
+    Self::from_scheme(PaletteScheme::Standard)
+  }
+}
+
+impl Palette
+{
+  pub fn from_scheme(scheme: PaletteScheme) -> Self
+  {
+    match scheme
+    {
+      PaletteScheme::Standard => Self {
+        axis_x: [0.90, 0.25, 0.25],
+        axis_y: [0.30, 0.80, 0.30],
+        axis_z: [0.30, 0.50, 1.00],
+        selection: [1.00, 0.60, 0.10],
+        marker: [1.00, 0.86, 0.47],
+        // Blue → cyan → green → yellow → red.
+        heat: [
+          [0.10, 0.20, 0.80],
+          [0.10, 0.70, 0.90],
+          [0.20, 0.80, 0.30],
+          [0.95, 0.85, 0.20],
+          [0.85, 0.15, 0.10],
+        ],
+      },
+      // Okabe-Ito hues; viridis for the heat map.
+      PaletteScheme::Deuteranopia => Self {
+        axis_x: [0.84, 0.37, 0.00],
+        axis_y: [0.94, 0.89, 0.26],
+        axis_z: [0.00, 0.45, 0.70],
+        selection: [0.34, 0.71, 0.91],
+        marker: [0.80, 0.47, 0.65],
+        heat: [
+          [0.27, 0.00, 0.33],
+          [0.23, 0.32, 0.55],
+          [0.13, 0.57, 0.55],
+          [0.37, 0.79, 0.38],
+          [0.99, 0.91, 0.15],
+        ],
+      },
+      // Okabe-Ito hues without vermillion; cividis for the heat map.
+      PaletteScheme::Protanopia => Self {
+        axis_x: [0.90, 0.62, 0.00],
+        axis_y: [0.94, 0.89, 0.26],
+        axis_z: [0.00, 0.45, 0.70],
+        selection: [0.34, 0.71, 0.91],
+        marker: [0.80, 0.47, 0.65],
+        heat: [
+          [0.00, 0.13, 0.30],
+          [0.25, 0.30, 0.42],
+          [0.49, 0.48, 0.47],
+          [0.74, 0.69, 0.44],
+          [1.00, 0.92, 0.27],
+        ],
+      },
+    }
+  }
+
+  /// Scheme colours with any configured overrides applied.
+  pub fn from_config(config: &PaletteConfig) -> Self
+  {
+    let mut palette = Self::from_scheme(config.scheme);
+    if let Some(c) = config.axis_x
+    {
+      palette.axis_x = c;
+    }
+    if let Some(c) = config.axis_y
+    {
+      palette.axis_y = c;
+    }
+    if let Some(c) = config.axis_z
+    {
+      palette.axis_z = c;
+    }
+    if let Some(c) = config.selection
+    {
+      palette.selection = c;
+    }
+    palette
+  }
+}
+
+/// Colour `t` in [0, 1] blended between evenly spaced stops.
+pub fn blend_stops(stops: &[Rgb; 5], t: f32) -> Rgb
+{
+  let last = stops.len() - 1;
+  let scaled = t.clamp(0.0, 1.0) * last as f32;
+  let index = (scaled as usize).min(last - 1);
+  let f = scaled - index as f32;
+
+  let (a, b) = (stops[index], stops[index + 1]);
+  [a[0] + (b[0] - a[0]) * f, a[1] + (b[1] - a[1]) * f, a[2] + (b[2] - a[2]) * f]
+}
+
+/// Colour map for scalar fields. Heat follows the palette scheme; the
+/// others are fixed five-stop versions of the usual maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMap
+{
+  Heat,
+  Viridis,
+  Jet,
+  Coolwarm,
+}
+
+impl ColorMap
+{
+  pub const ALL: [ColorMap; 4] =
+    [ColorMap::Heat, ColorMap::Viridis, ColorMap::Jet, ColorMap::Coolwarm];
+
+  pub fn name(self) -> &'static str
+  {
+    match self
+    {
+      ColorMap::Heat => "heat",
+      ColorMap::Viridis => "viridis",
+      ColorMap::Jet => "jet",
+      ColorMap::Coolwarm => "coolwarm",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<ColorMap>
+  {
+    ColorMap::ALL.into_iter().find(|map| map.name() == name)
+  }
+
+  pub fn stops(self, palette: &Palette) -> [Rgb; 5]
+  {
+    match self
+    {
+      ColorMap::Heat => palette.heat,
+      ColorMap::Viridis => [
+        [0.27, 0.00, 0.33],
+        [0.23, 0.32, 0.55],
+        [0.13, 0.57, 0.55],
+        [0.37, 0.79, 0.38],
+        [0.99, 0.91, 0.15],
+      ],
+      ColorMap::Jet => [
+        [0.00, 0.00, 0.90],
+        [0.00, 0.80, 1.00],
+        [0.50, 1.00, 0.50],
+        [1.00, 0.80, 0.00],
+        [0.90, 0.00, 0.00],
+      ],
+      ColorMap::Coolwarm => [
+        [0.23, 0.30, 0.75],
+        [0.55, 0.69, 1.00],
+        [0.87, 0.87, 0.87],
+        [0.96, 0.60, 0.49],
+        [0.71, 0.02, 0.15],
+      ],
+    }
+  }
+
+  /// Colour for `t` in [0, 1] along the map, as the body shader's
+  /// field_color blends it; the legend bar is drawn with this.
+  pub fn color(self, palette: &Palette, t: f32) -> Rgb
+  {
+    blend_stops(&self.stops(palette), t)
+  }
+}
+
+/// Palette colour as an egui colour with the given alpha (0-255).
+pub fn to_color32(rgb: Rgb, alpha: u8) -> egui::Color32
+{
+  let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+  egui::Color32::from_rgba_unmultiplied(channel(rgb[0]), channel(rgb[1]), channel(rgb[2]), alpha)
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn heat_map_follows_the_scheme()
+  {
+    let standard = Palette::from_scheme(PaletteScheme::Standard);
+    let deuteranopia = Palette::from_scheme(PaletteScheme::Deuteranopia);
+    assert_eq!(ColorMap::Heat.stops(&deuteranopia), deuteranopia.heat);
+    assert_ne!(ColorMap::Heat.color(&standard, 0.0), ColorMap::Heat.color(&deuteranopia, 0.0));
+    // Fixed maps ignore the scheme.
+    assert_eq!(ColorMap::Jet.color(&standard, 0.3), ColorMap::Jet.color(&deuteranopia, 0.3));
+  }
+
+  #[test]
+  fn colors_blend_between_stops_and_clamp()
+  {
+    let palette = Palette::default();
+    let map = ColorMap::Heat;
+    let close = |x: Rgb, y: Rgb| (0..3).all(|c| (x[c] - y[c]).abs() < 1e-6);
+    assert!(close(map.color(&palette, -1.0), palette.heat[0]));
+    assert!(close(map.color(&palette, 2.0), palette.heat[4]));
+
+    let [a, b] = [palette.heat[1], palette.heat[2]];
+    let halfway = [(a[0] + b[0]) * 0.5, (a[1] + b[1]) * 0.5, (a[2] + b[2]) * 0.5];
+    assert!(close(map.color(&palette, 0.375), halfway));
+  }
+}
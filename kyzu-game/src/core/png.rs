@@ -0,0 +1,242 @@
+use std::io::{Error, ErrorKind, Read, Write};
+use std::path::Path;
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  PNG input and output
+//
+//  Just enough of the format for rendered frames and body textures.
+//  Writing emits one IHDR, one zlib-compressed IDAT with no row filtering,
+//  and IEND. Reading takes 8-bit grey, grey-alpha, RGB or RGBA images
+//  without interlacing, which covers what image editors save by default.
+// ─────────────────────────────────────────────────────────────────────────────
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+/// IHDR colour type for 8-bit RGBA.
+const COLOR_TYPE_RGBA: u8 = 6;
+
+/// Write 8-bit RGBA pixels, rows top to bottom, as a PNG file.
+pub fn write_rgba(path: &Path, width: u32, height: u32, rgba: &[u8]) -> std::io::Result<()>
+{
+  let row_bytes = width as usize * 4;
+  if rgba.len() != row_bytes * height as usize
+  {
+    return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "pixel count mismatch"));
+  }
+
+  // Each scanline starts with its filter type; 0 is none.
+  let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+  for row in rgba.chunks_exact(row_bytes)
+  {
+    zlib.write_all(&[0])?;
+    zlib.write_all(row)?;
+  }
+  let idat = zlib.finish()?;
+
+  let mut ihdr = Vec::with_capacity(13);
+  ihdr.extend_from_slice(&width.to_be_bytes());
+  ihdr.extend_from_slice(&height.to_be_bytes());
+  ihdr.extend_from_slice(&[8, COLOR_TYPE_RGBA, 0, 0, 0]);
+
+  let mut out = Vec::with_capacity(idat.len() + 64);
+  out.extend_from_slice(&SIGNATURE);
+  push_chunk(&mut out, b"IHDR", &ihdr);
+  push_chunk(&mut out, b"IDAT", &idat);
+  push_chunk(&mut out, b"IEND", &[]);
+  std::fs::write(path, out)
+}
+
+/// Length, type, data, then a CRC over type and data.
+fn push_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8])
+{
+  out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+  out.extend_from_slice(kind);
+  out.extend_from_slice(data);
+
+  let mut crc = Crc::new();
+  crc.update(kind);
+  crc.update(data);
+  out.extend_from_slice(&crc.sum().to_be_bytes());
+}
+
+/// Decoded image: width, height and RGBA8 pixels, rows top to bottom.
+pub type RgbaImage = (u32, u32, Vec<u8>);
+
+/// Read a PNG file as RGBA8. Palette, 16-bit and interlaced images are
+/// rejected.
+pub fn read_rgba(path: &Path) -> std::io::Result<RgbaImage>
+{
+  decode(&std::fs::read(path)?)
+}
+
+fn decode(bytes: &[u8]) -> std::io::Result<RgbaImage>
+{
+  if bytes.len() < SIGNATURE.len() || bytes[..SIGNATURE.len()] != SIGNATURE
+  {
+    return Err(invalid("not a PNG file"));
+  }
+
+  let mut header = None;
+  let mut compressed = Vec::new();
+  let mut at = SIGNATURE.len();
+  while at + 8 <= bytes.len()
+  {
+    let length = u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+    let kind = &bytes[at + 4..at + 8];
+    let data = bytes.get(at + 8..at + 8 + length).ok_or_else(|| invalid("truncated chunk"))?;
+    match kind
+    {
+      b"IHDR" => header = Some(data.to_vec()),
+      b"IDAT" => compressed.extend_from_slice(data),
+      b"IEND" => break,
+      _ => (),
+    }
+    // Skip the CRC; zlib's own checksum catches corrupt pixel data.
+    at += 12 + length;
+  }
+
+  let header = header.filter(|h| h.len() == 13).ok_or_else(|| invalid("missing IHDR"))?;
+  let width = u32::from_be_bytes(header[0..4].try_into().unwrap());
+  let height = u32::from_be_bytes(header[4..8].try_into().unwrap());
+  let (bit_depth, color_type, interlace) = (header[8], header[9], header[12]);
+
+  let channels = match color_type
+  {
+    0 => 1,
+    2 => 3,
+    4 => 2,
+    COLOR_TYPE_RGBA => 4,
+    _ => return Err(invalid("palette images are not supported")),
+  };
+  if bit_depth != 8 || interlace != 0
+  {
+    return Err(invalid("only 8-bit, non-interlaced images are supported"));
+  }
+
+  let mut raw = Vec::new();
+  ZlibDecoder::new(&compressed[..]).read_to_end(&mut raw)?;
+
+  let stride = width as usize * channels;
+  if raw.len() < (stride + 1) * height as usize
+  {
+    return Err(invalid("pixel data too short"));
+  }
+
+  let mut pixels = vec![0u8; stride * height as usize];
+  for row in 0..height as usize
+  {
+    let filter = raw[row * (stride + 1)];
+    let line = &raw[row * (stride + 1) + 1..(row + 1) * (stride + 1)];
+    let (done, rest) = pixels.split_at_mut(row * stride);
+    let previous = &done[done.len().saturating_sub(stride)..];
+    unfilter(filter, line, previous, &mut rest[..stride], channels)?;
+  }
+
+  let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+  for pixel in pixels.chunks_exact(channels)
+  {
+    match channels
+    {
+      1 => rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], 255]),
+      2 => rgba.extend_from_slice(&[pixel[0], pixel[0], pixel[0], pixel[1]]),
+      3 => rgba.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]),
+      _ => rgba.extend_from_slice(pixel),
+    }
+  }
+  Ok((width, height, rgba))
+}
+
+/// Undo one scanline's filter. `previous` is the decoded line above, empty
+/// for the first row.
+fn unfilter(
+  filter: u8,
+  line: &[u8],
+  previous: &[u8],
+  out: &mut [u8],
+  bpp: usize,
+) -> std::io::Result<()>
+{
+  for i in 0..line.len()
+  {
+    let mut left = 0;
+    if i >= bpp
+    {
+      left = out[i - bpp];
+    }
+    let up = previous.get(i).copied().unwrap_or(0);
+    let mut up_left = 0;
+    if i >= bpp
+    {
+      up_left = previous.get(i - bpp).copied().unwrap_or(0);
+    }
+
+    let predicted = match filter
+    {
+      0 => 0,
+      1 => left,
+      2 => up,
+      3 => ((left as u16 + up as u16) / 2) as u8,
+      4 => paeth(left, up, up_left),
+      _ => return Err(invalid("unknown row filter")),
+    };
+    out[i] = line[i].wrapping_add(predicted);
+  }
+  Ok(())
+}
+
+/// Whichever of left, up and up-left is closest to left + up - up_left.
+fn paeth(a: u8, b: u8, c: u8) -> u8
+{
+  let p = a as i16 + b as i16 - c as i16;
+  let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+  if pa <= pb && pa <= pc
+  {
+    return a;
+  }
+  if pb <= pc
+  {
+    return b;
+  }
+  c
+}
+
+fn invalid(message: &str) -> Error
+{
+  Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn written_images_read_back_unchanged()
+  {
+    let path = std::env::temp_dir().join("kyzu_png_round_trip.png");
+    let rgba: Vec<u8> = (0..3 * 2 * 4).map(|i| (i * 10) as u8).collect();
+    write_rgba(&path, 3, 2, &rgba).unwrap();
+
+    let (width, height, read) = read_rgba(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!((width, height), (3, 2));
+    assert_eq!(read, rgba);
+  }
+
+  #[test]
+  fn filtered_rows_are_reconstructed()
+  {
+    // Two grey pixels per row: Sub on the first row, Up on the second.
+    let line = [1, 10, 5, 2, 3, 4];
+    let mut first = [0u8; 2];
+    unfilter(line[0], &line[1..3], &[], &mut first, 1).unwrap();
+    assert_eq!(first, [10, 15]);
+
+    let mut second = [0u8; 2];
+    unfilter(line[3], &line[4..6], &first, &mut second, 1).unwrap();
+    assert_eq!(second, [13, 19]);
+  }
+}
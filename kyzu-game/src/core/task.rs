@@ -0,0 +1,188 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::command::Command;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  TaskHandle
+//
+//  Shared view of one long-running job (bake, export, import). The worker
+//  reports progress and polls is_cancelled(); the UI reads progress and
+//  calls cancel(). Cheap to clone — every clone points at the same task.
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct TaskInner
+{
+  name: String,
+  /// f32 progress [0, 1] stored as raw bits so it can live in an atomic.
+  progress_bits: AtomicU32,
+  message: Mutex<String>,
+  cancel_requested: AtomicBool,
+  failed: AtomicBool,
+  finished: AtomicBool,
+}
+
+#[derive(Clone)]
+pub struct TaskHandle
+{
+  inner: Arc<TaskInner>,
+  /// Command that starts the same job again, offered when it fails.
+  retry: Option<Command>,
+}
+
+impl TaskHandle
+{
+  pub fn new(name: &str) -> Self
+  {
+    let inner = TaskInner {
+      name: name.to_string(),
+      progress_bits: AtomicU32::new(0.0f32.to_bits()),
+      message: Mutex::new(String::new()),
+      cancel_requested: AtomicBool::new(false),
+      failed: AtomicBool::new(false),
+      finished: AtomicBool::new(false),
+    };
+    Self { inner: Arc::new(inner), retry: None }
+  }
+
+  pub fn name(&self) -> &str
+  {
+    &self.inner.name
+  }
+
+  /// What the failure notification's Retry runs; None offers no retry.
+  pub fn retry(&self) -> Option<&Command>
+  {
+    self.retry.as_ref()
+  }
+
+  /// Worker side: fraction in [0, 1] plus a short status line.
+  pub fn set_progress(&self, fraction: f32, message: &str)
+  {
+    let clamped = fraction.clamp(0.0, 1.0);
+    self.inner.progress_bits.store(clamped.to_bits(), Ordering::Relaxed);
+
+    if let Ok(mut current) = self.inner.message.lock()
+    {
+      *current = message.to_string();
+    }
+  }
+
+  pub fn progress(&self) -> f32
+  {
+    f32::from_bits(self.inner.progress_bits.load(Ordering::Relaxed))
+  }
+
+  pub fn message(&self) -> String
+  {
+    match self.inner.message.lock()
+    {
+      Ok(current) => current.clone(),
+      Err(_) => String::new(),
+    }
+  }
+
+  /// UI side: ask the worker to stop at its next check.
+  pub fn cancel(&self)
+  {
+    self.inner.cancel_requested.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_cancelled(&self) -> bool
+  {
+    self.inner.cancel_requested.load(Ordering::Relaxed)
+  }
+
+  /// Worker side: record an error. Still call finish() afterwards.
+  pub fn fail(&self, message: &str)
+  {
+    self.inner.failed.store(true, Ordering::Relaxed);
+    self.set_progress(self.progress(), message);
+  }
+
+  pub fn has_failed(&self) -> bool
+  {
+    self.inner.failed.load(Ordering::Relaxed)
+  }
+
+  /// Worker side: mark done, whether it succeeded, failed or was cancelled.
+  pub fn finish(&self)
+  {
+    self.inner.finished.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_finished(&self) -> bool
+  {
+    self.inner.finished.load(Ordering::Relaxed)
+  }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  TaskList
+//
+//  Every task started from the app, for the status bar.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+pub struct TaskList
+{
+  pub tasks: Vec<TaskHandle>,
+}
+
+impl TaskList
+{
+  /// Register a new task and return a handle for the worker. `retry`
+  /// starts the job again if it fails; None for jobs that cannot simply
+  /// be rerun.
+  pub fn start(&mut self, name: &str, retry: Option<Command>) -> TaskHandle
+  {
+    let mut handle = TaskHandle::new(name);
+    handle.retry = retry;
+    self.tasks.push(handle.clone());
+    handle
+  }
+
+  /// Drop finished tasks. Returns them so the caller can report outcomes.
+  pub fn take_finished(&mut self) -> Vec<TaskHandle>
+  {
+    let mut finished = Vec::new();
+    let mut running = Vec::new();
+
+    for task in self.tasks.drain(..)
+    {
+      if task.is_finished()
+      {
+        finished.push(task);
+      }
+      else
+      {
+        running.push(task);
+      }
+    }
+
+    self.tasks = running;
+    finished
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn finished_tasks_keep_their_retry_command()
+  {
+    let mut tasks = TaskList::default();
+    let bake = tasks.start("Bake", Some(Command::StartBake));
+    let import = tasks.start("Import", None);
+    bake.fail("out of disk");
+    bake.finish();
+    import.finish();
+
+    let finished = tasks.take_finished();
+    assert_eq!(finished[0].retry(), Some(&Command::StartBake));
+    assert_eq!(finished[1].retry(), None);
+    assert!(tasks.tasks.is_empty());
+  }
+}
@@ -1,13 +1,23 @@
 use std::time::{Duration, Instant};
 
+/// Longest step handed to motion code. A stall (window drag, breakpoint,
+/// device recovery) then costs a short pause instead of a jump.
+const MAX_MOTION_STEP: f32 = 0.1;
+
 pub struct TimeState
 {
   pub last_frame: Instant,
+  /// Real time since the last frame; the sim clock and watchdog use this.
   pub delta: Duration,
+  /// Seconds since the last frame, capped at MAX_MOTION_STEP, for cameras,
+  /// animations and UI motion.
   pub delta_f32: f32,
   pub total_time: Duration,
   pub frame_count: u64,
   pub fps: f32,
+  /// From the first input of a frame to that frame being presented. The
+  /// display adds its own scan-out delay on top.
+  pub input_latency: Duration,
 
   // For FPS averaging
   last_fps_update: Instant,
@@ -26,11 +36,19 @@ impl TimeState
       total_time: Duration::from_secs(0),
       frame_count: 0,
       fps: 0.0,
+      input_latency: Duration::ZERO,
       last_fps_update: now,
       frames_since_last_update: 0,
     }
   }
 
+  /// Forget the time spent idle, so the first frame after a pause is not
+  /// measured as one long frame.
+  pub fn restart(&mut self)
+  {
+    self.last_frame = Instant::now();
+  }
+
   /// Update the clock. Call this at the start of every frame.
   pub fn update(&mut self)
   {
@@ -39,7 +57,7 @@ impl TimeState
     self.last_frame = now;
 
     // Convenience float for math: 0.016 for 60fps
-    self.delta_f32 = self.delta.as_secs_f32();
+    self.delta_f32 = self.delta.as_secs_f32().min(MAX_MOTION_STEP);
     self.total_time += self.delta;
     self.frame_count += 1;
 
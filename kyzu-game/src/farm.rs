@@ -0,0 +1,384 @@
+use std::collections::VecDeque;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+
+use crate::core::config::KyzuConfig;
+use crate::core::log::{LogLevel, Logger};
+use crate::headless::{self, HeadlessOptions};
+use crate::render::capabilities::DEFAULT_MSAA_SAMPLES;
+use crate::render::kernel::Renderer;
+use crate::world::body::BodyManifest;
+use crate::world::manifest_loader;
+
+/// GPU memory per worker that doesn't scale with image size: the device,
+/// meshes, textures, pipelines, uniform buffers.
+const WORKER_BASE_BYTES: u64 = 64 * 1024 * 1024;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Render farm
+//
+//  `kyzu --farm <dir>` renders every `*.job` file in a directory with a pool
+//  of headless renderers, then exits. A job is JSON naming the image to
+//  write and, optionally, a single body manifest, the camera and settings:
+//
+//    { "output": "earth.png", "model": "earth.manifest",
+//      "width": 1920, "height": 1080, "lat_deg": 20, "distance_radii": 3 }
+//
+//  Paths are relative to the job directory and may not leave it. A finished job is renamed to
+//  `.done`, a failed one to `.failed` (the reason is in the log), so
+//  rerunning the farm only picks up new work.
+//
+//  Each worker owns one headless renderer (its own device) and reuses it
+//  for every job it takes. Workers are limited by the configured GPU memory
+//  budget divided by the footprint of the largest job, so a queue of 8K
+//  renders runs fewer at once than a queue of thumbnails.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FarmJob
+{
+  pub output: String,
+  /// A `.manifest` to render alone; the whole loaded world when None.
+  pub model: Option<String>,
+  pub width: u32,
+  pub height: u32,
+  pub focus: Option<String>,
+  pub lat_deg: f64,
+  pub lon_deg: f64,
+  pub distance_radii: f64,
+  /// Overrides the configured MSAA sample count.
+  pub msaa_samples: Option<u32>,
+}
+
+impl Default for FarmJob
+{
+  fn default() -> Self
+  {
+    let camera = HeadlessOptions::new(PathBuf::new(), 1920, 1080);
+    Self {
+      output: String::new(),
+      model: None,
+      width: camera.width,
+      height: camera.height,
+      focus: None,
+      lat_deg: camera.lat_deg,
+      lon_deg: camera.lon_deg,
+      distance_radii: camera.distance_radii,
+      msaa_samples: None,
+    }
+  }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FarmSummary
+{
+  pub done: usize,
+  pub failed: usize,
+}
+
+/// Rough GPU memory for a worker rendering one job: the fixed cost plus the
+/// job's resolved image, its readback copy and depth, and multisampled
+/// colour and depth when MSAA is on.
+pub fn job_footprint_bytes(width: u32, height: u32, samples: u32) -> u64
+{
+  let pixel_bytes = width as u64 * height as u64 * 4;
+  let mut targets = 3;
+  if samples > 1
+  {
+    targets += 2 * samples as u64;
+  }
+  WORKER_BASE_BYTES + pixel_bytes * targets
+}
+
+/// Workers that fit the memory budget, at least one and at most one per
+/// CPU thread or job.
+pub fn worker_count(budget_bytes: u64, footprint_bytes: u64, threads: usize, jobs: usize) -> usize
+{
+  let fit = (budget_bytes / footprint_bytes.max(1)) as usize;
+  fit.min(threads).min(jobs).max(1)
+}
+
+/// Render every job in `dir`. `manifests` is the loaded world, used by jobs
+/// without a model of their own.
+pub fn run(
+  config: &KyzuConfig,
+  manifests: Vec<BodyManifest>,
+  dir: &Path,
+  logger: &mut Logger,
+) -> anyhow::Result<FarmSummary>
+{
+  let mut summary = FarmSummary::default();
+  let mut queue = VecDeque::new();
+  for path in job_files(dir)?
+  {
+    let parsed = std::fs::read_to_string(&path)
+      .map_err(anyhow::Error::from)
+      .and_then(|text| serde_json::from_str::<FarmJob>(&text).map_err(anyhow::Error::from));
+    match parsed
+    {
+      Ok(job) => queue.push_back((path, job)),
+      Err(e) =>
+      {
+        logger.emit(LogLevel::Error, &format!("Bad job {}: {}", path.display(), e));
+        finish(&path, "failed");
+        summary.failed += 1;
+      }
+    }
+  }
+
+  if queue.is_empty()
+  {
+    logger.emit(LogLevel::Info, &format!("No jobs in {}", dir.display()));
+    return Ok(summary);
+  }
+
+  let mut largest = 0;
+  for (_, job) in &queue
+  {
+    // The adapter isn't known yet: budget for full-quality MSAA.
+    let samples = job.msaa_samples.or(config.app.msaa_samples).unwrap_or(DEFAULT_MSAA_SAMPLES);
+    largest = largest.max(job_footprint_bytes(job.width, job.height, samples));
+  }
+  let budget = config.app.farm_memory_mb * 1024 * 1024;
+  let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+  let workers = worker_count(budget, largest, threads, queue.len());
+  logger.emit(
+    LogLevel::Info,
+    &format!("Farm: {} jobs on {} workers ({} MB budget)", queue.len(), workers, budget >> 20),
+  );
+
+  let queue = Arc::new(Mutex::new(queue));
+  let manifests = Arc::new(manifests);
+  let results = Arc::new(Mutex::new(summary));
+
+  // Built up front so a machine without a usable adapter fails the farm
+  // rather than every job.
+  let mut renderers = Vec::new();
+  for _ in 0..workers
+  {
+    renderers.push(headless::create_renderer(config, logger)?);
+  }
+
+  let mut handles = Vec::new();
+  for mut renderer in renderers
+  {
+    let queue = queue.clone();
+    let manifests = manifests.clone();
+    let results = results.clone();
+    let config = config.clone();
+    let dir = dir.to_path_buf();
+
+    handles.push(std::thread::spawn(move || {
+      let mut logger = Logger::new(&config.app.log_filename);
+      loop
+      {
+        let next = queue.lock().unwrap().pop_front();
+        let (path, job) = match next
+        {
+          Some(n) => n,
+          None => break,
+        };
+
+        let outcome = render_job(&mut renderer, &config, &manifests, &dir, &job, &mut logger);
+        if renderer.is_lost()
+        {
+          match headless::create_renderer(&config, &mut logger)
+          {
+            Ok(fresh) => renderer = fresh,
+            Err(e) =>
+            {
+              logger.emit(LogLevel::Error, &format!("Farm worker lost its device: {}", e));
+              queue.lock().unwrap().push_front((path, job));
+              break;
+            }
+          }
+        }
+
+        let mut results = results.lock().unwrap();
+        match outcome
+        {
+          Ok(()) =>
+          {
+            finish(&path, "done");
+            results.done += 1;
+          }
+          Err(e) =>
+          {
+            logger.emit(LogLevel::Error, &format!("Job {} failed: {}", path.display(), e));
+            finish(&path, "failed");
+            results.failed += 1;
+          }
+        }
+      }
+    }));
+  }
+
+  for handle in handles
+  {
+    if handle.join().is_err()
+    {
+      logger.emit(LogLevel::Error, "A farm worker panicked");
+    }
+  }
+
+  // Jobs handed back by workers that lost their device and could not
+  // rebuild it, or left when every worker stopped.
+  let mut summary = *results.lock().unwrap();
+  fail_unrendered(&mut queue.lock().unwrap(), &mut summary, logger);
+  Ok(summary)
+}
+
+/// Mark every job still queued as failed.
+fn fail_unrendered(
+  queue: &mut VecDeque<(PathBuf, FarmJob)>,
+  summary: &mut FarmSummary,
+  logger: &mut Logger,
+)
+{
+  for (path, _) in queue.drain(..)
+  {
+    logger.emit(LogLevel::Error, &format!("Job {} was never rendered", path.display()));
+    finish(&path, "failed");
+    summary.failed += 1;
+  }
+}
+
+fn render_job(
+  renderer: &mut Renderer,
+  config: &KyzuConfig,
+  world: &[BodyManifest],
+  dir: &Path,
+  job: &FarmJob,
+  logger: &mut Logger,
+) -> anyhow::Result<()>
+{
+  if job.output.is_empty()
+  {
+    return Err(anyhow::anyhow!("no output file"));
+  }
+  let output = job_path(dir, &job.output)?;
+
+  let manifests = match &job.model
+  {
+    Some(model) => vec![manifest_loader::load_single_manifest(&job_path(dir, model)?)?],
+    None => world.to_vec(),
+  };
+
+  let mut config = config.clone();
+  if let Some(samples) = job.msaa_samples
+  {
+    config.app.msaa_samples = Some(samples);
+  }
+
+  let mut options = HeadlessOptions::new(output, job.width, job.height);
+  options.focus = job.focus.clone();
+  options.lat_deg = job.lat_deg;
+  options.lon_deg = job.lon_deg;
+  options.distance_radii = job.distance_radii;
+  headless::render(renderer, &config, manifests, &options, logger)
+}
+
+/// `name` under the job directory. Absolute paths and `..` are refused so a
+/// job cannot read or write outside it.
+fn job_path(dir: &Path, name: &str) -> anyhow::Result<PathBuf>
+{
+  let relative = Path::new(name);
+  let inside = relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+  if !inside
+  {
+    return Err(anyhow::anyhow!("{} is outside the job directory", name));
+  }
+  Ok(dir.join(relative))
+}
+
+/// `*.job` files in `dir`, in name order so queues run predictably.
+fn job_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>>
+{
+  let entries = std::fs::read_dir(dir)
+    .map_err(|e| anyhow::anyhow!("Cannot read job dir {}: {}", dir.display(), e))?;
+
+  let mut files = Vec::new();
+  for entry in entries
+  {
+    let path = entry?.path();
+    if path.extension().and_then(|e| e.to_str()) == Some("job")
+    {
+      files.push(path);
+    }
+  }
+  files.sort();
+  Ok(files)
+}
+
+/// Rename a job so the next run skips it.
+fn finish(path: &Path, state: &str)
+{
+  let _ = std::fs::rename(path, path.with_extension(state));
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn workers_fit_the_memory_budget()
+  {
+    let footprint = job_footprint_bytes(1920, 1080, 4);
+    assert_eq!(worker_count(footprint * 3, footprint, 16, 10), 3);
+    assert_eq!(worker_count(footprint * 3, footprint, 2, 10), 2);
+    assert_eq!(worker_count(footprint * 3, footprint, 16, 1), 1);
+    // A job bigger than the whole budget still runs, alone.
+    assert_eq!(worker_count(footprint / 2, footprint, 16, 10), 1);
+  }
+
+  #[test]
+  fn msaa_grows_the_footprint()
+  {
+    let plain = job_footprint_bytes(1024, 1024, 1);
+    assert_eq!(plain, WORKER_BASE_BYTES + 1024 * 1024 * 4 * 3);
+    assert!(job_footprint_bytes(1024, 1024, 4) > plain);
+  }
+
+  #[test]
+  fn jobs_fill_missing_fields_with_defaults()
+  {
+    let job: FarmJob = serde_json::from_str(r#"{ "output": "a.png", "width": 64 }"#).unwrap();
+    assert_eq!(job.output, "a.png");
+    assert_eq!((job.width, job.height), (64, 1080));
+    assert!(job.model.is_none());
+  }
+
+  #[test]
+  fn jobs_left_in_the_queue_count_as_failed()
+  {
+    let dir = std::env::temp_dir().join("kyzu_farm_leftovers");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("orphan.job");
+    std::fs::write(&path, "{}").unwrap();
+
+    let mut queue = VecDeque::from([(path.clone(), FarmJob::default())]);
+    let mut summary = FarmSummary { done: 2, failed: 0 };
+    let mut logger = Logger::new(dir.join("test.log").to_str().unwrap());
+    fail_unrendered(&mut queue, &mut summary, &mut logger);
+
+    assert!(queue.is_empty());
+    assert_eq!((summary.done, summary.failed), (2, 1));
+    assert!(!path.exists());
+    assert!(path.with_extension("failed").exists());
+  }
+
+  #[test]
+  fn job_paths_stay_in_the_job_directory()
+  {
+    let dir = Path::new("jobs");
+    assert_eq!(job_path(dir, "earth.png").unwrap(), dir.join("earth.png"));
+    assert_eq!(job_path(dir, "./renders/earth.png").unwrap(), dir.join("./renders/earth.png"));
+    assert!(job_path(dir, "../earth.png").is_err());
+    assert!(job_path(dir, "renders/../../earth.png").is_err());
+    assert!(job_path(dir, "/tmp/earth.png").is_err());
+  }
+}
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+
+use crate::bake::BakeManager;
+use crate::core::config::KyzuConfig;
+use crate::core::log::{LogLevel, Logger};
+use crate::core::palette::Palette;
+use crate::core::png;
+use crate::input::state::InputState;
+use crate::render::kernel::Renderer;
+use crate::render::modules::body_renderer::BodyRenderer;
+use crate::world::body::BodyManifest;
+use crate::world::registry::BodyRegistry;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Headless rendering
+//
+//  `kyzu --headless out.png` renders one frame of the loaded world without
+//  opening a window, writes it as a PNG and exits. For CI thumbnails and
+//  documentation renders; no event loop, UI or input is involved.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct HeadlessOptions
+{
+  pub output: PathBuf,
+  pub width: u32,
+  pub height: u32,
+  /// Body to orbit; the first loaded body when None.
+  pub focus: Option<String>,
+  pub lat_deg: f64,
+  pub lon_deg: f64,
+  /// Distance from the body centre, in body radii.
+  pub distance_radii: f64,
+}
+
+impl HeadlessOptions
+{
+  pub fn new(output: PathBuf, width: u32, height: u32) -> Self
+  {
+    Self { output, width, height, focus: None, lat_deg: 20.0, lon_deg: 0.0, distance_radii: 3.0 }
+  }
+}
+
+/// Render `manifests` as configured and save the image to `options.output`.
+pub fn run(
+  config: &KyzuConfig,
+  manifests: Vec<BodyManifest>,
+  options: &HeadlessOptions,
+  logger: &mut Logger,
+) -> anyhow::Result<()>
+{
+  let mut renderer = create_renderer(config, logger)?;
+  render(&mut renderer, config, manifests, options, logger)
+}
+
+/// A headless renderer set up from `config`, for any number of render()
+/// calls. render_to_texture() brings its own targets, so the default ones
+/// are kept at 1x1.
+pub fn create_renderer(config: &KyzuConfig, logger: &mut Logger) -> anyhow::Result<Renderer>
+{
+  let wanted = config.app.adapter.as_deref();
+  let mut renderer = pollster::block_on(Renderer::new_headless(1, 1, wanted))?;
+  logger.emit(LogLevel::Info, &renderer.shared.caps.summary());
+  renderer.shared.palette = Palette::from_config(&config.app.palette);
+  renderer.shared.show_axes = false;
+  renderer.shared.filtering = config.app.texture_filtering();
+  Ok(renderer)
+}
+
+/// Replace the renderer's scene with `manifests`, draw it and save the
+/// image to `options.output`.
+pub fn render(
+  renderer: &mut Renderer,
+  config: &KyzuConfig,
+  manifests: Vec<BodyManifest>,
+  options: &HeadlessOptions,
+  logger: &mut Logger,
+) -> anyhow::Result<()>
+{
+  let (width, height) = (options.width.max(1), options.height.max(1));
+
+  renderer.shared.body_registry = BodyRegistry::new();
+  for manifest in manifests
+  {
+    renderer.shared.body_registry.spawn(manifest, false);
+  }
+  renderer.shared.body_registry.apply_flags(&config.save.body_flags);
+
+  // Modules are built for the sample count and the bodies, so they are
+  // rebuilt for every scene.
+  renderer.clear_modules();
+  let (default_samples, _) = renderer.shared.caps.default_antialiasing();
+  let samples = renderer.set_msaa(config.app.msaa_samples.unwrap_or(default_samples));
+  logger.emit(LogLevel::Info, &format!("MSAA: {}x", samples));
+
+  let mesh_path = PathBuf::from(&config.app.data_dir).join("primitives").join("icosahedron.bake");
+  let mut body_renderer =
+    BodyRenderer::new(&renderer.device, &renderer.queue, &mut renderer.shared, &mesh_path, logger);
+  let bake_dir = BakeManager::new(config).output_root;
+  body_renderer.load_meshes(&renderer.device, &renderer.shared, &bake_dir, logger);
+  body_renderer.load_textures(
+    &renderer.device,
+    &renderer.queue,
+    &mut renderer.shared,
+    &bake_dir,
+    logger,
+  );
+  renderer.add_module(body_renderer);
+
+  aim_camera(renderer, options)?;
+  let mut input = InputState::new();
+  renderer.update(&mut input, 0.0)?;
+
+  let texture = renderer.render_to_texture(width, height);
+  let rgba = renderer.read_texture(&texture)?;
+  png::write_rgba(&options.output, width, height, &rgba)?;
+
+  logger.emit(LogLevel::Info, &format!("Rendered {}", options.output.display()));
+  Ok(())
+}
+
+/// Put the orbital camera around the focus body at the requested angle.
+fn aim_camera(renderer: &mut Renderer, options: &HeadlessOptions) -> anyhow::Result<()>
+{
+  let registry = &renderer.shared.body_registry;
+  let mut index = None;
+  if !registry.bodies.is_empty()
+  {
+    index = Some(0);
+  }
+  if let Some(name) = &options.focus
+  {
+    index = registry.find(name);
+  }
+
+  let body = match index
+  {
+    Some(i) => &registry.bodies[i],
+    None => return Err(anyhow::anyhow!("No body to render")),
+  };
+
+  let orbit = &mut renderer.camera_system.orbital_controller;
+  orbit.target = body.world_pos;
+  orbit.altitude = body.manifest.radius_m * options.distance_radii;
+  orbit.lat = options.lat_deg;
+  orbit.lon = options.lon_deg;
+  Ok(())
+}
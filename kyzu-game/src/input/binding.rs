@@ -0,0 +1,91 @@
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+
+use crate::command::Command;
+use crate::input::state::InputState;
+use crate::render::camera::ViewPreset;
+use crate::world::selection::SelectTool;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Key bindings
+//
+//  Maps a freshly pressed physical key (plus held modifiers) to a Command.
+//  Continuous controls (WASD flight, mouse look) read InputState directly and
+//  don't go through here.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn command_for_key(code: KeyCode, input: &InputState) -> Option<Command>
+{
+  let ctrl = input.is_key_down(KeyCode::ControlLeft) || input.is_key_down(KeyCode::ControlRight);
+
+  if ctrl
+  {
+    return ctrl_binding(code);
+  }
+
+  let alt = input.is_key_down(KeyCode::AltLeft) || input.is_key_down(KeyCode::AltRight);
+  if alt
+  {
+    return alt_binding(code);
+  }
+
+  match code
+  {
+    KeyCode::Escape => Some(Command::Exit),
+    KeyCode::Tab => Some(Command::ToggleCameraMode),
+    KeyCode::KeyH => Some(Command::LevelHorizon),
+    KeyCode::Home => Some(Command::ResetView),
+    KeyCode::Numpad7 => Some(Command::SetView(ViewPreset::Top)),
+    KeyCode::Numpad1 => Some(Command::SetView(ViewPreset::Front)),
+    KeyCode::Numpad3 => Some(Command::SetView(ViewPreset::Right)),
+    KeyCode::Numpad5 => Some(Command::SetView(ViewPreset::Iso)),
+    KeyCode::KeyB => Some(Command::SetSelectTool(SelectTool::Box)),
+    KeyCode::KeyL => Some(Command::SetSelectTool(SelectTool::Lasso)),
+    KeyCode::KeyP => Some(Command::SetSelectTool(SelectTool::Paint)),
+    KeyCode::KeyI => Some(Command::ToggleProbe),
+    KeyCode::F9 => Some(Command::ToggleMacroRecording),
+    KeyCode::F3 => Some(Command::ToggleTelemetry),
+    KeyCode::F5 => Some(Command::StartBake),
+    KeyCode::F6 => Some(Command::ToggleUvPanel),
+    KeyCode::F7 => Some(Command::ToggleSettings),
+    KeyCode::Backquote => Some(Command::ToggleLogPanel),
+    KeyCode::F10 => Some(Command::PlayMacro),
+    _ => None,
+  }
+}
+
+fn alt_binding(code: KeyCode) -> Option<Command>
+{
+  match code
+  {
+    KeyCode::ArrowLeft => Some(Command::ViewBack),
+    KeyCode::ArrowRight => Some(Command::ViewForward),
+    _ => None,
+  }
+}
+
+/// Extra mouse buttons (thumb buttons) that trigger commands on press.
+pub fn command_for_mouse_button(button: MouseButton) -> Option<Command>
+{
+  match button
+  {
+    MouseButton::Back => Some(Command::ViewBack),
+    MouseButton::Forward => Some(Command::ViewForward),
+    _ => None,
+  }
+}
+
+fn ctrl_binding(code: KeyCode) -> Option<Command>
+{
+  match code
+  {
+    KeyCode::KeyZ => Some(Command::Undo),
+    KeyCode::KeyY => Some(Command::Redo),
+    KeyCode::KeyF => Some(Command::OpenSearch),
+    // Ctrl flips the numpad views to the opposite side.
+    KeyCode::Numpad7 => Some(Command::SetView(ViewPreset::Bottom)),
+    KeyCode::Numpad1 => Some(Command::SetView(ViewPreset::Back)),
+    KeyCode::Numpad3 => Some(Command::SetView(ViewPreset::Left)),
+    _ => None,
+  }
+}
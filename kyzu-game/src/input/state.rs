@@ -1,17 +1,53 @@
 use std::collections::HashSet;
+use std::time::Instant;
 
 use glam::Vec2;
-use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event::{DeviceEvent, ElementState, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::keyboard::{KeyCode, PhysicalKey};
 
+use crate::world::selection::SelectMode;
+
+/// Pixels the cursor must travel with the left button held before a click
+/// becomes a marquee drag.
+const DRAG_THRESHOLD: f32 = 4.0;
+/// Minimum pixels between recorded lasso points.
+const PATH_SPACING: f32 = 2.0;
+/// Time constant for smoothing mouse_velocity, in seconds.
+const VELOCITY_SMOOTHING: f32 = 0.05;
+
+/// How a left press ended, from InputState::finish_drag.
+pub enum DragEnd
+{
+  Click,
+  /// (min, max) pixel corners.
+  Marquee(Vec2, Vec2),
+}
+
 pub struct InputState
 {
   // We use a HashSet for keys so we don't have to worry about array bounds
   pub keys_down: HashSet<KeyCode>,
   pub mouse_pos: Vec2,
+  /// Cursor travel since the last tick, summed over all events.
   pub mouse_delta: Vec2,
+  /// Smoothed look velocity in pixels per second; see track_velocity.
+  pub mouse_velocity: Vec2,
+  /// Raw pointer motion since the last tick (DeviceEvent::MouseMotion).
+  /// Unlike the cursor it keeps going at the window edge.
+  pub raw_mouse_delta: Vec2,
+  /// Set once the platform delivers raw motion; look_delta then uses it.
+  raw_motion_seen: bool,
+  /// Cursor position when a right-drag grabbed the pointer, restored on release.
+  pub look_grab: Option<Vec2>,
   pub mouse_buttons_down: HashSet<MouseButton>,
   pub scroll_delta: f32,
+  /// Where the left button went down, until the app takes it on release.
+  pub drag_origin: Option<Vec2>,
+  /// Cursor trail since the left button went down, for the lasso.
+  pub drag_path: Vec<Vec2>,
+  /// When the oldest input not yet drawn arrived. The app takes it after
+  /// presenting to measure input latency.
+  pub input_at: Option<Instant>,
 }
 
 impl InputState
@@ -22,8 +58,15 @@ impl InputState
       keys_down: HashSet::new(),
       mouse_pos: Vec2::ZERO,
       mouse_delta: Vec2::ZERO,
+      mouse_velocity: Vec2::ZERO,
+      raw_mouse_delta: Vec2::ZERO,
+      raw_motion_seen: false,
+      look_grab: None,
       mouse_buttons_down: HashSet::new(),
       scroll_delta: 0.0,
+      drag_origin: None,
+      drag_path: Vec::new(),
+      input_at: None,
     }
   }
 
@@ -31,12 +74,46 @@ impl InputState
   {
     let delta = self.mouse_delta;
     self.mouse_delta = glam::Vec2::ZERO;
+    self.raw_mouse_delta = glam::Vec2::ZERO;
     delta
   }
 
+  /// Motion for mouse look and orbit drags: raw pointer motion where the
+  /// platform provides it, otherwise cursor travel.
+  pub fn look_delta(&self) -> Vec2
+  {
+    if self.raw_motion_seen
+    {
+      return self.raw_mouse_delta;
+    }
+    self.mouse_delta
+  }
+
+  pub fn process_device_event(&mut self, event: &DeviceEvent)
+  {
+    if let DeviceEvent::MouseMotion { delta } = event
+    {
+      self.raw_mouse_delta += Vec2::new(delta.0 as f32, delta.1 as f32);
+      self.raw_motion_seen = true;
+      self.input_at.get_or_insert_with(Instant::now);
+    }
+  }
+
   /// The core Phase 1.4 logic: Update state from winit events
   pub fn process_event(&mut self, event: &WindowEvent)
   {
+    let is_input = matches!(
+      event,
+      WindowEvent::KeyboardInput { .. }
+        | WindowEvent::CursorMoved { .. }
+        | WindowEvent::MouseInput { .. }
+        | WindowEvent::MouseWheel { .. }
+    );
+    if is_input
+    {
+      self.input_at.get_or_insert_with(Instant::now);
+    }
+
     match event
     {
       WindowEvent::KeyboardInput { event: key_event, .. } =>
@@ -56,14 +133,21 @@ impl InputState
       WindowEvent::CursorMoved { position, .. } =>
       {
         let new_pos = Vec2::new(position.x as f32, position.y as f32);
-        self.mouse_delta = new_pos - self.mouse_pos;
+        self.mouse_delta += new_pos - self.mouse_pos;
         self.mouse_pos = new_pos;
+        self.extend_drag_path();
       }
       WindowEvent::MouseInput { state, button, .. } =>
       {
         if *state == ElementState::Pressed
         {
           self.mouse_buttons_down.insert(*button);
+          if *button == MouseButton::Left
+          {
+            self.drag_origin = Some(self.mouse_pos);
+            self.drag_path.clear();
+            self.drag_path.push(self.mouse_pos);
+          }
         }
         else
         {
@@ -84,11 +168,91 @@ impl InputState
   pub fn tick(&mut self)
   {
     self.mouse_delta = Vec2::ZERO;
+    self.raw_mouse_delta = Vec2::ZERO;
     self.scroll_delta = 0.0;
   }
 
+  /// Fold this frame's look_delta into mouse_velocity. Call once per
+  /// frame before the delta is consumed.
+  pub fn track_velocity(&mut self, dt: f32)
+  {
+    if dt <= 0.0
+    {
+      return;
+    }
+    let blend = 1.0 - (-dt / VELOCITY_SMOOTHING).exp();
+    self.mouse_velocity = self.mouse_velocity.lerp(self.look_delta() / dt, blend);
+  }
+
+  /// True while any key or mouse button is held; held input moves the
+  /// camera every frame.
+  pub fn is_held(&self) -> bool
+  {
+    !self.keys_down.is_empty() || !self.mouse_buttons_down.is_empty()
+  }
+
   pub fn is_key_down(&self, code: KeyCode) -> bool
   {
     self.keys_down.contains(&code)
   }
+
+  /// Append the cursor to the lasso trail while the left button is held,
+  /// skipping sub-pixel jitter.
+  fn extend_drag_path(&mut self)
+  {
+    if self.drag_origin.is_none() || !self.mouse_buttons_down.contains(&MouseButton::Left)
+    {
+      return;
+    }
+    if let Some(last) = self.drag_path.last()
+    {
+      if last.distance(self.mouse_pos) < PATH_SPACING
+      {
+        return;
+      }
+    }
+    self.drag_path.push(self.mouse_pos);
+  }
+
+  /// Selection modifier: Shift adds, Ctrl subtracts, neither replaces.
+  pub fn select_mode(&self) -> SelectMode
+  {
+    if self.is_key_down(KeyCode::ControlLeft) || self.is_key_down(KeyCode::ControlRight)
+    {
+      return SelectMode::Subtract;
+    }
+    if self.is_key_down(KeyCode::ShiftLeft) || self.is_key_down(KeyCode::ShiftRight)
+    {
+      return SelectMode::Add;
+    }
+    SelectMode::Replace
+  }
+
+  /// Current marquee as (min, max) pixel corners, while the left button is
+  /// held and the cursor has moved past the click threshold.
+  pub fn marquee(&self) -> Option<(Vec2, Vec2)>
+  {
+    if !self.mouse_buttons_down.contains(&MouseButton::Left)
+    {
+      return None;
+    }
+    let origin = self.drag_origin?;
+    if origin.distance(self.mouse_pos) < DRAG_THRESHOLD
+    {
+      return None;
+    }
+    Some((origin.min(self.mouse_pos), origin.max(self.mouse_pos)))
+  }
+
+  /// On left release: what the press turned into. None if the press was
+  /// never seen (e.g. egui claimed it).
+  pub fn finish_drag(&mut self) -> Option<DragEnd>
+  {
+    let origin = self.drag_origin.take()?;
+    if origin.distance(self.mouse_pos) < DRAG_THRESHOLD
+    {
+      return Some(DragEnd::Click);
+    }
+    Some(DragEnd::Marquee(origin.min(self.mouse_pos), origin.max(self.mouse_pos)))
+  }
 }
@@ -1,7 +1,11 @@
 pub mod app;
 pub mod bake;
+pub mod command;
 pub mod core;
+pub mod farm;
+pub mod headless;
 pub mod input;
+pub mod mirror;
 pub mod render;
 pub mod ui;
 pub mod world;
@@ -1,7 +1,11 @@
 use kyzu::app::App;
 use kyzu::bake::BakeManager;
+use kyzu::command::script;
 use kyzu::core::config;
 use kyzu::core::log::{LogLevel, Logger};
+use kyzu::core::task::TaskHandle;
+use kyzu::farm;
+use kyzu::headless::{self, HeadlessOptions};
 use kyzu::world::manifest_loader::load_all_manifests;
 use winit::event_loop::{ControlFlow, EventLoop};
 
@@ -27,7 +31,8 @@ fn main()
   if args.contains(&"--bake".to_string())
   {
     logger.emit(LogLevel::Info, "Baking sol_system world data...");
-    bake_manager.start_bake(&mut logger);
+    let task = TaskHandle::new("Bake");
+    bake_manager.start_bake(&mut logger, &task);
   }
 
   // 3. Load manifests from disk — always, regardless of --bake.
@@ -43,16 +48,113 @@ fn main()
     }
   };
 
-  // 4. Create app — manifests are moved into SharedState when the renderer
+  // 4. Headless: render one image and exit without opening a window.
+  if let Some(output) = arg_value(&args, "--headless")
+  {
+    let options = headless_options(&args, output, &config);
+    match headless::run(&config, manifests, &options, &mut logger)
+    {
+      Ok(()) => std::process::exit(0),
+      Err(e) =>
+      {
+        logger.emit(LogLevel::Error, &format!("Headless render failed: {}", e));
+        eprintln!("[FATAL] Headless render failed: {}", e);
+        std::process::exit(1);
+      }
+    }
+  }
+
+  // 4b. Farm: render every job file in a directory, then exit.
+  if let Some(dir) = arg_value(&args, "--farm")
+  {
+    match farm::run(&config, manifests, std::path::Path::new(dir), &mut logger)
+    {
+      Ok(summary) =>
+      {
+        let message = format!("Farm finished: {} done, {} failed", summary.done, summary.failed);
+        logger.emit(LogLevel::Info, &message);
+        println!("{}", message);
+        let mut code = 0;
+        if summary.failed > 0
+        {
+          code = 1;
+        }
+        std::process::exit(code);
+      }
+      Err(e) =>
+      {
+        logger.emit(LogLevel::Error, &format!("Farm failed: {}", e));
+        eprintln!("[FATAL] Farm failed: {}", e);
+        std::process::exit(1);
+      }
+    }
+  }
+
+  // 5. Create app — manifests are moved into SharedState when the renderer
   //    initialises inside resumed().
   let mut app = App::new(config, logger, manifests);
+  app.batch = args.contains(&"--batch".to_string());
+
+  // 6. Startup script: a bad script is fatal, since batch runs depend on it.
+  if let Some(path) = arg_value(&args, "--script")
+  {
+    match script::load(std::path::Path::new(path))
+    {
+      Ok(commands) => app.startup_script = commands,
+      Err(e) =>
+      {
+        app.logger.emit(LogLevel::Error, &format!("Script error: {}", e));
+        eprintln!("[FATAL] Script error: {}", e);
+        std::process::exit(1);
+      }
+    }
+  }
 
-  // 5. Run event loop
+  // 7. Run event loop
   let event_loop = EventLoop::new().expect("Failed to create event loop");
-  event_loop.set_control_flow(ControlFlow::Poll);
+  event_loop.set_control_flow(ControlFlow::Wait);
 
   if let Err(e) = event_loop.run_app(&mut app)
   {
     app.logger.emit(LogLevel::Info, &format!("Application error: {}", e));
   }
 }
+
+/// Image size and camera for --headless: "--size 1920x1080", "--focus
+/// earth", "--lat 20", "--lon 0", "--distance 3" (body radii). The size
+/// defaults to the configured window size.
+fn headless_options(args: &[String], output: &str, config: &config::KyzuConfig) -> HeadlessOptions
+{
+  let mut options =
+    HeadlessOptions::new(output.into(), config.app.window_width, config.app.window_height);
+
+  if let Some((w, h)) = arg_value(args, "--size").and_then(|s| s.split_once('x'))
+  {
+    if let (Ok(w), Ok(h)) = (w.parse(), h.parse())
+    {
+      options.width = w;
+      options.height = h;
+    }
+  }
+  options.focus = arg_value(args, "--focus").cloned();
+  if let Some(lat) = arg_value(args, "--lat").and_then(|s| s.parse().ok())
+  {
+    options.lat_deg = lat;
+  }
+  if let Some(lon) = arg_value(args, "--lon").and_then(|s| s.parse().ok())
+  {
+    options.lon_deg = lon;
+  }
+  if let Some(distance) = arg_value(args, "--distance").and_then(|s| s.parse().ok())
+  {
+    options.distance_radii = distance;
+  }
+  options
+}
+
+/// The argument after `flag`, e.g. the path in "--script path".
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a String>
+{
+  let position = args.iter().position(|a| a == flag)?;
+  args.get(position + 1)
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use winit::window::Window;
+
+use crate::core::config::MirrorWindowConfig;
+use crate::input::state::InputState;
+use crate::render::kernel::Renderer;
+use crate::render::modules::body_renderer::BodyRenderer;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Mirror windows
+//
+//  Extra windows showing the main view, each with its own device on the
+//  adapter its config names, so a wall display can run from a second GPU.
+//  Nothing is shared between devices: before every frame the main
+//  renderer's CPU-side scene and camera pose are copied across, and each
+//  device keeps its own meshes, textures and pipelines.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct MirrorWindow
+{
+  pub window: Arc<Window>,
+  pub renderer: Renderer,
+  pub config: MirrorWindowConfig,
+}
+
+impl MirrorWindow
+{
+  /// Copy the scene and view from `main`; see sync_scene.
+  pub fn sync(&mut self, main: &Renderer)
+  {
+    sync_scene(main, &mut self.renderer);
+  }
+
+  /// Draw one frame of the synced scene, without UI.
+  pub fn draw(&mut self) -> anyhow::Result<()>
+  {
+    // No input reaches a mirror; the update only rebuilds the matrices
+    // for the followed pose at this window's aspect.
+    self.renderer.update(&mut InputState::new(), 0.0)?;
+    self.renderer.render(None)
+  }
+}
+
+/// Copy the CPU-side scene and camera pose from `from` into `to`, which
+/// may be on another device. Bodies added since the last copy get slots
+/// in `to`'s body renderer.
+pub fn sync_scene(from: &Renderer, to: &mut Renderer)
+{
+  let source = &from.shared;
+  let target = &mut to.shared;
+  let added = source.body_registry.bodies.len() > target.body_registry.bodies.len();
+
+  target.body_registry = source.body_registry.clone();
+  target.selected = source.selected.clone();
+  target.display_mode = source.display_mode;
+  target.field = source.field;
+  target.show_axes = source.show_axes;
+  target.palette = source.palette;
+  target.sim_alpha = source.sim_alpha;
+
+  if added
+  {
+    to.with_module(|bodies: &mut BodyRenderer, device, shared| bodies.add_bodies(device, shared));
+  }
+
+  let pose = from.camera_system.pose(source.mode);
+  to.camera_system.follow(&mut to.shared, &pose);
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+  use crate::render::kernel::test_renderer;
+  use crate::render::shared::CameraMode;
+  use crate::world::body::{BodyKind, BodyManifest};
+
+  #[test]
+  fn mirror_copies_the_scene_and_follows_the_camera()
+  {
+    let (mut main, mut mirror) = match (test_renderer(), test_renderer())
+    {
+      (Some(main), Some(mirror)) => (main, mirror),
+      _ => return,
+    };
+    main.shared.body_registry.spawn(
+      BodyManifest {
+        name: "Moon".to_string(),
+        kind: BodyKind::SmallBody { base_color: [0.5; 3] },
+        radius_m: 1000.0,
+        lod_max: 0,
+        position_at_epoch: glam::DVec3::X * 5000.0,
+        orbital_elements: None,
+        axial_tilt_rad: 0.0,
+        rotation_period_s: 0.0,
+      },
+      false,
+    );
+    main.shared.selected = vec![0];
+    main.shared.mode = CameraMode::Free;
+    main.camera_system.free_controller.yaw = 1.0;
+
+    sync_scene(&main, &mut mirror);
+    assert_eq!(mirror.shared.body_registry.bodies.len(), 1);
+    assert_eq!(mirror.shared.selected, vec![0]);
+    assert_eq!(mirror.shared.mode, CameraMode::Free);
+    assert_eq!(
+      mirror.camera_system.pose(CameraMode::Free),
+      main.camera_system.pose(CameraMode::Free)
+    );
+  }
+}
@@ -0,0 +1,123 @@
+use crate::render::camera::history::CameraPose;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Camera animator
+//
+//  Eases the camera between two poses instead of jumping: view presets,
+//  framing a body and history steps all go through here. CameraSystem
+//  advances it each frame and applies the blended pose.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Seconds for a camera move to complete.
+const ANIMATION_SECONDS: f32 = 0.3;
+
+#[derive(Default)]
+pub struct CameraAnimator
+{
+  from: Option<CameraPose>,
+  to: Option<CameraPose>,
+  elapsed: f32,
+}
+
+impl CameraAnimator
+{
+  pub fn start(&mut self, from: CameraPose, to: CameraPose)
+  {
+    self.from = Some(from);
+    self.to = Some(to);
+    self.elapsed = 0.0;
+  }
+
+  pub fn cancel(&mut self)
+  {
+    self.from = None;
+    self.to = None;
+  }
+
+  pub fn is_active(&self) -> bool
+  {
+    self.to.is_some()
+  }
+
+  /// Advance by `dt` and return the pose to show, or None when idle. The
+  /// last step returns exactly the destination and stops the animation.
+  pub fn step(&mut self, dt: f32) -> Option<CameraPose>
+  {
+    let (from, to) = match (self.from, self.to)
+    {
+      (Some(from), Some(to)) => (from, to),
+      _ => return None,
+    };
+
+    self.elapsed += dt;
+    let t = (self.elapsed / ANIMATION_SECONDS).min(1.0) as f64;
+    if t >= 1.0
+    {
+      self.cancel();
+      return Some(to);
+    }
+
+    Some(from.lerp(&to, ease_in_out(t)))
+  }
+}
+
+/// Smoothstep: starts and ends at rest.
+fn ease_in_out(t: f64) -> f64
+{
+  t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests
+{
+  use glam::DVec3;
+
+  use super::*;
+  use crate::render::shared::CameraMode;
+
+  fn pose(lat: f64, altitude: f64) -> CameraPose
+  {
+    CameraPose {
+      mode: CameraMode::Orbital,
+      target: DVec3::ZERO,
+      lat,
+      lon: 0.0,
+      altitude,
+      orbit_roll: 0.0,
+      position: DVec3::ZERO,
+      yaw: 0.0,
+      pitch: 0.0,
+      roll: 0.0,
+    }
+  }
+
+  #[test]
+  fn lands_exactly_on_the_destination()
+  {
+    let mut animator = CameraAnimator::default();
+    animator.start(pose(0.0, 1.0e9), pose(90.0, 2.0e9));
+
+    let mut last = None;
+    while animator.is_active()
+    {
+      last = animator.step(0.016);
+    }
+
+    assert_eq!(last, Some(pose(90.0, 2.0e9)));
+    assert_eq!(animator.step(0.016), None);
+  }
+
+  #[test]
+  fn eases_in_and_out()
+  {
+    let mut animator = CameraAnimator::default();
+    animator.start(pose(0.0, 1.0e9), pose(90.0, 1.0e9));
+
+    let early = animator.step(ANIMATION_SECONDS * 0.1).unwrap();
+    let middle = animator.step(ANIMATION_SECONDS * 0.4).unwrap();
+
+    // Slow start, halfway at the midpoint.
+    assert!(early.lat < 9.0);
+    assert!((middle.lat - 45.0).abs() < 1e-6);
+  }
+}
@@ -1,6 +1,7 @@
 use glam::{DVec3, EulerRot, Quat, Vec3};
 use winit::keyboard::KeyCode;
 
+use super::projection::{fit_clip_planes, Projection, RENDER_SCALE};
 use super::CameraController;
 use crate::input::state::InputState;
 use crate::render::shared::SharedState;
@@ -10,6 +11,10 @@ pub struct FreeController
   pub position: DVec3,
   pub yaw: f32,
   pub pitch: f32,
+  /// Rotation about the view axis, radians. Q/E while flying.
+  pub roll: f32,
+  /// Set by the level-horizon command; roll eases back to zero.
+  pub leveling: bool,
   pub speed_gear: i32, // gear multiplier: each Shift+scroll notch = 2x/0.5x WASD speed
   pub sensitivity: f32,
   pub fov: f32,
@@ -25,6 +30,8 @@ impl Default for FreeController
       position: glam::DVec3::new(0.0, 0.0, 15_000_000.0),
       yaw: -90.0f32.to_radians(),
       pitch: 0.0,
+      roll: 0.0,
+      leveling: false,
       speed_gear: 0,
       sensitivity: 0.1,
       fov: 45.0,
@@ -46,6 +53,69 @@ fn gear_multiplier(gear: i32) -> f64
   2.0_f64.powi(gear)
 }
 
+const ROLL_SPEED: f32 = 1.2; // radians per second while Q/E is held
+const LEVEL_SPEED: f32 = 2.5; // radians per second when levelling the horizon
+
+impl FreeController
+{
+  /// Right-drag: mouse pixels to yaw/pitch.
+  pub fn apply_look(&mut self, delta: glam::Vec2)
+  {
+    let sensitivity_scale = 0.005;
+    self.yaw -= delta.x * self.sensitivity * sensitivity_scale;
+    self.pitch -= delta.y * self.sensitivity * sensitivity_scale;
+    self.pitch = self.pitch.clamp(-1.5, 1.5);
+  }
+
+  fn update_roll(&mut self, input: &InputState, dt: f32)
+  {
+    step_roll(&mut self.roll, &mut self.leveling, input, dt);
+  }
+}
+
+/// Q/E roll while held; any manual roll cancels an in-progress level.
+/// Shared with the orbital camera.
+pub(super) fn step_roll(roll: &mut f32, leveling: &mut bool, input: &InputState, dt: f32)
+{
+  let mut roll_input = 0.0;
+  if input.is_key_down(KeyCode::KeyQ)
+  {
+    roll_input += 1.0;
+  }
+  if input.is_key_down(KeyCode::KeyE)
+  {
+    roll_input -= 1.0;
+  }
+
+  if roll_input != 0.0
+  {
+    *leveling = false;
+    *roll = wrap_angle(*roll + roll_input * ROLL_SPEED * dt);
+    return;
+  }
+
+  if *leveling
+  {
+    let step = LEVEL_SPEED * dt;
+    if roll.abs() <= step
+    {
+      *roll = 0.0;
+      *leveling = false;
+    }
+    else
+    {
+      *roll -= step * roll.signum();
+    }
+  }
+}
+
+/// Wrap to [-PI, PI] so levelling always takes the short way round.
+fn wrap_angle(angle: f32) -> f32
+{
+  let tau = std::f32::consts::TAU;
+  (angle + std::f32::consts::PI).rem_euclid(tau) - std::f32::consts::PI
+}
+
 impl CameraController for FreeController
 {
   fn update(&mut self, shared: &mut SharedState, input: &mut InputState, dt: f32)
@@ -53,15 +123,13 @@ impl CameraController for FreeController
     // --- 1. HANDLE INPUT (Rotation) ---
     if input.mouse_buttons_down.contains(&winit::event::MouseButton::Right)
     {
-      let sensitivity_scale = 0.005;
-      let delta = input.mouse_delta;
-      self.yaw -= delta.x * self.sensitivity * sensitivity_scale;
-      self.pitch -= delta.y * self.sensitivity * sensitivity_scale;
-      self.pitch = self.pitch.clamp(-1.5, 1.5);
+      self.apply_look(input.look_delta());
     }
 
+    self.update_roll(input, dt);
+
     // --- 2. Build direction vectors ---
-    let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
+    let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, self.roll);
     let forward = rotation * -Vec3::Z;
     let right = rotation * Vec3::X;
     let up = rotation * Vec3::Y;
@@ -127,9 +195,20 @@ impl CameraController for FreeController
     shared.eye_world = self.position;
 
     let view_rel = glam::Mat4::look_to_rh(Vec3::ZERO, forward, up);
+    // Near/far are fitted to the bodies in render units; the configured
+    // planes (metres) are the fallback for an empty registry.
     let aspect = shared.screen_width as f32 / shared.screen_height as f32;
-    let proj = glam::Mat4::perspective_rh(self.fov.to_radians(), aspect, self.z_near, self.z_far);
-    let view_proj = proj * view_rel;
+    let mut z_near = (self.z_near as f64 / RENDER_SCALE) as f32;
+    let mut z_far = (self.z_far as f64 / RENDER_SCALE) as f32;
+    if let Some((near, far)) = fit_clip_planes(self.position, &shared.body_registry)
+    {
+      z_near = near;
+      z_far = far;
+    }
+    shared.projection = Projection { fov_y_rad: self.fov.to_radians(), aspect, z_near, z_far };
+    shared.focus_distance = (shared.target_body_pos - self.position).length();
+
+    let view_proj = shared.projection.matrix() * view_rel;
 
     shared.camera.view_proj = view_proj.to_cols_array_2d();
     shared.camera.inv_view_proj = view_proj.inverse().to_cols_array_2d();
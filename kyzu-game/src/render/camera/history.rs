@@ -0,0 +1,248 @@
+use glam::DVec3;
+
+use crate::render::shared::CameraMode;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Camera history
+//
+//  Browser-style back/forward for the viewport. A pose is recorded once the
+//  camera has been still for SETTLE_SECONDS after moving; stepping back and
+//  then moving somewhere new drops the forward entries.
+// ─────────────────────────────────────────────────────────────────────────────
+
+const SETTLE_SECONDS: f32 = 0.6;
+const MAX_POSES: usize = 64;
+
+/// Everything needed to put both controllers back where they were.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose
+{
+  pub mode: CameraMode,
+  // Orbital
+  pub target: DVec3,
+  pub lat: f64,
+  pub lon: f64,
+  pub altitude: f64,
+  pub orbit_roll: f32,
+  // Free
+  pub position: DVec3,
+  pub yaw: f32,
+  pub pitch: f32,
+  pub roll: f32,
+}
+
+impl CameraPose
+{
+  /// Blend towards `to`. Angles take the short way round and altitude is
+  /// blended logarithmically so large zooms move at an even pace.
+  pub fn lerp(&self, to: &CameraPose, t: f64) -> CameraPose
+  {
+    let tf = t as f32;
+    CameraPose {
+      mode: to.mode,
+      target: self.target.lerp(to.target, t),
+      lat: self.lat + wrap_degrees(to.lat - self.lat) * t,
+      lon: self.lon + wrap_degrees(to.lon - self.lon) * t,
+      altitude: (self.altitude.ln() + (to.altitude.ln() - self.altitude.ln()) * t).exp(),
+      orbit_roll: self.orbit_roll + wrap_radians(to.orbit_roll - self.orbit_roll) * tf,
+      position: self.position.lerp(to.position, t),
+      yaw: self.yaw + wrap_radians(to.yaw - self.yaw) * tf,
+      pitch: self.pitch + (to.pitch - self.pitch) * tf,
+      roll: self.roll + wrap_radians(to.roll - self.roll) * tf,
+    }
+  }
+}
+
+fn wrap_degrees(delta: f64) -> f64
+{
+  (delta + 180.0).rem_euclid(360.0) - 180.0
+}
+
+fn wrap_radians(delta: f32) -> f32
+{
+  let pi = std::f32::consts::PI;
+  (delta + pi).rem_euclid(std::f32::consts::TAU) - pi
+}
+
+#[derive(Default)]
+pub struct CameraHistory
+{
+  poses: Vec<CameraPose>,
+  /// Index of the pose currently shown.
+  cursor: usize,
+  /// Pose seen last frame, to detect movement.
+  last_seen: Option<CameraPose>,
+  still_time: f32,
+  moving: bool,
+}
+
+impl CameraHistory
+{
+  /// Call every frame with the current pose. Records it once the camera
+  /// settles after moving.
+  pub fn observe(&mut self, pose: CameraPose, dt: f32)
+  {
+    if self.poses.is_empty()
+    {
+      self.poses.push(pose);
+    }
+
+    if self.last_seen != Some(pose)
+    {
+      self.last_seen = Some(pose);
+      self.still_time = 0.0;
+      self.moving = true;
+      return;
+    }
+
+    self.still_time += dt;
+    if self.moving && self.still_time >= SETTLE_SECONDS
+    {
+      self.moving = false;
+      self.record(pose);
+    }
+  }
+
+  /// True from the camera moving until its pose is recorded. Frames must
+  /// keep coming meanwhile, since only observe() counts the still time.
+  pub fn is_settling(&self) -> bool
+  {
+    self.moving
+  }
+
+  fn record(&mut self, pose: CameraPose)
+  {
+    if self.poses[self.cursor] == pose
+    {
+      return;
+    }
+
+    self.poses.truncate(self.cursor + 1);
+    self.poses.push(pose);
+    if self.poses.len() > MAX_POSES
+    {
+      self.poses.remove(0);
+    }
+    self.cursor = self.poses.len() - 1;
+  }
+
+  pub fn back(&mut self) -> Option<CameraPose>
+  {
+    if self.cursor == 0
+    {
+      return None;
+    }
+    self.cursor -= 1;
+    self.arrive()
+  }
+
+  pub fn forward(&mut self) -> Option<CameraPose>
+  {
+    if self.cursor + 1 >= self.poses.len()
+    {
+      return None;
+    }
+    self.cursor += 1;
+    self.arrive()
+  }
+
+  /// The pose at the cursor, marked as already seen so landing on it
+  /// doesn't record it again.
+  fn arrive(&mut self) -> Option<CameraPose>
+  {
+    let pose = self.poses[self.cursor];
+    self.last_seen = Some(pose);
+    self.moving = false;
+    Some(pose)
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  fn pose(lon: f64) -> CameraPose
+  {
+    CameraPose {
+      mode: CameraMode::Orbital,
+      target: DVec3::ZERO,
+      lat: 0.0,
+      lon,
+      altitude: 1.0e9,
+      orbit_roll: 0.0,
+      position: DVec3::ZERO,
+      yaw: 0.0,
+      pitch: 0.0,
+      roll: 0.0,
+    }
+  }
+
+  /// Hold `p` long enough to be recorded.
+  fn settle(history: &mut CameraHistory, p: CameraPose)
+  {
+    history.observe(p, 0.016);
+    history.observe(p, SETTLE_SECONDS);
+  }
+
+  #[test]
+  fn back_and_forward_step_through_settled_poses()
+  {
+    let mut history = CameraHistory::default();
+    settle(&mut history, pose(0.0));
+    settle(&mut history, pose(10.0));
+    settle(&mut history, pose(20.0));
+
+    assert_eq!(history.back(), Some(pose(10.0)));
+    assert_eq!(history.back(), Some(pose(0.0)));
+    assert_eq!(history.back(), None);
+    assert_eq!(history.forward(), Some(pose(10.0)));
+  }
+
+  #[test]
+  fn moving_after_back_drops_forward_entries()
+  {
+    let mut history = CameraHistory::default();
+    settle(&mut history, pose(0.0));
+    settle(&mut history, pose(10.0));
+    history.back();
+    settle(&mut history, pose(30.0));
+
+    assert_eq!(history.forward(), None);
+    assert_eq!(history.back(), Some(pose(0.0)));
+  }
+
+  #[test]
+  fn unsettled_motion_is_not_recorded()
+  {
+    let mut history = CameraHistory::default();
+    settle(&mut history, pose(0.0));
+    history.observe(pose(5.0), 0.016);
+    history.observe(pose(6.0), 0.016);
+
+    assert_eq!(history.back(), None);
+  }
+
+  #[test]
+  fn settling_lasts_until_the_pose_is_recorded()
+  {
+    let mut history = CameraHistory::default();
+    settle(&mut history, pose(0.0));
+    assert!(!history.is_settling());
+
+    history.observe(pose(5.0), 0.016);
+    assert!(history.is_settling());
+    history.observe(pose(5.0), SETTLE_SECONDS * 0.5);
+    assert!(history.is_settling());
+    history.observe(pose(5.0), SETTLE_SECONDS * 0.5);
+    assert!(!history.is_settling());
+    assert_eq!(history.back(), Some(pose(0.0)));
+  }
+
+  #[test]
+  fn lerp_takes_short_way_round()
+  {
+    let halfway = pose(170.0).lerp(&pose(-170.0), 0.5);
+    assert!((halfway.lon - 180.0).abs() < 1e-9);
+  }
+}
@@ -0,0 +1,171 @@
+use glam::Vec2;
+
+use crate::core::config::InertiaConfig;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Camera inertia
+//
+//  Optional momentum for right-drag look/orbit and for orbital panning.
+//  While dragging (or panning), the velocity is remembered; after release
+//  the camera keeps moving at that rate, decaying exponentially.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Below this speed (pixels per second) coasting stops.
+const MIN_SPEED: f32 = 5.0;
+/// Below this pan speed (altitudes per second) the target stops gliding.
+const MIN_PAN_SPEED: f32 = 0.01;
+
+pub struct Inertia
+{
+  pub enabled: bool,
+  /// Time for the coasting speed to fall to 1/e.
+  pub decay_seconds: f32,
+  /// Coasting velocity in pixels per second.
+  velocity: Vec2,
+  /// Coasting pan of the orbit target, in altitudes per second along the
+  /// view's right and up.
+  pan_velocity: Vec2,
+}
+
+impl Default for Inertia
+{
+  fn default() -> Self
+  {
+    Self::new(&InertiaConfig::default())
+  }
+}
+
+impl Inertia
+{
+  pub fn new(config: &InertiaConfig) -> Self
+  {
+    Self {
+      enabled: config.enabled,
+      decay_seconds: config.decay_seconds,
+      velocity: Vec2::ZERO,
+      pan_velocity: Vec2::ZERO,
+    }
+  }
+
+  pub fn stop(&mut self)
+  {
+    self.velocity = Vec2::ZERO;
+    self.pan_velocity = Vec2::ZERO;
+  }
+
+  pub fn is_coasting(&self) -> bool
+  {
+    self.enabled
+      && (self.velocity.length() >= MIN_SPEED || self.pan_velocity.length() >= MIN_PAN_SPEED)
+  }
+
+  /// Call once per frame. While dragging this tracks `drag_velocity`;
+  /// afterwards it returns the pixel delta to apply this frame, if any.
+  pub fn coast(&mut self, dragging: bool, drag_velocity: Vec2, dt: f32) -> Option<Vec2>
+  {
+    if !self.enabled
+    {
+      return None;
+    }
+    if dragging
+    {
+      self.velocity = drag_velocity;
+      return None;
+    }
+    glide(&mut self.velocity, MIN_SPEED, self.decay_seconds, dt)
+  }
+
+  /// Pan counterpart of coast(): while panning this tracks `pan_velocity`
+  /// (altitudes per second); afterwards it returns the pan for this frame.
+  pub fn coast_pan(&mut self, panning: bool, pan_velocity: Vec2, dt: f32) -> Option<Vec2>
+  {
+    if !self.enabled
+    {
+      return None;
+    }
+    if panning
+    {
+      self.pan_velocity = pan_velocity;
+      return None;
+    }
+    glide(&mut self.pan_velocity, MIN_PAN_SPEED, self.decay_seconds, dt)
+  }
+}
+
+/// One frame of coasting at `velocity`, which then decays. None (and the
+/// velocity cleared) once it is below `min_speed`.
+fn glide(velocity: &mut Vec2, min_speed: f32, decay_seconds: f32, dt: f32) -> Option<Vec2>
+{
+  if velocity.length() < min_speed
+  {
+    *velocity = Vec2::ZERO;
+    return None;
+  }
+
+  let step = *velocity * dt;
+  *velocity *= (-dt / decay_seconds.max(0.01)).exp();
+  Some(step)
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  fn enabled() -> Inertia
+  {
+    Inertia::new(&InertiaConfig { enabled: true, decay_seconds: 0.2 })
+  }
+
+  #[test]
+  fn disabled_never_coasts()
+  {
+    let mut inertia = Inertia::default();
+    inertia.coast(true, Vec2::new(500.0, 0.0), 0.016);
+    assert_eq!(inertia.coast(false, Vec2::ZERO, 0.016), None);
+  }
+
+  #[test]
+  fn coasting_decays_to_rest()
+  {
+    let mut inertia = enabled();
+    inertia.coast(true, Vec2::new(500.0, 0.0), 0.016);
+
+    let first = inertia.coast(false, Vec2::ZERO, 0.016).unwrap();
+    let second = inertia.coast(false, Vec2::ZERO, 0.016).unwrap();
+    assert!((first.x - 8.0).abs() < 1e-4);
+    assert!(second.x < first.x);
+
+    let mut frames = 0;
+    while inertia.coast(false, Vec2::ZERO, 0.016).is_some()
+    {
+      frames += 1;
+      assert!(frames < 1000);
+    }
+  }
+
+  #[test]
+  fn stop_ends_coasting()
+  {
+    let mut inertia = enabled();
+    inertia.coast(true, Vec2::new(500.0, 0.0), 0.016);
+    inertia.coast_pan(true, Vec2::new(0.5, 0.0), 0.016);
+    inertia.stop();
+    assert!(!inertia.is_coasting());
+    assert_eq!(inertia.coast(false, Vec2::ZERO, 0.016), None);
+    assert_eq!(inertia.coast_pan(false, Vec2::ZERO, 0.016), None);
+  }
+
+  #[test]
+  fn pan_glides_after_release()
+  {
+    let mut inertia = enabled();
+    assert_eq!(inertia.coast_pan(true, Vec2::new(0.0, 0.5), 0.016), None);
+    assert!(inertia.is_coasting());
+
+    let first = inertia.coast_pan(false, Vec2::ZERO, 0.016).unwrap();
+    assert!((first.y - 0.008).abs() < 1e-6);
+    // Orbit momentum is separate and was never started.
+    assert_eq!(inertia.coast(false, Vec2::ZERO, 0.016), None);
+  }
+}
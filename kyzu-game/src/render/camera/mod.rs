@@ -1,4 +1,9 @@
+use glam::DVec3;
+
 use crate::input::state::InputState;
+use crate::render::camera::animator::CameraAnimator;
+use crate::render::camera::history::{CameraHistory, CameraPose};
+use crate::render::camera::inertia::Inertia;
 use crate::render::shared::{CameraMode, SharedState};
 
 pub trait CameraController
@@ -6,14 +11,85 @@ pub trait CameraController
   fn update(&mut self, shared: &mut SharedState, input: &mut InputState, dt: f32);
 }
 
+pub mod animator;
 pub mod free;
+pub mod history;
+pub mod inertia;
 pub mod orbital;
+pub mod projection;
+
+/// Axis-aligned and isometric views for the orbital camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewPreset
+{
+  Top,
+  Bottom,
+  Front,
+  Back,
+  Left,
+  Right,
+  Iso,
+}
+
+impl ViewPreset
+{
+  pub const ALL: [ViewPreset; 7] = [
+    ViewPreset::Top,
+    ViewPreset::Bottom,
+    ViewPreset::Front,
+    ViewPreset::Back,
+    ViewPreset::Left,
+    ViewPreset::Right,
+    ViewPreset::Iso,
+  ];
+
+  /// Orbit latitude/longitude in degrees. Front looks down -Z, Right down -X.
+  pub fn lat_lon(self) -> (f64, f64)
+  {
+    // Isometric elevation: atan(1 / sqrt(2)).
+    let iso_lat = (1.0f64 / 2.0f64.sqrt()).atan().to_degrees();
+
+    match self
+    {
+      ViewPreset::Top => (90.0, 0.0),
+      ViewPreset::Bottom => (-90.0, 0.0),
+      ViewPreset::Front => (0.0, 0.0),
+      ViewPreset::Back => (0.0, 180.0),
+      ViewPreset::Left => (0.0, -90.0),
+      ViewPreset::Right => (0.0, 90.0),
+      ViewPreset::Iso => (iso_lat, 45.0),
+    }
+  }
+
+  pub fn name(self) -> &'static str
+  {
+    match self
+    {
+      ViewPreset::Top => "top",
+      ViewPreset::Bottom => "bottom",
+      ViewPreset::Front => "front",
+      ViewPreset::Back => "back",
+      ViewPreset::Left => "left",
+      ViewPreset::Right => "right",
+      ViewPreset::Iso => "iso",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<ViewPreset>
+  {
+    ViewPreset::ALL.into_iter().find(|preset| preset.name() == name)
+  }
+}
 
 pub struct CameraSystem
 {
   pub free_controller: free::FreeController,
   pub orbital_controller: orbital::OrbitalController,
   last_mode: CameraMode, // Track the mode to detect transitions
+  pub history: CameraHistory,
+  /// Eases preset, framing and history moves.
+  animator: CameraAnimator,
+  pub inertia: Inertia,
 }
 
 impl CameraSystem
@@ -24,7 +100,208 @@ impl CameraSystem
       free_controller: free::FreeController::default(),
       orbital_controller: orbital::OrbitalController::default(),
       last_mode: CameraMode::Orbital, // Default starting mode
+      history: CameraHistory::default(),
+      animator: CameraAnimator::default(),
+      inertia: Inertia::default(),
+    }
+  }
+
+  /// Snapshot of both controllers, tagged with the active mode.
+  pub fn pose(&self, mode: CameraMode) -> CameraPose
+  {
+    let orbit = &self.orbital_controller;
+    let free = &self.free_controller;
+    CameraPose {
+      mode,
+      target: orbit.target,
+      lat: orbit.lat,
+      lon: orbit.lon,
+      altitude: orbit.altitude,
+      orbit_roll: orbit.roll,
+      position: free.position,
+      yaw: free.yaw,
+      pitch: free.pitch,
+      roll: free.roll,
+    }
+  }
+
+  fn apply_pose(&mut self, shared: &mut SharedState, pose: &CameraPose)
+  {
+    let orbit = &mut self.orbital_controller;
+    orbit.target = pose.target;
+    orbit.lat = pose.lat;
+    orbit.lon = pose.lon;
+    orbit.altitude = pose.altitude;
+    orbit.roll = pose.orbit_roll;
+
+    let free = &mut self.free_controller;
+    free.position = pose.position;
+    free.yaw = pose.yaw;
+    free.pitch = pose.pitch;
+    free.roll = pose.roll;
+
+    shared.mode = pose.mode;
+    self.last_mode = pose.mode;
+  }
+
+  /// Jump to `pose` with no animation or momentum, for a camera that
+  /// follows another one (a mirror window).
+  pub fn follow(&mut self, shared: &mut SharedState, pose: &CameraPose)
+  {
+    self.animator.cancel();
+    self.inertia.stop();
+    self.apply_pose(shared, pose);
+  }
+
+  /// Step to the previous recorded view. False if there is none.
+  pub fn go_back(&mut self, shared: &mut SharedState) -> bool
+  {
+    match self.history.back()
+    {
+      Some(pose) => self.animate_to(shared, pose),
+      None => return false,
+    }
+    true
+  }
+
+  /// Step to the next recorded view. False if there is none.
+  pub fn go_forward(&mut self, shared: &mut SharedState) -> bool
+  {
+    match self.history.forward()
+    {
+      Some(pose) => self.animate_to(shared, pose),
+      None => return false,
+    }
+    true
+  }
+
+  /// Animate from the current view to `to`. When the mode changes, the
+  /// incoming controller is first lined up with the current eye so the
+  /// move starts where the camera is.
+  fn animate_to(&mut self, shared: &mut SharedState, to: CameraPose)
+  {
+    if to.mode != shared.mode
+    {
+      match to.mode
+      {
+        CameraMode::Orbital =>
+        {
+          self.orbital_controller.target = to.target;
+          self.orbital_controller.set_from_eye(shared.eye_world);
+        }
+        CameraMode::Free => self.aim_free_camera(shared.eye_world),
+      }
     }
+
+    let from = self.pose(to.mode);
+    self.apply_pose(shared, &from);
+    self.inertia.stop();
+    self.animator.start(from, to);
+  }
+
+  /// Put the free camera at `eye`, looking at the orbital target.
+  fn aim_free_camera(&mut self, eye: DVec3)
+  {
+    self.free_controller.position = eye;
+
+    let to_target = (self.orbital_controller.target - eye).normalize();
+
+    let pitch = (to_target.y as f32).asin();
+    let yaw = {
+      let xz_len = (to_target.x * to_target.x + to_target.z * to_target.z).sqrt() as f32;
+      if xz_len < 1e-6
+      {
+        self.free_controller.yaw
+      }
+      else
+      {
+        f32::atan2(-to_target.x as f32, -to_target.z as f32)
+      }
+    };
+
+    self.free_controller.pitch = pitch;
+    self.free_controller.yaw = yaw;
+    self.free_controller.roll = 0.0;
+  }
+
+  /// True while the camera moves on its own (animation, coasting or
+  /// levelling), or has stopped but history has not recorded the pose yet,
+  /// so frames must keep coming without input.
+  pub fn is_moving(&self) -> bool
+  {
+    self.animator.is_active()
+      || self.inertia.is_coasting()
+      || self.free_controller.leveling
+      || self.orbital_controller.leveling
+      || self.history.is_settling()
+  }
+
+  /// Advance an active animation. Returns true while one is running.
+  fn update_animation(&mut self, shared: &mut SharedState, dt: f32) -> bool
+  {
+    match self.animator.step(dt)
+    {
+      Some(pose) =>
+      {
+        self.apply_pose(shared, &pose);
+        true
+      }
+      None => false,
+    }
+  }
+
+  /// Switch to the orbital camera around its current target, starting from
+  /// the current eye, so an animated move begins where the camera is.
+  fn enter_orbital(&mut self, shared: &mut SharedState)
+  {
+    if shared.mode != CameraMode::Orbital
+    {
+      self.orbital_controller.set_from_eye(shared.eye_world);
+      shared.mode = CameraMode::Orbital;
+      self.last_mode = CameraMode::Orbital;
+    }
+  }
+
+  /// Look at the orbit target from a preset direction, keeping the
+  /// distance and levelling any roll. Switches to the orbital camera
+  /// around the current target.
+  pub fn set_view(&mut self, shared: &mut SharedState, preset: ViewPreset)
+  {
+    self.enter_orbital(shared);
+
+    let (lat, lon) = preset.lat_lon();
+    let mut to = self.pose(CameraMode::Orbital);
+    to.lat = lat;
+    to.lon = lon;
+    to.orbit_roll = 0.0;
+    self.animate_to(shared, to);
+  }
+
+  /// Glide back to the orbital camera's starting target, angle and distance.
+  pub fn reset_view(&mut self, shared: &mut SharedState)
+  {
+    self.enter_orbital(shared);
+
+    let start = orbital::OrbitalController::default();
+    let mut to = self.pose(CameraMode::Orbital);
+    to.target = start.target;
+    to.lat = start.lat;
+    to.lon = start.lon;
+    to.altitude = start.altitude;
+    to.orbit_roll = start.roll;
+    self.animate_to(shared, to);
+  }
+
+  /// Move to orbit `target` at `distance` metres, switching to the orbital
+  /// camera. The viewing direction is kept.
+  pub fn frame_target(&mut self, shared: &mut SharedState, target: glam::DVec3, distance: f64)
+  {
+    self.enter_orbital(shared);
+
+    let mut to = self.pose(CameraMode::Orbital);
+    to.target = target;
+    to.altitude = orbital::clamp_altitude(distance);
+    self.animate_to(shared, to);
   }
 
   pub fn update(&mut self, shared: &mut SharedState, input: &mut InputState, dt: f32)
@@ -33,48 +310,70 @@ impl CameraSystem
     {
       // 1. Reset the mouse delta IMMEDIATELY on transition
       input.consume_mouse_delta();
+      self.animator.cancel();
+      self.inertia.stop();
 
       match shared.mode
       {
         CameraMode::Free =>
         {
-          self.free_controller.position = shared.eye_world;
-
-          let to_target = (self.orbital_controller.target - shared.eye_world).normalize();
-
-          let pitch = (to_target.y as f32).asin();
-          let yaw = {
-            let xz_len = (to_target.x * to_target.x + to_target.z * to_target.z).sqrt() as f32;
-            if xz_len < 1e-6
-            {
-              self.free_controller.yaw
-            }
-            else
-            {
-              f32::atan2(-to_target.x as f32, -to_target.z as f32)
-            }
-          };
-
-          self.free_controller.pitch = pitch;
-          self.free_controller.yaw = yaw;
+          self.aim_free_camera(shared.eye_world);
+          self.free_controller.leveling = false;
           self.free_controller.speed_gear = 0;
         }
         CameraMode::Orbital =>
         {
-          let rel = shared.eye_world - self.orbital_controller.target;
-          let dist = rel.length();
-          self.orbital_controller.altitude = dist;
-          self.orbital_controller.lat = (rel.y / dist).asin().to_degrees();
-          self.orbital_controller.lon = (rel.x).atan2(rel.z).to_degrees();
+          self.orbital_controller.set_from_eye(shared.eye_world);
         }
       }
       self.last_mode = shared.mode;
     }
 
+    // Mid-animation the pose is driven here; the controller still runs to
+    // build the matrices, with this frame's mouse and scroll input dropped.
+    let animating = self.update_animation(shared, dt);
+    input.track_velocity(dt);
+    if animating
+    {
+      input.consume_mouse_delta();
+      input.scroll_delta = 0.0;
+    }
+
+    // Momentum after a right-drag release, applied ahead of the controller.
+    let dragging = input.mouse_buttons_down.contains(&winit::event::MouseButton::Right);
+    let mut coast = None;
+    if !animating
+    {
+      coast = self.inertia.coast(dragging, input.mouse_velocity, dt);
+    }
+    if let Some(coast) = coast
+    {
+      match shared.mode
+      {
+        CameraMode::Free => self.free_controller.apply_look(coast),
+        CameraMode::Orbital => self.orbital_controller.apply_drag(coast),
+      }
+    }
+
+    // The orbit target glides on after WASD panning stops, the same way.
+    if shared.mode == CameraMode::Orbital && !animating
+    {
+      let pan = self.orbital_controller.key_pan(input);
+      if let Some(glide) = self.inertia.coast_pan(pan != glam::Vec2::ZERO, pan, dt)
+      {
+        self.orbital_controller.apply_pan(glide);
+      }
+    }
+
     match shared.mode
     {
       CameraMode::Free => self.free_controller.update(shared, input, dt),
       CameraMode::Orbital => self.orbital_controller.update(shared, input, dt),
     }
+
+    if !animating
+    {
+      self.history.observe(self.pose(shared.mode), dt);
+    }
   }
 }
@@ -1,16 +1,21 @@
 use glam::DVec3;
+use winit::keyboard::KeyCode;
 
+use super::free::step_roll;
+use super::projection::{fit_clip_planes, Projection, RENDER_SCALE};
 use super::CameraController;
 use crate::render::camera::InputState;
 
-const RENDER_SCALE: f64 = 1_000_000.0; // 1 render unit = 1 000 km
-
 pub struct OrbitalController
 {
-  pub lat: f64,            // Latitude in degrees (-90 to 90)
+  pub lat: f64,            // Latitude in degrees, wrapped to [-180, 180)
   pub lon: f64,            // Longitude in degrees
   pub altitude: f64,       // Distance from center
   pub target: glam::DVec3, // The center of the world body
+  /// Rotation about the view axis, radians. Q/E, as in the free camera.
+  pub roll: f32,
+  /// Set by the level-horizon command; roll eases back to zero.
+  pub leveling: bool,
   pub fov: f32,
   pub z_near: f32,
   pub z_far: f32,
@@ -26,6 +31,8 @@ impl Default for OrbitalController
       lon: 0.0,
       altitude: 2_000_000_000.0,
       target: glam::DVec3::ZERO,
+      roll: 0.0,
+      leveling: false,
       fov: 45.0,
       z_near: 100_000.0,
       z_far: 1_000_000_000_000.0,
@@ -34,62 +41,387 @@ impl Default for OrbitalController
   }
 }
 
+const MIN_ALTITUDE: f64 = 1_000_000.0;
+/// Arrow keys: degrees of latitude/longitude per second.
+const KEY_ORBIT_SPEED: f64 = 60.0;
+/// WASD: fraction of the altitude panned per second.
+const KEY_PAN_SPEED: f64 = 0.5;
+/// +/-: scroll notches per second.
+const KEY_ZOOM_RATE: f32 = 5.0;
+const MAX_ALTITUDE: f64 = 100_000_000_000_000.0;
+
+impl OrbitalController
+{
+  /// Right-drag: mouse pixels to degrees of longitude/latitude.
+  /// Latitude is not clamped: dragging over a pole carries on down the
+  /// far side with the view upside down, like a trackball.
+  pub fn apply_drag(&mut self, delta: glam::Vec2)
+  {
+    self.lon -= (delta.x * 0.2) as f64;
+    self.lat = wrap_latitude(self.lat + (delta.y * 0.2) as f64);
+  }
+
+  /// Scroll: each notch moves 10% of the current altitude.
+  pub fn apply_zoom(&mut self, scroll: f32)
+  {
+    self.altitude -= (scroll as f64) * self.altitude * 0.1;
+    self.altitude = clamp_altitude(self.altitude);
+  }
+
+  /// Keyboard navigation, scaled by frame time: arrows orbit, WASD pans
+  /// the target across the view, +/- zoom. Skipped while Ctrl or Alt is
+  /// held so those chords stay free for commands.
+  pub fn apply_keys(&mut self, input: &InputState, dt: f32)
+  {
+    if chord_held(input)
+    {
+      return;
+    }
+    let dt = dt as f64;
+
+    let orbit_x = key_axis(input, &[KeyCode::ArrowRight], &[KeyCode::ArrowLeft]);
+    let orbit_y = key_axis(input, &[KeyCode::ArrowUp], &[KeyCode::ArrowDown]);
+    if orbit_x != 0.0 || orbit_y != 0.0
+    {
+      // Right/up move the camera right/up around the target.
+      self.lon += orbit_x * KEY_ORBIT_SPEED * dt;
+      self.lat = wrap_latitude(self.lat + orbit_y * KEY_ORBIT_SPEED * dt);
+    }
+
+    let pan = self.key_pan(input);
+    if pan != glam::Vec2::ZERO
+    {
+      self.apply_pan(pan * dt as f32);
+    }
+
+    let zoom = key_axis(
+      input,
+      &[KeyCode::Equal, KeyCode::NumpadAdd],
+      &[KeyCode::Minus, KeyCode::NumpadSubtract],
+    );
+    if zoom != 0.0
+    {
+      self.apply_zoom(zoom as f32 * KEY_ZOOM_RATE * dt as f32);
+    }
+  }
+
+  /// WASD pan rate, in altitudes per second along the view's right and
+  /// up; zero while Ctrl or Alt is held. Inertia keeps it after release.
+  pub fn key_pan(&self, input: &InputState) -> glam::Vec2
+  {
+    if chord_held(input)
+    {
+      return glam::Vec2::ZERO;
+    }
+    let pan_x = key_axis(input, &[KeyCode::KeyD], &[KeyCode::KeyA]);
+    let pan_y = key_axis(input, &[KeyCode::KeyW], &[KeyCode::KeyS]);
+    glam::Vec2::new(pan_x as f32, pan_y as f32) * KEY_PAN_SPEED as f32
+  }
+
+  /// Move the target across the view by `delta` altitudes (right, up).
+  pub fn apply_pan(&mut self, delta: glam::Vec2)
+  {
+    let backward = orbit_offset(self.lat, self.lon, 1.0);
+    let up = rolled_up(self.lat, self.lon, self.roll);
+    let right = up.cross(backward);
+    self.target += (right * delta.x as f64 + up * delta.y as f64) * self.altitude;
+  }
+
+  /// Eye offset from the target for the current lat/lon/altitude.
+  pub fn eye_offset(&self) -> DVec3
+  {
+    orbit_offset(self.lat, self.lon, self.altitude)
+  }
+
+  /// Take up lat/lon/altitude from an eye position, e.g. when switching
+  /// from the free camera.
+  pub fn set_from_eye(&mut self, eye_world: DVec3)
+  {
+    let rel = eye_world - self.target;
+    let (lat, lon) = lat_lon_from_offset(rel);
+    self.altitude = rel.length();
+    self.lat = lat;
+    self.lon = lon;
+  }
+}
+
+/// Ctrl or Alt is down, so keys belong to command chords, not navigation.
+fn chord_held(input: &InputState) -> bool
+{
+  let modifiers =
+    [KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::AltLeft, KeyCode::AltRight];
+  modifiers.iter().any(|&key| input.is_key_down(key))
+}
+
+/// +1, -1 or 0 for a pair of key sets; both held cancel out.
+fn key_axis(input: &InputState, positive: &[KeyCode], negative: &[KeyCode]) -> f64
+{
+  let mut value = 0.0;
+  if positive.iter().any(|&key| input.is_key_down(key))
+  {
+    value += 1.0;
+  }
+  if negative.iter().any(|&key| input.is_key_down(key))
+  {
+    value -= 1.0;
+  }
+  value
+}
+
+/// Limit an orbit distance to the zoom range.
+pub fn clamp_altitude(distance: f64) -> f64
+{
+  distance.clamp(MIN_ALTITUDE, MAX_ALTITUDE)
+}
+
+/// Wrap to [-180, 180): past a pole the orbit continues down the far side.
+fn wrap_latitude(lat: f64) -> f64
+{
+  (lat + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Spherical to cartesian: lat/lon in degrees, +Y is north.
+pub fn orbit_offset(lat_deg: f64, lon_deg: f64, distance: f64) -> DVec3
+{
+  let lat_rad = lat_deg.to_radians();
+  let lon_rad = lon_deg.to_radians();
+
+  DVec3::new(
+    distance * lat_rad.cos() * lon_rad.sin(),
+    distance * lat_rad.sin(),
+    distance * lat_rad.cos() * lon_rad.cos(),
+  )
+}
+
+/// Screen-up direction for an orbit at lat/lon: the northward tangent of
+/// orbit_offset. Matches +Y for |lat| < 90, stays defined at the poles and
+/// turns over past them.
+pub fn orbit_up(lat_deg: f64, lon_deg: f64) -> DVec3
+{
+  let lat_rad = lat_deg.to_radians();
+  let lon_rad = lon_deg.to_radians();
+
+  DVec3::new(-lat_rad.sin() * lon_rad.sin(), lat_rad.cos(), -lat_rad.sin() * lon_rad.cos())
+}
+
+/// orbit_up turned by `roll` radians about the view axis. Positive roll
+/// tilts the camera left, matching the free camera.
+pub fn rolled_up(lat_deg: f64, lon_deg: f64, roll: f32) -> DVec3
+{
+  let backward = orbit_offset(lat_deg, lon_deg, 1.0);
+  glam::DQuat::from_axis_angle(backward, roll as f64) * orbit_up(lat_deg, lon_deg)
+}
+
+/// Inverse of orbit_offset. Returns (lat, lon) in degrees.
+pub fn lat_lon_from_offset(offset: DVec3) -> (f64, f64)
+{
+  let dist = offset.length();
+  let lat = (offset.y / dist).asin().to_degrees();
+  let lon = offset.x.atan2(offset.z).to_degrees();
+  (lat, lon)
+}
+
 impl CameraController for OrbitalController
 {
   fn update(
     &mut self,
     shared: &mut crate::render::shared::SharedState,
     input: &mut InputState,
-    _dt: f32,
+    dt: f32,
   )
   {
     // 1. Handle Input (Logic stays the same)
     if input.mouse_buttons_down.contains(&winit::event::MouseButton::Right)
     {
-      self.lon -= (input.mouse_delta.x * 0.2) as f64;
-      self.lat += (input.mouse_delta.y * 0.2) as f64;
-      self.lat = self.lat.clamp(-89.0, 89.0);
+      self.apply_drag(input.look_delta());
     }
     if input.scroll_delta != 0.0
     {
-      self.altitude -= (input.scroll_delta as f64) * self.altitude * 0.1;
-      self.altitude = self.altitude.clamp(1_000_000.0, 100_000_000_000_000.0);
+      self.apply_zoom(input.scroll_delta);
     }
-
-    // Convert eye position to render units for the projection matrix
-    let altitude_render = self.altitude / RENDER_SCALE;
-
-    // Replace the existing view/proj math with render-unit versions:
-    let lat_rad = self.lat.to_radians();
-    let lon_rad = self.lon.to_radians();
+    self.apply_keys(input, dt);
+    step_roll(&mut self.roll, &mut self.leveling, input, dt);
 
     // Eye position in render units
-    let x = altitude_render * lat_rad.cos() * lon_rad.sin();
-    let y = altitude_render * lat_rad.sin();
-    let z = altitude_render * lat_rad.cos() * lon_rad.cos();
-    let offset_render = glam::DVec3::new(x, y, z);
+    let offset_render = self.eye_offset() / RENDER_SCALE;
 
     // eye_world stays in metres for the rest of the engine
     let offset_metres = offset_render * RENDER_SCALE;
     shared.eye_world = self.target + offset_metres;
+    shared.pivot = self.target;
 
     // View matrix in render units
     let relative_target_render = -offset_render;
-    let view_rel =
-      glam::DMat4::look_at_rh(glam::DVec3::ZERO, relative_target_render, glam::DVec3::Y);
+    let up = rolled_up(self.lat, self.lon, self.roll);
+    let view_rel = glam::DMat4::look_at_rh(glam::DVec3::ZERO, relative_target_render, up);
 
-    // Near/far in render units
-    // z_near = 1.0 render unit = 1000 km (fine for solar system scale)
-    // z_far  = 200_000.0 render units = 200 billion km (past Pluto)
-    let z_near = 1.0_f32;
-    let z_far = 200_000.0_f32;
+    // Near/far in render units, fitted to the bodies; the configured
+    // planes (metres) are the fallback for an empty registry.
+    let mut z_near = (self.z_near as f64 / RENDER_SCALE) as f32;
+    let mut z_far = (self.z_far as f64 / RENDER_SCALE) as f32;
+    if let Some((near, far)) = fit_clip_planes(shared.eye_world, &shared.body_registry)
+    {
+      z_near = near;
+      z_far = far;
+    }
 
     let aspect = shared.screen_width as f32 / shared.screen_height as f32;
-    let proj = glam::Mat4::perspective_rh(self.fov.to_radians(), aspect, z_near, z_far);
-    let view_proj = proj * view_rel.as_mat4();
+    shared.projection = Projection { fov_y_rad: self.fov.to_radians(), aspect, z_near, z_far };
+    shared.focus_distance = self.altitude;
+
+    let view_proj = shared.projection.matrix() * view_rel.as_mat4();
 
     shared.camera.view_proj = view_proj.to_cols_array_2d();
     shared.camera.inv_view_proj = view_proj.inverse().to_cols_array_2d();
     shared.camera.eye_rel = [0.0, 0.0, 0.0];
   }
 }
+
+#[cfg(test)]
+mod tests
+{
+  use glam::{DVec3, Vec2};
+  use proptest::prelude::*;
+
+  use super::*;
+
+  #[test]
+  fn drag_carries_latitude_over_the_poles()
+  {
+    let mut orbit = OrbitalController::default();
+
+    // 100 px = 20 degrees.
+    orbit.apply_drag(Vec2::new(0.0, 500.0));
+    assert!((orbit.lat - 100.0).abs() < 1e-9);
+
+    orbit.apply_drag(Vec2::new(0.0, 500.0));
+    orbit.apply_drag(Vec2::new(0.0, 500.0));
+    assert!((orbit.lat + 60.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn up_turns_over_past_the_pole()
+  {
+    assert!(orbit_up(80.0, 0.0).y > 0.0);
+    assert!(orbit_up(100.0, 0.0).y < 0.0);
+  }
+
+  #[test]
+  fn drag_turns_longitude_freely()
+  {
+    let mut orbit = OrbitalController::default();
+    orbit.apply_drag(Vec2::new(-3600.0, 0.0));
+    assert_eq!(orbit.lon, 720.0);
+  }
+
+  #[test]
+  fn zoom_clamps_altitude()
+  {
+    let mut orbit = OrbitalController::default();
+
+    for _ in 0..500
+    {
+      orbit.apply_zoom(3.0);
+    }
+    assert_eq!(orbit.altitude, MIN_ALTITUDE);
+
+    for _ in 0..500
+    {
+      orbit.apply_zoom(-3.0);
+    }
+    assert_eq!(orbit.altitude, MAX_ALTITUDE);
+  }
+
+  #[test]
+  fn offset_axes()
+  {
+    let front = orbit_offset(0.0, 0.0, 10.0);
+    assert!((front - DVec3::new(0.0, 0.0, 10.0)).length() < 1e-9);
+
+    let north = orbit_offset(90.0, 0.0, 10.0);
+    assert!((north - DVec3::new(0.0, 10.0, 0.0)).length() < 1e-9);
+
+    let east = orbit_offset(0.0, 90.0, 10.0);
+    assert!((east - DVec3::new(10.0, 0.0, 0.0)).length() < 1e-9);
+  }
+
+  #[test]
+  fn up_is_perpendicular_to_the_view_at_the_poles()
+  {
+    for lat in [-90.0, 0.0, 45.0, 90.0]
+    {
+      let up = orbit_up(lat, 30.0);
+      let offset = orbit_offset(lat, 30.0, 1.0);
+      assert!(up.dot(offset).abs() < 1e-12);
+      assert!((up.length() - 1.0).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn pan_keys_move_the_target_across_the_view()
+  {
+    let mut orbit = OrbitalController::default();
+    let mut input = InputState::new();
+    input.keys_down.insert(KeyCode::KeyD);
+
+    orbit.apply_keys(&input, 1.0);
+
+    // Looking down -Z from +Z, screen right is +X.
+    let expected = KEY_PAN_SPEED * orbit.altitude;
+    assert!((orbit.target.x - expected).abs() < 1e-3);
+    assert!(orbit.target.y.abs() < 1e-3);
+  }
+
+  #[test]
+  fn roll_turns_up_about_the_view_axis()
+  {
+    let level = rolled_up(0.0, 0.0, 0.0);
+    assert!((level - DVec3::Y).length() < 1e-9);
+
+    // Looking down -Z from +Z: a quarter roll left puts up along -X.
+    let tilted = rolled_up(0.0, 0.0, std::f32::consts::FRAC_PI_2);
+    assert!((tilted - DVec3::NEG_X).length() < 1e-6);
+  }
+
+  #[test]
+  fn set_from_eye_uses_target()
+  {
+    let mut orbit =
+      OrbitalController { target: DVec3::new(5.0e9, -2.0e9, 1.0e9), ..Default::default() };
+    let eye = orbit.target + orbit_offset(30.0, -45.0, 7.0e8);
+
+    orbit.set_from_eye(eye);
+
+    assert!((orbit.lat - 30.0).abs() < 1e-6);
+    assert!((orbit.lon + 45.0).abs() < 1e-6);
+    assert!((orbit.altitude - 7.0e8).abs() < 1e-3);
+  }
+
+  proptest! {
+    #[test]
+    fn offset_length_is_distance(lat in -90.0f64..90.0, lon in -360.0f64..360.0, dist in 1.0f64..1e14)
+    {
+      let offset = orbit_offset(lat, lon, dist);
+      prop_assert!((offset.length() - dist).abs() <= dist * 1e-12);
+    }
+
+    #[test]
+    fn lat_lon_round_trip(lat in -89.0f64..89.0, lon in -179.0f64..179.0, dist in 1.0f64..1e14)
+    {
+      let (lat_back, lon_back) = lat_lon_from_offset(orbit_offset(lat, lon, dist));
+      prop_assert!((lat_back - lat).abs() < 1e-9);
+      prop_assert!((lon_back - lon).abs() < 1e-9);
+    }
+
+    #[test]
+    fn eye_round_trip(lat in -89.0f64..89.0, lon in -179.0f64..179.0, alt in MIN_ALTITUDE..1e13)
+    {
+      let source = OrbitalController { lat, lon, altitude: alt, ..Default::default() };
+      let mut copy = OrbitalController::default();
+
+      copy.set_from_eye(source.target + source.eye_offset());
+
+      prop_assert!((copy.eye_offset() - source.eye_offset()).length() <= alt * 1e-9);
+    }
+  }
+}
@@ -0,0 +1,373 @@
+use glam::{DVec3, Mat4, Vec2, Vec3, Vec4};
+
+use crate::core::math::Viewport;
+use crate::world::registry::BodyRegistry;
+
+/// Metres per render unit. Bodies are stored in metres (f64); rendering
+/// works in units of 1 000 km so f32 precision is adequate across the
+/// visible scene: Earth's radius is 6.371 units, the Sun's 695.7 and
+/// 1 AU 149 598.
+pub const RENDER_SCALE: f64 = 1_000_000.0;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Projection
+//
+//  The perspective the active camera built this frame, kept in SharedState
+//  so tools (telemetry, rulers, picking) can ask questions of it instead of
+//  re-deriving the maths. All lengths are in render units.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy)]
+pub struct Projection
+{
+  pub fov_y_rad: f32,
+  pub aspect: f32,
+  pub z_near: f32,
+  pub z_far: f32,
+}
+
+impl Default for Projection
+{
+  fn default() -> Self
+  {
+    Self { fov_y_rad: 45.0f32.to_radians(), aspect: 1.0, z_near: 1.0, z_far: 200_000.0 }
+  }
+}
+
+impl Projection
+{
+  pub fn matrix(&self) -> Mat4
+  {
+    Mat4::perspective_rh(self.fov_y_rad, self.aspect, self.z_near, self.z_far)
+  }
+
+  /// Width and height of the view frustum cross-section `depth` units ahead.
+  pub fn frustum_size_at(&self, depth: f32) -> Vec2
+  {
+    let height = 2.0 * depth * (self.fov_y_rad * 0.5).tan();
+    Vec2::new(height * self.aspect, height)
+  }
+
+  /// World length covered by one screen pixel at `depth` units ahead.
+  pub fn units_per_pixel(&self, depth: f32, screen_height: u32) -> f32
+  {
+    if screen_height == 0
+    {
+      return 0.0;
+    }
+    self.frustum_size_at(depth).y / screen_height as f32
+  }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Clip plane fitting
+//
+//  Near and far hug the bodies' bounding spheres instead of a fixed range,
+//  so depth precision goes where the geometry is. The far/near ratio is
+//  capped to keep Depth32Float usable; when the scene spans more than that,
+//  near wins and distant bodies may be clipped.
+// ─────────────────────────────────────────────────────────────────────────────
+
+const MIN_NEAR: f64 = 0.001; // render units (1 km): eye inside or touching a body
+const MAX_DEPTH_RATIO: f64 = 1.0e7;
+const FIT_MARGIN: f64 = 0.05;
+
+/// Near/far in render units bracketing every body seen from `eye_world`.
+/// None if there are no bodies to fit.
+pub fn fit_clip_planes(eye_world: DVec3, bodies: &BodyRegistry) -> Option<(f32, f32)>
+{
+  let mut nearest = f64::MAX;
+  let mut furthest = 0.0f64;
+
+  for body in &bodies.bodies
+  {
+    let distance = (body.world_pos - eye_world).length() / RENDER_SCALE;
+    let radius = body.manifest.radius_m / RENDER_SCALE;
+
+    nearest = nearest.min(distance - radius);
+    furthest = furthest.max(distance + radius);
+  }
+
+  if furthest <= 0.0
+  {
+    return None;
+  }
+
+  let near = (nearest * (1.0 - FIT_MARGIN)).max(MIN_NEAR);
+  let far = (furthest * (1.0 + FIT_MARGIN)).min(near * MAX_DEPTH_RATIO).max(near * 2.0);
+  Some((near as f32, far as f32))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Scene visibility
+//
+//  Catches a camera that sees nothing, e.g. restored from a bad saved state
+//  or left far from a tiny model: no body inside the frustum, or none big
+//  enough to cover a pixel. scene_bounds and fit_distance then give an
+//  orbit that frames everything.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A body smaller than this on screen counts as not visible.
+const MIN_VISIBLE_PX: f32 = 1.0;
+
+/// Whether a sphere overlaps the frustum's side planes. `center` is
+/// camera-relative, in render units. Near and far are left out: they are
+/// fitted to the bodies each frame.
+pub fn sphere_in_frustum(view_proj: &Mat4, center: Vec3, radius: f32) -> bool
+{
+  let (r0, r1, r3) = (view_proj.row(0), view_proj.row(1), view_proj.row(3));
+  for plane in [r3 + r0, r3 - r0, r3 + r1, r3 - r1]
+  {
+    let normal = plane.truncate();
+    let distance = (normal.dot(center) + plane.w) / normal.length();
+    if distance < -radius
+    {
+      return false;
+    }
+  }
+  true
+}
+
+/// Whether any body is in view and at least a pixel across.
+pub fn any_body_visible(
+  view_proj: &Mat4,
+  projection: &Projection,
+  eye_world: DVec3,
+  screen_height: u32,
+  bodies: &BodyRegistry,
+) -> bool
+{
+  for body in &bodies.bodies
+  {
+    let center = ((body.world_pos - eye_world) / RENDER_SCALE).as_vec3();
+    let radius = (body.manifest.radius_m / RENDER_SCALE) as f32;
+    if !sphere_in_frustum(view_proj, center, radius)
+    {
+      continue;
+    }
+
+    let depth = (center.length() - radius).max(projection.z_near);
+    let pixels = 2.0 * radius / projection.units_per_pixel(depth, screen_height);
+    if pixels >= MIN_VISIBLE_PX
+    {
+      return true;
+    }
+  }
+  false
+}
+
+/// Centre and radius in metres of a sphere around every body's bounding
+/// box. None if there are no bodies.
+pub fn scene_bounds(bodies: &BodyRegistry) -> Option<(DVec3, f64)>
+{
+  let mut min = DVec3::splat(f64::MAX);
+  let mut max = DVec3::splat(f64::MIN);
+  for body in &bodies.bodies
+  {
+    let extent = DVec3::splat(body.manifest.radius_m);
+    min = min.min(body.world_pos - extent);
+    max = max.max(body.world_pos + extent);
+  }
+
+  if bodies.bodies.is_empty()
+  {
+    return None;
+  }
+  Some(((min + max) * 0.5, (max - min).length() * 0.5))
+}
+
+/// Distance from which a sphere of `radius` fits the narrower field of view.
+pub fn fit_distance(projection: &Projection, radius: f64) -> f64
+{
+  let half_y = (projection.fov_y_rad as f64) * 0.5;
+  let half_x = (half_y.tan() * projection.aspect as f64).atan();
+  radius / half_y.min(half_x).sin()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  project / unproject
+//
+//  Map between world positions (metres, f64) and screen pixels. Screen
+//  origin is top-left, y down; depth is NDC z (0 at the near plane, 1 at
+//  the far plane). view_proj is camera-relative, so positions are taken
+//  relative to the eye before dropping to f32.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// World position to screen pixels plus NDC depth. None if behind the eye.
+pub fn project(
+  view_proj: &Mat4,
+  eye_world: DVec3,
+  viewport: &Viewport,
+  world: DVec3,
+) -> Option<Vec3>
+{
+  let relative = ((world - eye_world) / RENDER_SCALE).as_vec3();
+  let clip = *view_proj * Vec4::new(relative.x, relative.y, relative.z, 1.0);
+
+  if clip.w <= 0.0
+  {
+    return None;
+  }
+
+  let ndc = clip.truncate() / clip.w;
+  let x = (ndc.x + 1.0) * 0.5 * viewport.width;
+  let y = (1.0 - ndc.y) * 0.5 * viewport.height;
+  Some(Vec3::new(x, y, ndc.z))
+}
+
+/// Screen pixels at NDC `depth` back to a world position in metres.
+pub fn unproject(
+  inv_view_proj: &Mat4,
+  eye_world: DVec3,
+  viewport: &Viewport,
+  screen: Vec2,
+  depth: f32,
+) -> DVec3
+{
+  let ndc_x = screen.x / viewport.width * 2.0 - 1.0;
+  let ndc_y = 1.0 - screen.y / viewport.height * 2.0;
+  let point = *inv_view_proj * Vec4::new(ndc_x, ndc_y, depth, 1.0);
+  let relative = point.truncate() / point.w;
+
+  eye_world + relative.as_dvec3() * RENDER_SCALE
+}
+
+#[cfg(test)]
+mod tests
+{
+  use glam::{DVec3, Mat4, Vec2, Vec3};
+  use proptest::prelude::*;
+
+  use super::*;
+  use crate::core::math::Viewport;
+
+  const VIEWPORT: Viewport = Viewport { width: 1600.0, height: 900.0 };
+
+  fn test_projection() -> Projection
+  {
+    Projection {
+      fov_y_rad: 60.0f32.to_radians(),
+      aspect: 16.0 / 9.0,
+      z_near: 1.0,
+      z_far: 200_000.0,
+    }
+  }
+
+  /// Camera-relative view looking down -Z, as the free camera does at yaw -90.
+  fn view_proj() -> Mat4
+  {
+    let view = Mat4::look_to_rh(Vec3::ZERO, -Vec3::Z, Vec3::Y);
+    test_projection().matrix() * view
+  }
+
+  #[test]
+  fn frustum_follows_aspect()
+  {
+    let size = test_projection().frustum_size_at(100.0);
+    assert!((size.x / size.y - 16.0 / 9.0).abs() < 1e-5);
+    // 60 degree vertical FOV: height = 2 * d * tan(30) = d * 1.1547
+    assert!((size.y - 115.47).abs() < 0.01);
+  }
+
+  #[test]
+  fn units_per_pixel_scales_with_depth()
+  {
+    let projection = test_projection();
+    let near = projection.units_per_pixel(10.0, 900);
+    let far = projection.units_per_pixel(20.0, 900);
+    assert!((far - 2.0 * near).abs() < 1e-6);
+    assert_eq!(projection.units_per_pixel(10.0, 0), 0.0);
+  }
+
+  #[test]
+  fn point_ahead_projects_to_centre()
+  {
+    let eye = DVec3::new(1.0e9, 2.0e9, 3.0e9);
+    let ahead = eye + DVec3::new(0.0, 0.0, -50.0 * RENDER_SCALE);
+
+    let screen = project(&view_proj(), eye, &VIEWPORT, ahead).unwrap();
+    assert!((screen.x - 800.0).abs() < 1e-3);
+    assert!((screen.y - 450.0).abs() < 1e-3);
+    assert!(screen.z > 0.0 && screen.z < 1.0);
+  }
+
+  #[test]
+  fn screen_y_points_down()
+  {
+    let above = DVec3::new(0.0, 10.0, -50.0) * RENDER_SCALE;
+    let screen = project(&view_proj(), DVec3::ZERO, &VIEWPORT, above).unwrap();
+    assert!(screen.y < 450.0);
+  }
+
+  #[test]
+  fn point_behind_is_not_projected()
+  {
+    let behind = DVec3::new(0.0, 0.0, 50.0 * RENDER_SCALE);
+    assert!(project(&view_proj(), DVec3::ZERO, &VIEWPORT, behind).is_none());
+  }
+
+  #[test]
+  fn spheres_outside_the_frustum_are_culled()
+  {
+    let vp = view_proj();
+    assert!(sphere_in_frustum(&vp, Vec3::new(0.0, 0.0, -50.0), 1.0));
+    assert!(!sphere_in_frustum(&vp, Vec3::new(0.0, 0.0, 50.0), 1.0));
+    assert!(!sphere_in_frustum(&vp, Vec3::new(500.0, 0.0, -50.0), 1.0));
+    // Centre just off screen but the sphere reaches back in.
+    assert!(sphere_in_frustum(&vp, Vec3::new(100.0, 0.0, -50.0), 60.0));
+  }
+
+  #[test]
+  fn fit_distance_uses_the_narrower_fov()
+  {
+    // Wider than tall, so the 30 degree vertical half-angle limits: r / sin 30.
+    let projection = test_projection();
+    assert!((fit_distance(&projection, 10.0) - 20.0).abs() < 1e-4);
+
+    let portrait = Projection { aspect: 0.5, ..projection };
+    assert!(fit_distance(&portrait, 10.0) > 20.0);
+  }
+
+  #[test]
+  fn empty_scene_has_no_bounds()
+  {
+    assert!(scene_bounds(&BodyRegistry::new()).is_none());
+  }
+
+  #[test]
+  fn unproject_corners_at_near_plane()
+  {
+    let inv = view_proj().inverse();
+    let top_left = unproject(&inv, DVec3::ZERO, &VIEWPORT, Vec2::ZERO, 0.0) / RENDER_SCALE;
+    let half = test_projection().frustum_size_at(1.0) * 0.5;
+
+    assert!((top_left.x + half.x as f64).abs() < 1e-4);
+    assert!((top_left.y - half.y as f64).abs() < 1e-4);
+    assert!((top_left.z + 1.0).abs() < 1e-4);
+  }
+
+  proptest! {
+    #[test]
+    fn project_unproject_round_trip(
+      sx in 0.0f32..1600.0,
+      sy in 0.0f32..900.0,
+      depth in 2.0f64..1000.0,
+      eye_x in -1e12f64..1e12,
+    )
+    {
+      let eye = DVec3::new(eye_x, 0.0, 0.0);
+      let inv = view_proj().inverse();
+
+      // Build a point in the frustum from a screen position and a distance.
+      let dir = unproject(&inv, DVec3::ZERO, &VIEWPORT, Vec2::new(sx, sy), 0.5).normalize();
+      let world = eye + dir * depth * RENDER_SCALE;
+
+      let screen = project(&view_proj(), eye, &VIEWPORT, world).unwrap();
+      let back = unproject(&inv, eye, &VIEWPORT, screen.truncate(), screen.z);
+
+      prop_assert!((screen.x - sx).abs() < 0.05);
+      prop_assert!((screen.y - sy).abs() < 0.05);
+      prop_assert!((back - world).length() <= depth * RENDER_SCALE * 1e-3);
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use wgpu::{Adapter, Limits};
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  GpuCapabilities
+//
+//  What the adapter can actually do, decided once at startup. The device is
+//  requested with these limits, and antialiasing left unset in the config
+//  defaults to the cheaper FXAA on GL / downlevel adapters instead of MSAA.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// MSAA samples on a fully capable adapter when the config sets none.
+pub const DEFAULT_MSAA_SAMPLES: u32 = 4;
+
+#[derive(Debug, Clone)]
+pub struct GpuCapabilities
+{
+  /// Not fully WebGPU compliant (GL, older mobile/integrated parts).
+  pub downlevel: bool,
+  pub required_limits: Limits,
+}
+
+impl GpuCapabilities
+{
+  pub fn detect(adapter: &Adapter) -> Self
+  {
+    let downlevel = !adapter.get_downlevel_capabilities().is_webgpu_compliant();
+
+    // Start from the matching baseline, then raise resolution-dependent
+    // limits (texture sizes) to whatever the adapter offers.
+    let mut base_limits = Limits::default();
+    if downlevel
+    {
+      base_limits = Limits::downlevel_webgl2_defaults();
+    }
+    let required_limits = base_limits.using_resolution(adapter.limits());
+
+    Self { downlevel, required_limits }
+  }
+
+  /// MSAA samples and FXAA when the config leaves them unset.
+  pub fn default_antialiasing(&self) -> (u32, bool)
+  {
+    if self.downlevel
+    {
+      return (1, true);
+    }
+    (DEFAULT_MSAA_SAMPLES, false)
+  }
+
+  /// One line for the startup log.
+  pub fn summary(&self) -> String
+  {
+    let (samples, fxaa) = self.default_antialiasing();
+    format!("GPU caps: downlevel={} default_msaa={} default_fxaa={}", self.downlevel, samples, fxaa)
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn downlevel_defaults_to_fxaa()
+  {
+    let full = GpuCapabilities { downlevel: false, required_limits: Limits::default() };
+    let weak =
+      GpuCapabilities { downlevel: true, required_limits: Limits::downlevel_webgl2_defaults() };
+    assert_eq!(full.default_antialiasing(), (DEFAULT_MSAA_SAMPLES, false));
+    assert_eq!(weak.default_antialiasing(), (1, true));
+  }
+}
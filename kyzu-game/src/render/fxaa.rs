@@ -0,0 +1,221 @@
+use wgpu::{
+  BindGroup, BindGroupLayout, CommandEncoder, Device, Queue, RenderPipeline, Sampler,
+  TextureFormat, TextureView,
+};
+
+use crate::render::module::{FramePass, RenderModule};
+use crate::render::shared::SharedState;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  FXAA
+//
+//  Cheap antialiasing for GPUs where MSAA costs too much. The scene and
+//  overlay modules draw into an offscreen colour texture instead of the
+//  swapchain image; a fullscreen pass then filters it onto the swapchain.
+//  The selection outline and UI are drawn afterwards, unfiltered.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct FxaaPass
+{
+  pipeline: RenderPipeline,
+  bgl: BindGroupLayout,
+  sampler: Sampler,
+  surface_format: TextureFormat,
+  /// Allocated while FXAA is on, rebuilt when the frame size changes.
+  target: Option<SceneTarget>,
+}
+
+/// Offscreen texture the scene renders into (or MSAA resolves into).
+struct SceneTarget
+{
+  view: TextureView,
+  bind_group: BindGroup,
+  width: u32,
+  height: u32,
+}
+
+impl FxaaPass
+{
+  pub fn new(device: &Device, surface_format: TextureFormat) -> Self
+  {
+    // Linear filtering does the sub-pixel blend along the edge.
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+      label: Some("FXAA Sampler"),
+      address_mode_u: wgpu::AddressMode::ClampToEdge,
+      address_mode_v: wgpu::AddressMode::ClampToEdge,
+      mag_filter: wgpu::FilterMode::Linear,
+      min_filter: wgpu::FilterMode::Linear,
+      ..Default::default()
+    });
+
+    let bgl = Self::create_bind_group_layout(device);
+    let pipeline = Self::create_pipeline(device, &bgl, surface_format);
+
+    Self { pipeline, bgl, sampler, surface_format, target: None }
+  }
+
+  fn create_target(&self, device: &Device, width: u32, height: u32) -> SceneTarget
+  {
+    let scene_texture = device.create_texture(&wgpu::TextureDescriptor {
+      label: Some("FXAA Scene Texture"),
+      size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: self.surface_format,
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+      view_formats: &[],
+    });
+    let view = scene_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some("FXAA BG"),
+      layout: &self.bgl,
+      entries: &[
+        wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+        wgpu::BindGroupEntry {
+          binding: 1,
+          resource: wgpu::BindingResource::Sampler(&self.sampler),
+        },
+      ],
+    });
+
+    SceneTarget { view, bind_group, width, height }
+  }
+
+  fn create_bind_group_layout(device: &Device) -> BindGroupLayout
+  {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: Some("FXAA BGL"),
+      entries: &[
+        wgpu::BindGroupLayoutEntry {
+          binding: 0,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+          },
+          count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+          binding: 1,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+          count: None,
+        },
+      ],
+    })
+  }
+
+  fn create_pipeline(
+    device: &Device,
+    bgl: &BindGroupLayout,
+    surface_format: TextureFormat,
+  ) -> RenderPipeline
+  {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/fxaa.wgsl"));
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("FXAA Pipeline Layout"),
+      bind_group_layouts: &[bgl],
+      push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("FXAA Pipeline"),
+      layout: Some(&layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers: &[],
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: Some("fs_main"),
+        compilation_options: Default::default(),
+        targets: &[Some(wgpu::ColorTargetState {
+          format: surface_format,
+          blend: None,
+          write_mask: wgpu::ColorWrites::ALL,
+        })],
+      }),
+      primitive: wgpu::PrimitiveState::default(),
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default(),
+      multiview: None,
+      cache: None,
+    })
+  }
+}
+
+impl FramePass for FxaaPass
+{
+  /// Runs while FXAA is on; turning it off frees the scene texture.
+  fn prepare(
+    &mut self,
+    device: &Device,
+    _queue: &Queue,
+    shared: &SharedState,
+    width: u32,
+    height: u32,
+  ) -> bool
+  {
+    if !shared.fxaa
+    {
+      self.target = None;
+      return false;
+    }
+
+    let stale = match &self.target
+    {
+      Some(target) => target.width != width || target.height != height,
+      None => true,
+    };
+    if stale
+    {
+      self.target = Some(self.create_target(device, width, height));
+    }
+    true
+  }
+
+  fn input_view(&self) -> Option<&TextureView>
+  {
+    self.target.as_ref().map(|target| &target.view)
+  }
+
+  /// Filter the offscreen scene onto `output`, replacing its contents.
+  fn encode(
+    &self,
+    encoder: &mut CommandEncoder,
+    output: &TextureView,
+    _modules: &[Box<dyn RenderModule>],
+    _shared: &SharedState,
+  )
+  {
+    let target = match &self.target
+    {
+      Some(target) => target,
+      None => return,
+    };
+
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("FXAA Pass"),
+      color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+        view: output,
+        resolve_target: None,
+        ops: wgpu::Operations {
+          load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+          store: wgpu::StoreOp::Store,
+        },
+        depth_slice: None,
+      })],
+      ..Default::default()
+    });
+
+    pass.set_pipeline(&self.pipeline);
+    pass.set_bind_group(0, &target.bind_group, &[]);
+    pass.draw(0..3, 0..1);
+  }
+}
@@ -1,11 +1,18 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use winit::window::Window;
 
+use crate::core::config::PresentMode;
 use crate::input::state::InputState;
 use crate::render::camera::CameraSystem;
-use crate::render::module::{FrameTargets, RenderModule};
-use crate::render::shared::SharedState;
+use crate::render::capabilities::GpuCapabilities;
+use crate::render::fxaa::FxaaPass;
+use crate::render::module::{self, FramePass, FrameTargets, RenderCategory, RenderModule};
+use crate::render::outline::OutlinePass;
+use crate::render::picking::{IdRegion, PickTarget};
+use crate::render::shared::{self, SharedState};
+use crate::render::surface;
+use crate::ui::UiSystem;
 
 pub struct Renderer
 {
@@ -17,60 +24,168 @@ pub struct Renderer
   pub shared: SharedState,
   pub modules: Vec<Box<dyn RenderModule>>,
   pub camera_system: CameraSystem,
-  pub surface: wgpu::Surface<'static>,
+  /// None for a headless renderer, which only draws offscreen.
+  pub surface: Option<wgpu::Surface<'static>>,
+  /// Running count of surface acquire timeouts, read by the GPU watchdog.
+  pub surface_timeouts: u32,
+  /// Created on the first pick and rebuilt when the window size changes.
+  pick_target: Option<PickTarget>,
+  /// Fullscreen steps after the modules, in run order.
+  passes: Vec<Box<dyn FramePass>>,
+  /// Reason given by wgpu's device-lost callback (driver reset, eGPU
+  /// unplugged); None while the device is healthy.
+  lost: Arc<Mutex<Option<String>>>,
 }
 
 impl Renderer
 {
-  pub async fn new(window: Arc<Window>) -> anyhow::Result<Self>
+  /// `transparent` asks for a see-through window; it is honoured only if the
+  /// surface offers a compositing alpha mode. `adapter` picks a GPU by name
+  /// (see select_adapter).
+  pub async fn new(
+    window: Arc<Window>,
+    transparent: bool,
+    adapter: Option<&str>,
+  ) -> anyhow::Result<Self>
   {
     let size = window.inner_size();
     let instance = wgpu::Instance::default();
     let surface = instance.create_surface(window.clone())?;
+    let adapter = Self::select_adapter(&instance, Some(&surface), adapter).await?;
 
-    let adapter = instance
+    let caps = GpuCapabilities::detect(&adapter);
+    let (device, queue) = Self::request_device(&adapter, &caps).await?;
+
+    let swapchain_capabilities = surface.get_capabilities(&adapter);
+    let choice = surface::negotiate(&swapchain_capabilities, transparent);
+
+    let config = wgpu::SurfaceConfiguration {
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+      format: choice.format,
+      width: size.width,
+      height: size.height,
+      present_mode: wgpu::PresentMode::Fifo,
+      alpha_mode: choice.alpha_mode,
+      view_formats: vec![],
+      desired_maximum_frame_latency: 2,
+    };
+
+    surface.configure(&device, &config);
+
+    let shared = SharedState::new(&device, caps, config.format, config.width, config.height);
+    let mut renderer = Self::assemble(instance, adapter, device, queue, config, shared);
+    renderer.surface = Some(surface);
+    renderer.shared.transparent = choice.transparent;
+    Ok(renderer)
+  }
+
+  /// A renderer with no window or surface, for drawing with
+  /// render_to_texture() alone. `width` x `height` sizes the default
+  /// targets and the camera's aspect.
+  pub async fn new_headless(width: u32, height: u32, adapter: Option<&str>)
+    -> anyhow::Result<Self>
+  {
+    let instance = wgpu::Instance::default();
+    let adapter = Self::select_adapter(&instance, None, adapter).await?;
+
+    let caps = GpuCapabilities::detect(&adapter);
+    let (device, queue) = Self::request_device(&adapter, &caps).await?;
+
+    // Never configured; holds the size and format the targets are built for.
+    let config = wgpu::SurfaceConfiguration {
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+      format: wgpu::TextureFormat::Rgba8UnormSrgb,
+      width: width.max(1),
+      height: height.max(1),
+      present_mode: wgpu::PresentMode::Fifo,
+      alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+      view_formats: vec![],
+      desired_maximum_frame_latency: 2,
+    };
+
+    let shared = SharedState::new(&device, caps, config.format, config.width, config.height);
+    Ok(Self::assemble(instance, adapter, device, queue, config, shared))
+  }
+
+  /// The first adapter whose name contains `wanted` (ignoring case) and
+  /// can present to `surface`. Without a name, or if none matches, the
+  /// default high-performance adapter.
+  async fn select_adapter(
+    instance: &wgpu::Instance,
+    surface: Option<&wgpu::Surface<'static>>,
+    wanted: Option<&str>,
+  ) -> anyhow::Result<wgpu::Adapter>
+  {
+    if let Some(wanted) = wanted
+    {
+      let wanted = wanted.to_lowercase();
+      for adapter in instance.enumerate_adapters(wgpu::Backends::all())
+      {
+        let named = adapter.get_info().name.to_lowercase().contains(&wanted);
+        let presents = surface.is_none_or(|s| adapter.is_surface_supported(s));
+        if named && presents
+        {
+          return Ok(adapter);
+        }
+      }
+    }
+
+    instance
       .request_adapter(&wgpu::RequestAdapterOptions {
         power_preference: wgpu::PowerPreference::HighPerformance,
-        compatible_surface: Some(&surface),
+        compatible_surface: surface,
         force_fallback_adapter: false,
       })
       .await
-      .map_err(|e| anyhow::anyhow!("No suitable GPU adapter found: {:?}", e))?;
+      .map_err(|e| anyhow::anyhow!("No suitable GPU adapter found: {:?}", e))
+  }
 
+  /// Names of every adapter wgpu can see, for choosing one in the config.
+  pub fn adapter_names(&self) -> Vec<String>
+  {
+    let adapters = self.instance.enumerate_adapters(wgpu::Backends::all());
+    adapters.iter().map(|a| format!("{} ({:?})", a.get_info().name, a.get_info().backend)).collect()
+  }
+
+  async fn request_device(
+    adapter: &wgpu::Adapter,
+    caps: &GpuCapabilities,
+  ) -> anyhow::Result<(wgpu::Device, wgpu::Queue)>
+  {
     let (device, queue) = adapter
       .request_device(&wgpu::DeviceDescriptor {
         label: Some("Kyzu Device"),
         required_features: wgpu::Features::empty(),
-        required_limits: wgpu::Limits::default(),
+        required_limits: caps.required_limits.clone(),
         experimental_features: Default::default(),
         trace: wgpu::Trace::default(),
         memory_hints: wgpu::MemoryHints::Performance,
       })
       .await?;
+    Ok((device, queue))
+  }
 
-    let swapchain_capabilities = surface.get_capabilities(&adapter);
-    let swapchain_format = swapchain_capabilities.formats[0];
-
-    let config = wgpu::SurfaceConfiguration {
-      usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-      format: swapchain_format,
-      width: size.width,
-      height: size.height,
-      present_mode: wgpu::PresentMode::Fifo,
-      alpha_mode: swapchain_capabilities.alpha_modes[0],
-      view_formats: vec![],
-      desired_maximum_frame_latency: 2,
-    };
-
-    surface.configure(&device, &config);
-
-    let shared = SharedState::new(&device, config.width, config.height);
+  fn assemble(
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    shared: SharedState,
+  ) -> Self
+  {
+    let lost = Arc::new(Mutex::new(None));
+    let lost_reason = lost.clone();
+    device.set_device_lost_callback(move |reason, message| {
+      *lost_reason.lock().unwrap() = Some(format!("{:?}: {}", reason, message));
+    });
 
     let camera_system = crate::render::camera::CameraSystem::new();
+    let format = shared.surface_format;
 
-    Ok(Self {
+    let mut renderer = Self {
       instance,
-      surface,
+      surface: None,
       adapter,
       device,
       queue,
@@ -78,7 +193,28 @@ impl Renderer
       shared,
       modules: Vec::new(),
       camera_system,
-    })
+      surface_timeouts: 0,
+      pick_target: None,
+      passes: Vec::new(),
+      lost,
+    };
+
+    // FXAA filters the scene; the outline and UI then draw over it unfiltered.
+    renderer.add_pass(FxaaPass::new(&renderer.device, format));
+    renderer.add_pass(OutlinePass::new(&renderer.device, format));
+    renderer
+  }
+
+  /// True once the device is gone; the app must build a new Renderer.
+  pub fn is_lost(&self) -> bool
+  {
+    self.lost.lock().unwrap().is_some()
+  }
+
+  /// Why the device was lost, for the log.
+  pub fn lost_reason(&self) -> Option<String>
+  {
+    self.lost.lock().unwrap().clone()
   }
 
   pub fn update(&mut self, input: &mut InputState, dt: f32) -> anyhow::Result<()>
@@ -99,6 +235,36 @@ impl Renderer
     self.modules.push(Box::new(module));
   }
 
+  /// Run `f` on the first module of type `T` with the device and shared
+  /// state, for changes that need more than update() gets (new GPU
+  /// buffers). None if there is no such module.
+  pub fn with_module<T: RenderModule + 'static, R>(
+    &mut self,
+    f: impl FnOnce(&mut T, &wgpu::Device, &SharedState) -> R,
+  ) -> Option<R>
+  {
+    for module in &mut self.modules
+    {
+      if let Some(module) = module.as_any_mut().downcast_mut::<T>()
+      {
+        return Some(f(module, &self.device, &self.shared));
+      }
+    }
+    None
+  }
+
+  /// Drop every module, to rebuild them for a new scene or sample count.
+  pub fn clear_modules(&mut self)
+  {
+    self.modules.clear();
+  }
+
+  /// Append a frame pass; passes run in the order they were added.
+  pub fn add_pass(&mut self, pass: impl FramePass + 'static)
+  {
+    self.passes.push(Box::new(pass));
+  }
+
   pub fn resize(&mut self, new_size: Option<winit::dpi::PhysicalSize<u32>>)
   {
     if let Some(size) = new_size
@@ -107,15 +273,332 @@ impl Renderer
       {
         self.config.width = size.width;
         self.config.height = size.height;
-        self.surface.configure(&self.device, &self.config);
-        // Update shared depth texture etc here
+        if let Some(surface) = &self.surface
+        {
+          surface.configure(&self.device, &self.config);
+        }
+        self.shared.rebuild_targets(&self.device, size.width, size.height);
+      }
+    }
+  }
+
+  /// Draw the scene with up to `samples` per pixel, lowered to what the
+  /// adapter supports for the surface and depth formats. Call before
+  /// adding modules, whose pipelines are built for this count. Returns the
+  /// count in use.
+  pub fn set_msaa(&mut self, samples: u32) -> u32
+  {
+    let colour = self.adapter.get_texture_format_features(self.shared.surface_format).flags;
+    let depth = self.adapter.get_texture_format_features(self.shared.depth_format).flags;
+
+    let mut chosen = 1;
+    for count in [8, 4, 2]
+    {
+      let supported = colour.sample_count_supported(count)
+        && colour.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
+        && depth.sample_count_supported(count);
+      if count <= samples && supported
+      {
+        chosen = count;
+        break;
+      }
+    }
+
+    self.shared.sample_count = chosen;
+    self.shared.rebuild_targets(&self.device, self.config.width, self.config.height);
+    chosen
+  }
+
+  /// Let the GPU queue up to `frames` frames (clamped to 1..=3) ahead of
+  /// the display. Fewer means less input latency and less throughput.
+  /// Takes effect at the next surface configure.
+  pub fn set_frame_latency(&mut self, frames: u32)
+  {
+    self.config.desired_maximum_frame_latency = frames.clamp(1, 3);
+  }
+
+  /// Reconfigure the surface for `wanted`. Falls back to Fifo, which
+  /// every surface supports, and returns false if `wanted` is not offered
+  /// or there is no surface.
+  pub fn set_present_mode(&mut self, wanted: PresentMode) -> bool
+  {
+    let surface = match &self.surface
+    {
+      Some(surface) => surface,
+      None => return false,
+    };
+    let mode = surface::wgpu_present_mode(wanted);
+    let offered = surface.get_capabilities(&self.adapter).present_modes.contains(&mode);
+
+    self.config.present_mode = wgpu::PresentMode::Fifo;
+    if offered
+    {
+      self.config.present_mode = mode;
+    }
+    surface.configure(&self.device, &self.config);
+    offered
+  }
+
+  /// Switch to degraded quality after the watchdog trips: FXAA and the
+  /// selection outline stop at once. MSAA needs the modules' pipelines
+  /// rebuilt for one sample, which the app does by recreating the renderer
+  /// (see App::rebuild_renderer). Returns a diagnostics line describing
+  /// the adapter for the log.
+  pub fn degrade(&mut self) -> String
+  {
+    self.shared.degraded = true;
+    self.shared.fxaa = false;
+
+    let info = self.adapter.get_info();
+    format!(
+      "GPU: {} ({:?}, {:?}), driver {} {}",
+      info.name, info.backend, info.device_type, info.driver, info.driver_info
+    )
+  }
+
+  /// Render the ID pass and read back the body index nearest `pixel`
+  /// (physical pixels, top-left origin) within `tolerance` pixels. Blocks
+  /// on the GPU, so call it on clicks rather than every frame.
+  pub fn pick(&mut self, pixel: glam::Vec2, tolerance: f32) -> anyhow::Result<Option<usize>>
+  {
+    let reach = glam::Vec2::splat(tolerance.max(0.0));
+    let region = self.pick_region(pixel - reach, pixel + reach + glam::Vec2::ONE)?;
+    Ok(region.nearest_index(pixel.x as u32, pixel.y as u32, tolerance))
+  }
+
+  /// Render the ID pass and read back the pixels between `min` and `max`
+  /// (physical pixels), clipped to the window. Blocks on the GPU like pick().
+  pub fn pick_region(&mut self, min: glam::Vec2, max: glam::Vec2) -> anyhow::Result<IdRegion>
+  {
+    let (width, height) = (self.config.width, self.config.height);
+    let lo = min.max(glam::Vec2::ZERO).as_uvec2();
+    let hi = max.ceil().min(glam::Vec2::new(width as f32, height as f32)).as_uvec2();
+    if hi.x <= lo.x || hi.y <= lo.y
+    {
+      return Ok(IdRegion::empty());
+    }
+
+    let stale = match &self.pick_target
+    {
+      Some(target) => target.width != width || target.height != height,
+      None => true,
+    };
+    if stale
+    {
+      let target = PickTarget::new(&self.device, self.shared.depth_format, width, height);
+      self.pick_target = Some(target);
+    }
+    let target = self.pick_target.as_ref().unwrap();
+
+    let mut encoder = self
+      .device
+      .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Pick Encoder") });
+
+    {
+      let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Pick Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+          view: &target.id_view,
+          resolve_target: None,
+          ops: wgpu::Operations {
+            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+            store: wgpu::StoreOp::Store,
+          },
+          depth_slice: None,
+        })],
+        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+          view: &target.depth_view,
+          depth_ops: Some(wgpu::Operations {
+            load: wgpu::LoadOp::Clear(1.0),
+            store: wgpu::StoreOp::Discard,
+          }),
+          stencil_ops: None,
+        }),
+        ..Default::default()
+      });
+
+      for module in &self.modules
+      {
+        module.encode_pick(&mut pass, &self.shared);
+      }
+    }
+
+    let size = hi - lo;
+    let readback = target.copy_region(&self.device, &mut encoder, (lo.x, lo.y), (size.x, size.y));
+    self.queue.submit([encoder.finish()]);
+
+    readback.read(&self.device)
+  }
+
+  /// Every module, in category order.
+  fn encode_modules(&self, encoder: &mut wgpu::CommandEncoder, targets: &FrameTargets)
+  {
+    for category in RenderCategory::ORDER
+    {
+      for module in &self.modules
+      {
+        if module.category() == category
+        {
+          module.encode(encoder, targets, &self.shared);
+        }
       }
     }
   }
 
-  pub fn render(&mut self) -> anyhow::Result<()>
+  /// Draw the scene and overlay modules into a new `width` x `height`
+  /// texture (surface format), independent of the window. The camera keeps
+  /// its current view with the aspect fitted to the texture; outline, FXAA,
+  /// UI and no_export bodies are left out. The texture can be sampled or copied from.
+  pub fn render_to_texture(&mut self, width: u32, height: u32) -> wgpu::Texture
+  {
+    self.render_jittered(width, height, glam::Vec2::ZERO)
+  }
+
+  /// render_to_texture() with the image shifted by `jitter` pixels, for
+  /// accumulating antialiased stills.
+  pub fn render_jittered(&mut self, width: u32, height: u32, jitter: glam::Vec2) -> wgpu::Texture
   {
-    let frame = match self.surface.get_current_texture()
+    let (width, height) = (width.max(1), height.max(1));
+    let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+      label: Some("Offscreen Colour"),
+      size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: self.shared.surface_format,
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+        | wgpu::TextureUsages::TEXTURE_BINDING
+        | wgpu::TextureUsages::COPY_SRC,
+      view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    // Pipelines are built for sample_count, so the targets must match it.
+    let samples = self.shared.sample_count;
+    let depth_format = self.shared.depth_format;
+    let depth_view =
+      shared::create_target(&self.device, "Offscreen Depth", depth_format, width, height, samples);
+    let mut msaa_view = None;
+    if samples > 1
+    {
+      let format = self.shared.surface_format;
+      msaa_view =
+        Some(shared::create_target(&self.device, "Offscreen MSAA", format, width, height, samples));
+    }
+
+    // Same view, projection re-fitted to the texture's aspect.
+    let window_camera = self.shared.camera;
+    let window_proj = self.shared.projection.matrix();
+    let mut projection = self.shared.projection;
+    projection.aspect = width as f32 / height as f32;
+    let view_matrix =
+      window_proj.inverse() * glam::Mat4::from_cols_array_2d(&window_camera.view_proj);
+    let shift =
+      glam::Vec3::new(2.0 * jitter.x / width as f32, -2.0 * jitter.y / height as f32, 0.0);
+    let view_proj = glam::Mat4::from_translation(shift) * projection.matrix() * view_matrix;
+
+    let mut camera = window_camera;
+    camera.view_proj = view_proj.to_cols_array_2d();
+    camera.inv_view_proj = view_proj.inverse().to_cols_array_2d();
+    self.shared.camera_gpu.upload(&self.queue, &camera);
+
+    let mut encoder = self
+      .device
+      .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Offscreen Encoder") });
+    let mut targets =
+      FrameTargets { surface_view: &view, resolve_view: None, depth_view: &depth_view };
+    if let Some(msaa_view) = &msaa_view
+    {
+      targets.surface_view = msaa_view;
+      targets.resolve_view = Some(&view);
+    }
+
+    self.shared.exporting = true;
+    self.encode_modules(&mut encoder, &targets);
+    self.shared.exporting = false;
+    self.queue.submit([encoder.finish()]);
+
+    // Queued writes land before the next submit, so the window frame gets
+    // its own camera back.
+    self.shared.camera_gpu.upload(&self.queue, &window_camera);
+    texture
+  }
+
+  /// Copy an 8-bit colour texture back to the CPU as RGBA, rows top to
+  /// bottom. Blocks on the GPU.
+  pub fn read_texture(&self, texture: &wgpu::Texture) -> anyhow::Result<Vec<u8>>
+  {
+    let (width, height) = (texture.width(), texture.height());
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_row = (width * 4).div_ceil(align) * align;
+
+    let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("Headless Readback"),
+      size: padded_row as u64 * height as u64,
+      usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+      mapped_at_creation: false,
+    });
+
+    let mut encoder = self
+      .device
+      .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Headless Readback") });
+    encoder.copy_texture_to_buffer(
+      texture.as_image_copy(),
+      wgpu::TexelCopyBufferInfo {
+        buffer: &buffer,
+        layout: wgpu::TexelCopyBufferLayout {
+          offset: 0,
+          bytes_per_row: Some(padded_row),
+          rows_per_image: None,
+        },
+      },
+      wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+    );
+    self.queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    self
+      .device
+      .poll(wgpu::PollType::wait_indefinitely())
+      .map_err(|e| anyhow::anyhow!("Readback failed: {:?}", e))?;
+
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    {
+      let data = slice.get_mapped_range();
+      for row in 0..height
+      {
+        let start = (row * padded_row) as usize;
+        rgba.extend_from_slice(&data[start..start + (width * 4) as usize]);
+      }
+    }
+    buffer.unmap();
+
+    // Swapchain formats are often BGRA.
+    let bgra = matches!(
+      texture.format(),
+      wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+    );
+    if bgra
+    {
+      for pixel in rgba.chunks_exact_mut(4)
+      {
+        pixel.swap(0, 2);
+      }
+    }
+    Ok(rgba)
+  }
+
+  /// Draw one frame: every scene module, then the UI on top if given.
+  /// Does nothing for a headless renderer.
+  pub fn render(&mut self, ui: Option<&mut UiSystem>) -> anyhow::Result<()>
+  {
+    let surface = match &self.surface
+    {
+      Some(surface) => surface,
+      None => return Ok(()),
+    };
+    let frame = match surface.get_current_texture()
     {
       Ok(frame) => frame,
       Err(wgpu::SurfaceError::Outdated) | Err(wgpu::SurfaceError::Lost) =>
@@ -123,7 +606,12 @@ impl Renderer
         self.resize(None);
         return Ok(());
       }
-      Err(wgpu::SurfaceError::Timeout) => return Err(anyhow::anyhow!("Surface timeout")),
+      Err(wgpu::SurfaceError::Timeout) =>
+      {
+        // Skip the frame; the watchdog decides whether this is a pattern.
+        self.surface_timeouts += 1;
+        return Ok(());
+      }
       Err(e) => return Err(anyhow::anyhow!("Surface error: {:?}", e)),
     };
 
@@ -132,16 +620,59 @@ impl Renderer
       .device
       .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Render Encoder") });
 
-    let targets = FrameTargets { surface_view: &view, depth_view: &self.shared.depth_view };
+    let (width, height) = (self.config.width, self.config.height);
+    let mut active = Vec::new();
+    for (index, pass) in self.passes.iter_mut().enumerate()
+    {
+      if pass.prepare(&self.device, &self.queue, &self.shared, width, height)
+      {
+        active.push(index);
+      }
+    }
+
+    // A pass that samples the scene makes everything before it draw into
+    // its input rather than the swapchain.
+    let inputs: Vec<Option<&wgpu::TextureView>> =
+      active.iter().map(|&index| self.passes[index].input_view()).collect();
+    let (scene_view, outputs) = module::chain_targets(&inputs, &view);
 
-    for module in &self.modules
+    let mut targets = FrameTargets {
+      surface_view: scene_view,
+      resolve_view: None,
+      depth_view: &self.shared.depth_view,
+    };
+    if let Some(msaa_view) = &self.shared.msaa_view
+    {
+      targets.surface_view = msaa_view;
+      targets.resolve_view = Some(scene_view);
+    }
+
+    self.encode_modules(&mut encoder, &targets);
+
+    for (slot, &index) in active.iter().enumerate()
     {
-      module.encode(&mut encoder, &targets, &self.shared);
+      self.passes[index].encode(&mut encoder, outputs[slot], &self.modules, &self.shared);
     }
 
-    self.queue.submit(std::iter::once(encoder.finish()));
+    let mut command_buffers = Vec::new();
+    if let Some(ui) = ui
+    {
+      let size = [self.config.width, self.config.height];
+      command_buffers = ui.paint(&self.device, &self.queue, &mut encoder, &view, size);
+    }
+
+    command_buffers.push(encoder.finish());
+    self.queue.submit(command_buffers);
     frame.present();
 
     Ok(())
   }
 }
+
+/// A small headless renderer for tests that need a device. None on machines
+/// without any adapter (not even a software one), where such tests skip.
+#[cfg(test)]
+pub(crate) fn test_renderer() -> Option<Renderer>
+{
+  pollster::block_on(Renderer::new_headless(64, 64, None)).ok()
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use wgpu::{BindGroupLayout, Device, Queue, RenderPipeline, Sampler, Texture, TextureFormat};
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Mipmap generation
+//
+//  Fills levels 1.. of an uploaded texture on the GPU, each one a filtered
+//  half of the level above. Sampling an sRGB view decodes to linear and
+//  rendering to one encodes again, so colour textures are averaged in
+//  linear light.
+//
+//  One pipeline per texture format, built the first time that format is
+//  seen.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Levels in a full chain down to 1×1.
+pub fn mip_level_count(width: u32, height: u32) -> u32
+{
+  32 - width.max(height).max(1).leading_zeros()
+}
+
+pub struct MipGenerator
+{
+  bgl: BindGroupLayout,
+  sampler: Sampler,
+  pipelines: HashMap<TextureFormat, RenderPipeline>,
+}
+
+impl MipGenerator
+{
+  pub fn new(device: &Device) -> Self
+  {
+    let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: Some("Mipmap BGL"),
+      entries: &[
+        wgpu::BindGroupLayoutEntry {
+          binding: 0,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+          },
+          count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+          binding: 1,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+          count: None,
+        },
+      ],
+    });
+
+    // Clamped so edge texels aren't blended with the opposite edge.
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+      label: Some("Mipmap Sampler"),
+      address_mode_u: wgpu::AddressMode::ClampToEdge,
+      address_mode_v: wgpu::AddressMode::ClampToEdge,
+      mag_filter: wgpu::FilterMode::Linear,
+      min_filter: wgpu::FilterMode::Linear,
+      ..Default::default()
+    });
+
+    Self { bgl, sampler, pipelines: HashMap::new() }
+  }
+
+  /// Render every level after the first from the one above it. The texture
+  /// needs TEXTURE_BINDING and RENDER_ATTACHMENT usage and level 0 filled.
+  pub fn generate(&mut self, device: &Device, queue: &Queue, texture: &Texture)
+  {
+    let levels = texture.mip_level_count();
+    if levels < 2
+    {
+      return;
+    }
+
+    let format = texture.format();
+    if !self.pipelines.contains_key(&format)
+    {
+      let pipeline = Self::create_pipeline(device, &self.bgl, format);
+      self.pipelines.insert(format, pipeline);
+    }
+    let pipeline = &self.pipelines[&format];
+
+    let level_view = |level: u32| {
+      texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some("Mip Level"),
+        base_mip_level: level,
+        mip_level_count: Some(1),
+        ..Default::default()
+      })
+    };
+
+    let mut encoder =
+      device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Mipmaps") });
+    for level in 1..levels
+    {
+      let source = level_view(level - 1);
+      let target = level_view(level);
+      let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Mipmap BG"),
+        layout: &self.bgl,
+        entries: &[
+          wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::TextureView(&source),
+          },
+          wgpu::BindGroupEntry {
+            binding: 1,
+            resource: wgpu::BindingResource::Sampler(&self.sampler),
+          },
+        ],
+      });
+
+      let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Mipmap Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+          view: &target,
+          resolve_target: None,
+          ops: wgpu::Operations {
+            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+            store: wgpu::StoreOp::Store,
+          },
+          depth_slice: None,
+        })],
+        ..Default::default()
+      });
+      pass.set_pipeline(pipeline);
+      pass.set_bind_group(0, &bind_group, &[]);
+      pass.draw(0..3, 0..1);
+    }
+    queue.submit(Some(encoder.finish()));
+  }
+
+  fn create_pipeline(
+    device: &Device,
+    bgl: &BindGroupLayout,
+    format: TextureFormat,
+  ) -> RenderPipeline
+  {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/mipmap.wgsl"));
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("Mipmap Pipeline Layout"),
+      bind_group_layouts: &[bgl],
+      push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Mipmap Pipeline"),
+      layout: Some(&layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers: &[],
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: Some("fs_main"),
+        compilation_options: Default::default(),
+        targets: &[Some(wgpu::ColorTargetState {
+          format,
+          blend: None,
+          write_mask: wgpu::ColorWrites::ALL,
+        })],
+      }),
+      primitive: wgpu::PrimitiveState::default(),
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default(),
+      multiview: None,
+      cache: None,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn chain_runs_down_to_one_pixel()
+  {
+    assert_eq!(mip_level_count(1, 1), 1);
+    assert_eq!(mip_level_count(2, 2), 2);
+    assert_eq!(mip_level_count(256, 256), 9);
+    assert_eq!(mip_level_count(300, 17), 9);
+    assert_eq!(mip_level_count(0, 0), 1);
+  }
+}
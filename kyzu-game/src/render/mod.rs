@@ -1,6 +1,17 @@
 pub mod camera;
+pub mod capabilities;
 pub mod depth;
+pub mod fxaa;
 pub mod kernel;
+pub mod mipmap;
 pub mod module;
 pub mod modules;
+pub mod outline;
+pub mod picking;
+pub mod primitives;
+pub mod raycast;
 pub mod shared;
+pub mod still;
+pub mod surface;
+pub mod texture;
+pub mod watchdog;
@@ -1,14 +1,135 @@
 use std::any::Any;
 
-use wgpu::{CommandEncoder, Queue};
+use wgpu::{CommandEncoder, Device, Queue, RenderPass, TextureView};
 
 pub use crate::render::shared::{FrameTargets, SharedState};
 
+/// When a module is encoded. All Scene modules run before any Overlay
+/// module, so tool visuals (gizmos, markers, measurement previews) are
+/// drawn over finished geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderCategory
+{
+  /// Depth-tested world geometry.
+  Scene,
+  /// Drawn after the scene with no depth attachment; never hidden by geometry.
+  Overlay,
+}
+
+impl RenderCategory
+{
+  /// Encode order for a frame. A new category goes here, not in the kernel.
+  pub const ORDER: [RenderCategory; 2] = [RenderCategory::Scene, RenderCategory::Overlay];
+}
+
 pub trait RenderModule: Send + Sync
 {
+  fn category(&self) -> RenderCategory
+  {
+    RenderCategory::Scene
+  }
+
   fn update(&mut self, queue: &Queue, shared: &SharedState);
 
   fn encode(&self, encoder: &mut CommandEncoder, targets: &FrameTargets, shared: &SharedState);
 
+  /// Draw pickable geometry into the R32Uint pick pass (see render::picking).
+  /// Modules with nothing to pick leave this empty.
+  fn encode_pick(&self, _pass: &mut RenderPass<'_>, _shared: &SharedState) {}
+
+  /// Draw selected geometry into the outline mask (see render::outline).
+  /// Modules with nothing selectable leave this empty.
+  fn encode_mask(&self, _pass: &mut RenderPass<'_>, _shared: &SharedState) {}
+
   fn as_any_mut(&mut self) -> &mut dyn Any;
 }
+
+/// A fullscreen step after the modules (FXAA, selection outline). Passes
+/// run in the order the renderer registered them. One with an input view
+/// reads the scene: everything before it draws into that view instead of
+/// the frame (see chain_targets).
+pub trait FramePass: Send + Sync
+{
+  /// Allocate or resize for a `width` x `height` frame. False skips the
+  /// pass this frame.
+  fn prepare(
+    &mut self,
+    device: &Device,
+    queue: &Queue,
+    shared: &SharedState,
+    width: u32,
+    height: u32,
+  ) -> bool;
+
+  /// Texture this pass samples, which the stage before it must draw into.
+  fn input_view(&self) -> Option<&TextureView>
+  {
+    None
+  }
+
+  fn encode(
+    &self,
+    encoder: &mut CommandEncoder,
+    output: &TextureView,
+    modules: &[Box<dyn RenderModule>],
+    shared: &SharedState,
+  );
+}
+
+/// Where the modules and each active pass draw, given the passes' inputs in
+/// run order. Every stage writes into the next input along, and the last
+/// ones into `frame`. Returns (module target, pass outputs).
+pub fn chain_targets<T: Copy>(inputs: &[Option<T>], frame: T) -> (T, Vec<T>)
+{
+  let mut outputs = vec![frame; inputs.len()];
+  let mut next = frame;
+  for (slot, input) in inputs.iter().enumerate().rev()
+  {
+    outputs[slot] = next;
+    if let Some(input) = input
+    {
+      next = *input;
+    }
+  }
+  (next, outputs)
+}
+
+/// Start a pass for an Overlay module: keeps the scene's colour and has no
+/// depth attachment, so pipelines used in it must set depth_stencil: None.
+pub fn begin_overlay_pass<'a>(
+  encoder: &'a mut CommandEncoder,
+  targets: &FrameTargets,
+  label: &str,
+) -> RenderPass<'a>
+{
+  encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+    label: Some(label),
+    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+      view: targets.surface_view,
+      resolve_target: targets.resolve_view,
+      ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+      depth_slice: None,
+    })],
+    ..Default::default()
+  })
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn passes_feed_the_next_reader()
+  {
+    // No readers: everything draws straight onto the frame.
+    assert_eq!(chain_targets(&[None, None], 0), (0, vec![0, 0]));
+
+    // FXAA (input 1) then the outline: the scene goes to FXAA's input,
+    // FXAA and the outline both write the frame.
+    assert_eq!(chain_targets(&[Some(1), None], 0), (1, vec![0, 0]));
+
+    // Two readers chain: the first writes into the second's input.
+    assert_eq!(chain_targets(&[Some(1), None, Some(2)], 0), (1, vec![2, 2, 0]));
+  }
+}
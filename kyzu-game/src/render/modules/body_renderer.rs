@@ -1,29 +1,31 @@
 use std::any::Any;
 use std::path::Path;
+use std::sync::Arc;
 
 use bytemuck::{Pod, Zeroable};
-use glam::{DVec3, Mat4, Quat, Vec3, Vec4};
+use glam::{DVec3, Mat4, Vec3, Vec4};
 use wgpu::util::DeviceExt;
 use wgpu::{include_wgsl, BindGroup, BindGroupLayout, Buffer, Queue};
 
-use crate::bake::geometry::BakedVertex;
+use crate::bake::geometry::{self, BakedVertex};
+use crate::bake::obj_reader::ObjMaterial;
 use crate::core::log::{LogLevel, Logger};
+use crate::core::png;
+use crate::render::camera::projection::RENDER_SCALE;
 use crate::render::module::{FrameTargets, RenderModule};
+use crate::render::outline::MASK_FORMAT;
+use crate::render::picking::PICK_FORMAT;
+use crate::render::primitives::{self, Primitive};
+use crate::render::raycast::Bvh;
 use crate::render::shared::SharedState;
+use crate::render::texture::{self, SamplerKind};
 use crate::world::body::BodyKind;
 use crate::world::registry::BodyState;
 
-// ─────────────────────────────────────────────────────────────────────────────
-//  Scale constant
-//
-//  Bodies are stored internally in metres (f64). For rendering we work in
-//  units of 1 000 km so f32 precision is adequate across the visible scene.
-//    Earth radius  =    6.371 units
-//    Sun radius    =  695.7   units
-//    1 AU          =  149 598 units
-// ─────────────────────────────────────────────────────────────────────────────
-
-const RENDER_SCALE: f64 = 1_000_000.0; // 1 render unit = 1 000 km
+/// Strength of the emissive selection tint (colour from the palette).
+const SELECTION_TINT_STRENGTH: f32 = 0.35;
+/// Material colours a body mesh can carry; must match body.wgsl.
+const MAX_MATERIALS: usize = 16;
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  BodyUniforms — must match body.wgsl layout exactly
@@ -37,30 +39,84 @@ struct BodyUniforms
   base_color: [f32; 4],
   light_dir: [f32; 3],
   is_star: u32,
+  /// Emissive tint added on top of lighting: rgb colour, a strength.
+  highlight: [f32; 4],
+  /// 0 = shaded, 1 = checker, 2 = field (DisplayMode).
+  display_mode: u32,
+  /// Elevation clamp range for the field colour map, in metres.
+  field_min: f32,
+  field_max: f32,
+  /// 1 when the body has its own albedo texture bound in group 2.
+  textured: u32,
+  /// Colour map stops, low to high (rgb, a unused).
+  field_stops: [[f32; 4]; 5],
+  /// Entries of `materials` in use; 0 keeps base_color.
+  material_count: u32,
+  /// 1 when the body is lit all round (RenderFlags::no_shadow).
+  unshadowed: u32,
+  _pad: [u32; 2],
+  /// Diffuse colour per material index, read from each vertex's hex_id.
+  materials: [[f32; 4]; MAX_MATERIALS],
 }
 
-// ─────────────────────────────────────────────────────────────────────────────
-//  GpuBody — per-body GPU resources
-// ─────────────────────────────────────────────────────────────────────────────
-
-struct GpuBody
+/// A body's own baked mesh (Manmade bodies), drawn in place of the shared
+/// icosphere.
+struct BodyMesh
 {
   vertex_buffer: Buffer,
   vertex_count: u32,
-  uniforms_buffer: Buffer,
-  bind_group: BindGroup,
+  /// Diffuse colours by material index (rgb, a unused).
+  materials: Vec<[f32; 4]>,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  BodyRenderer
 // ─────────────────────────────────────────────────────────────────────────────
 
+// Bodies share one icosphere vertex buffer unless load_meshes gave them a
+// baked mesh of their own, and all share one uniform buffer. Each body
+// owns a `uniform_stride`-sized slot in the uniform buffer, selected per
+// draw with a dynamic offset on bind group 1. Bind group 2 holds the
+// body's albedo texture: a shared 1×1 white one unless load_textures found
+// an image for it.
+
 pub struct BodyRenderer
 {
   pipeline: wgpu::RenderPipeline,
-  #[allow(dead_code)]
+  /// Depth test off, for bodies flagged always-on-top.
+  on_top_pipeline: wgpu::RenderPipeline,
+  pick_pipeline: wgpu::RenderPipeline,
+  pick_on_top_pipeline: wgpu::RenderPipeline,
+  mask_pipeline: wgpu::RenderPipeline,
   body_bgl: BindGroupLayout,
-  gpu_bodies: Vec<Option<GpuBody>>,
+  texture_bgl: BindGroupLayout,
+  /// Group 2 for a body without a texture of its own.
+  white_bind_group: BindGroup,
+  /// Group 2 per body slot; entries are clones of the white default until
+  /// load_textures replaces them.
+  texture_bind_groups: Vec<BindGroup>,
+  /// Which body slots have a texture of their own.
+  textured: Vec<bool>,
+  /// Per body slot; None draws the shared icosphere.
+  meshes: Vec<Option<BodyMesh>>,
+  vertex_buffer: Buffer,
+  vertex_count: u32,
+  uniforms_buffer: Buffer,
+  uniforms_bind_group: BindGroup,
+  /// Bytes between body slots: BodyUniforms rounded up to the device's
+  /// min_uniform_buffer_offset_alignment.
+  uniform_stride: u64,
+  /// Number of slots allocated; bodies spawned later are not drawn until
+  /// add_bodies() makes room for them.
+  body_capacity: usize,
+  /// CPU copy of every slot, uploaded with one write per frame.
+  staging: Vec<u8>,
+  /// CPU copy of the shared mesh for ray casts (see raycast.rs).
+  mesh_bvh: Arc<Bvh>,
+  /// Per-vertex UVs of the shared mesh, for the UV panel.
+  mesh_uvs: Arc<Vec<[f32; 2]>>,
+  /// Lowest and highest vertex elevation in the mesh, in metres.
+  height_range: [f32; 2],
   sun_pos_render: Vec3,
 }
 
@@ -68,7 +124,8 @@ impl BodyRenderer
 {
   pub fn new(
     device: &wgpu::Device,
-    shared: &SharedState,
+    queue: &Queue,
+    shared: &mut SharedState,
     mesh_path: &Path,
     logger: &mut Logger,
   ) -> Self
@@ -76,18 +133,18 @@ impl BodyRenderer
     let shader = device.create_shader_module(include_wgsl!("../shaders/body.wgsl"));
 
     // ── Load shared icosphere mesh ────────────────────────────────────────
-    let mesh_data = std::fs::read(mesh_path).expect("Failed to load icosphere mesh");
+    let vertices = geometry::load_bake(mesh_path).expect("Failed to load icosphere mesh");
     logger.emit(
       LogLevel::Info,
-      &format!("BodyRenderer: loaded mesh {} ({} bytes)", mesh_path.display(), mesh_data.len()),
+      &format!("BodyRenderer: loaded mesh {} ({} vertices)", mesh_path.display(), vertices.len()),
     );
+    let vertices = vertices.as_slice();
 
     let vertex_size = std::mem::size_of::<BakedVertex>();
-    let v_count = u32::from_le_bytes(mesh_data[0..4].try_into().unwrap()) as usize;
-    let vertex_data_end = 4 + v_count * vertex_size;
-    let vertices: &[BakedVertex] = bytemuck::cast_slice(&mesh_data[4..vertex_data_end]);
+    let v_count = vertices.len();
 
     // ── Bind group layout (group 1) ───────────────────────────────────────
+    let uniforms_size = std::mem::size_of::<BodyUniforms>() as u64;
     let body_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
       label: Some("Body BGL"),
       entries: &[wgpu::BindGroupLayoutEntry {
@@ -95,29 +152,397 @@ impl BodyRenderer
         visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
         ty: wgpu::BindingType::Buffer {
           ty: wgpu::BufferBindingType::Uniform,
-          has_dynamic_offset: false,
-          min_binding_size: None,
+          has_dynamic_offset: true,
+          min_binding_size: wgpu::BufferSize::new(uniforms_size),
         },
         count: None,
       }],
     });
 
+    // ── Bind group layout (group 2) ───────────────────────────────────────
+    let texture_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: Some("Body Texture BGL"),
+      entries: &[
+        wgpu::BindGroupLayoutEntry {
+          binding: 0,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+          },
+          count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+          binding: 1,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+          count: None,
+        },
+      ],
+    });
+
     // ── Pipeline ─────────────────────────────────────────────────────────
+    // The pick and mask shaders never sample, so they keep a two-group layout.
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-      label: Some("Body Pipeline Layout"),
+      label: Some("Body Pick Pipeline Layout"),
       bind_group_layouts: &[&shared.camera_gpu.layout, &body_bgl],
       push_constant_ranges: &[],
     });
+    let textured_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("Body Pipeline Layout"),
+      bind_group_layouts: &[&shared.camera_gpu.layout, &body_bgl, &texture_bgl],
+      push_constant_ranges: &[],
+    });
+
+    let pipeline = Self::create_body_pipeline(
+      device,
+      &textured_layout,
+      &shader,
+      vertex_size as u64,
+      shared,
+      wgpu::CompareFunction::Less,
+      "Body Render Pipeline",
+    );
+    let on_top_pipeline = Self::create_body_pipeline(
+      device,
+      &textured_layout,
+      &shader,
+      vertex_size as u64,
+      shared,
+      wgpu::CompareFunction::Always,
+      "Body On-Top Pipeline",
+    );
+
+    let pick_pipeline = Self::create_pick_pipeline(
+      device,
+      &pipeline_layout,
+      vertex_size as u64,
+      shared,
+      wgpu::CompareFunction::Less,
+    );
+    let pick_on_top_pipeline = Self::create_pick_pipeline(
+      device,
+      &pipeline_layout,
+      vertex_size as u64,
+      shared,
+      wgpu::CompareFunction::Always,
+    );
+    let mask_pipeline = Self::create_mask_pipeline(device, &pipeline_layout, vertex_size as u64);
+
+    // ── Shared GPU resources ──────────────────────────────────────────────
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: Some("Body VB (icosphere)"),
+      contents: bytemuck::cast_slice(vertices),
+      usage: wgpu::BufferUsages::VERTEX,
+    });
+
+    let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+    let uniform_stride = uniforms_size.div_ceil(alignment) * alignment;
+    let body_capacity = shared.body_registry.bodies.len().max(1);
+
+    let (uniforms_buffer, uniforms_bind_group) =
+      Self::create_uniforms(device, &body_bgl, uniform_stride, body_capacity);
+
+    let white = texture::white(device, queue);
+    let sampler = shared.samplers.get(device, SamplerKind::Linear(shared.filtering));
+    let white_bind_group = Self::texture_bind_group(device, &texture_bgl, &white.view, &sampler);
+
+    Self {
+      pipeline,
+      on_top_pipeline,
+      pick_pipeline,
+      pick_on_top_pipeline,
+      mask_pipeline,
+      body_bgl,
+      texture_bgl,
+      texture_bind_groups: vec![white_bind_group.clone(); body_capacity],
+      white_bind_group,
+      textured: vec![false; body_capacity],
+      meshes: (0..body_capacity).map(|_| None).collect(),
+      vertex_buffer,
+      vertex_count: v_count as u32,
+      uniforms_buffer,
+      uniforms_bind_group,
+      uniform_stride,
+      body_capacity,
+      staging: vec![0u8; (uniform_stride as usize) * body_capacity],
+      mesh_bvh: Arc::new(Bvh::from_vertices(vertices)),
+      mesh_uvs: Arc::new(vertices.iter().map(|v| v.uv).collect()),
+      height_range: Self::height_range_of(vertices),
+      sun_pos_render: Vec3::ZERO,
+    }
+  }
+
+  /// One BodyUniforms slot per body, `stride` bytes apart.
+  fn create_uniforms(
+    device: &wgpu::Device,
+    layout: &BindGroupLayout,
+    stride: u64,
+    capacity: usize,
+  ) -> (Buffer, BindGroup)
+  {
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("Body Uniforms"),
+      size: stride * capacity as u64,
+      usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some("Body Uniforms BG"),
+      layout,
+      entries: &[wgpu::BindGroupEntry {
+        binding: 0,
+        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+          buffer: &buffer,
+          offset: 0,
+          size: wgpu::BufferSize::new(std::mem::size_of::<BodyUniforms>() as u64),
+        }),
+      }],
+    });
+    (buffer, bind_group)
+  }
+
+  /// Make room for bodies spawned since construction (the Insert menu) and
+  /// build the meshes of any generated ones.
+  pub fn add_bodies(&mut self, device: &wgpu::Device, shared: &SharedState)
+  {
+    let count = shared.body_registry.bodies.len();
+    if count > self.body_capacity
+    {
+      let (buffer, bind_group) =
+        Self::create_uniforms(device, &self.body_bgl, self.uniform_stride, count);
+      self.uniforms_buffer = buffer;
+      self.uniforms_bind_group = bind_group;
+      self.texture_bind_groups.resize(count, self.white_bind_group.clone());
+      self.textured.resize(count, false);
+      self.meshes.resize_with(count, || None);
+      self.staging.resize(self.uniform_stride as usize * count, 0);
+      self.body_capacity = count;
+    }
+
+    for (index, body) in shared.body_registry.bodies.iter().enumerate()
+    {
+      if let (Some(primitive), None) = (body.primitive, &self.meshes[index])
+      {
+        self.set_primitive(device, index, primitive);
+      }
+    }
+  }
+
+  /// Draw body slot `index` as a generated shape, in its base colour.
+  fn set_primitive(&mut self, device: &wgpu::Device, index: usize, primitive: Primitive)
+  {
+    let (vertices, indices) = primitive.mesh();
+    self.set_mesh(device, index, &primitives::unweld(&vertices, &indices), Vec::new());
+  }
+
+  /// Draw body slot `index` with `vertices` (an unwelded triangle list)
+  /// instead of the shared icosphere.
+  fn set_mesh(
+    &mut self,
+    device: &wgpu::Device,
+    index: usize,
+    vertices: &[BakedVertex],
+    materials: Vec<[f32; 4]>,
+  )
+  {
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: Some("Body VB (mesh)"),
+      contents: bytemuck::cast_slice(vertices),
+      usage: wgpu::BufferUsages::VERTEX,
+    });
+    self.meshes[index] =
+      Some(BodyMesh { vertex_buffer, vertex_count: vertices.len() as u32, materials });
+  }
+
+  /// Give each body whose name matches a `<name>.png` in `texture_dir` that
+  /// image as its albedo. Unreadable images are logged and skipped.
+  pub fn load_textures(
+    &mut self,
+    device: &wgpu::Device,
+    queue: &Queue,
+    shared: &mut SharedState,
+    texture_dir: &Path,
+    logger: &mut Logger,
+  )
+  {
+    let sampler = shared.samplers.get(device, SamplerKind::Linear(shared.filtering));
+    let body_count = shared.body_registry.bodies.len().min(self.body_capacity);
+
+    for index in 0..body_count
+    {
+      let name = shared.body_registry.bodies[index].manifest.name.to_lowercase();
+      let path = texture_dir.join(format!("{}.png", name));
+      if !path.exists()
+      {
+        continue;
+      }
 
-    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-      label: Some("Body Render Pipeline"),
-      layout: Some(&pipeline_layout),
+      let image = match png::read_rgba(&path)
+      {
+        Ok(image) => image,
+        Err(e) =>
+        {
+          logger.emit(LogLevel::Warning, &format!("Skipping texture {}: {}", path.display(), e));
+          continue;
+        }
+      };
+
+      let label = format!("{} Albedo", name);
+      let mips = Some(&mut shared.mipmaps);
+      let albedo = texture::upload_rgba8(device, queue, mips, &label, &image, true);
+      self.texture_bind_groups[index] =
+        Self::texture_bind_group(device, &self.texture_bgl, &albedo.view, &sampler);
+      self.textured[index] = true;
+      let (width, height) = (image.0, image.1);
+      logger
+        .emit(LogLevel::Info, &format!("Loaded texture {} ({}x{})", path.display(), width, height));
+    }
+  }
+
+  /// Give each Manmade body its own `<name>.bake` from `bake_dir`, coloured
+  /// by the `<name>.materials.json` written beside it, and each generated
+  /// body its primitive. A body whose bake is missing keeps the shared
+  /// icosphere.
+  pub fn load_meshes(
+    &mut self,
+    device: &wgpu::Device,
+    shared: &SharedState,
+    bake_dir: &Path,
+    logger: &mut Logger,
+  )
+  {
+    let body_count = shared.body_registry.bodies.len().min(self.body_capacity);
+
+    for index in 0..body_count
+    {
+      let body = &shared.body_registry.bodies[index];
+      let manifest = &body.manifest;
+      if let Some(primitive) = body.primitive
+      {
+        self.set_primitive(device, index, primitive);
+        continue;
+      }
+      if !matches!(manifest.kind, BodyKind::Manmade)
+      {
+        continue;
+      }
+
+      let name = manifest.name.to_lowercase();
+      let vertices = match geometry::load_bake(&bake_dir.join(format!("{}.bake", name)))
+      {
+        Ok(vertices) => vertices,
+        Err(e) =>
+        {
+          logger.emit(LogLevel::Warning, &format!("{}; drawing {} as a sphere", e, manifest.name));
+          continue;
+        }
+      };
+      let materials =
+        Self::load_materials(&bake_dir.join(format!("{}.materials.json", name)), logger);
+
+      logger.emit(
+        LogLevel::Info,
+        &format!(
+          "Loaded mesh for {} ({} triangles, {} materials)",
+          manifest.name,
+          vertices.len() / 3,
+          materials.len()
+        ),
+      );
+      self.set_mesh(device, index, &vertices, materials);
+    }
+  }
+
+  /// Diffuse colours by material index. A missing or unreadable sidecar
+  /// leaves the mesh in its body's base colour.
+  fn load_materials(path: &Path, logger: &mut Logger) -> Vec<[f32; 4]>
+  {
+    let text = match std::fs::read_to_string(path)
+    {
+      Ok(text) => text,
+      Err(_) => return Vec::new(),
+    };
+    let materials: Vec<ObjMaterial> = match serde_json::from_str(&text)
+    {
+      Ok(materials) => materials,
+      Err(e) =>
+      {
+        logger.emit(LogLevel::Warning, &format!("Skipping materials {}: {}", path.display(), e));
+        return Vec::new();
+      }
+    };
+
+    if materials.len() > MAX_MATERIALS
+    {
+      logger.emit(
+        LogLevel::Warning,
+        &format!(
+          "{}: {} materials, only the first {} are coloured",
+          path.display(),
+          materials.len(),
+          MAX_MATERIALS
+        ),
+      );
+    }
+    materials
+      .iter()
+      .take(MAX_MATERIALS)
+      .map(|m| [m.diffuse[0], m.diffuse[1], m.diffuse[2], 1.0])
+      .collect()
+  }
+
+  /// Vertex buffer and count for a body slot: its own mesh if it has one,
+  /// otherwise the shared icosphere.
+  fn mesh_of(&self, index: usize) -> (&Buffer, u32)
+  {
+    match &self.meshes[index]
+    {
+      Some(mesh) => (&mesh.vertex_buffer, mesh.vertex_count),
+      None => (&self.vertex_buffer, self.vertex_count),
+    }
+  }
+
+  fn texture_bind_group(
+    device: &wgpu::Device,
+    layout: &BindGroupLayout,
+    view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+  ) -> BindGroup
+  {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some("Body Texture BG"),
+      layout,
+      entries: &[
+        wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(view) },
+        wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(sampler) },
+      ],
+    })
+  }
+
+  /// The lit body pipeline. `depth_compare` is Always for the on-top
+  /// variant, which draws after everything else.
+  fn create_body_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    vertex_size: u64,
+    shared: &SharedState,
+    depth_compare: wgpu::CompareFunction,
+    label: &str,
+  ) -> wgpu::RenderPipeline
+  {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some(label),
+      layout: Some(layout),
       vertex: wgpu::VertexState {
-        module: &shader,
+        module: shader,
         entry_point: Some("vs_main"),
         compilation_options: Default::default(),
         buffers: &[wgpu::VertexBufferLayout {
-          array_stride: vertex_size as u64,
+          array_stride: vertex_size,
           step_mode: wgpu::VertexStepMode::Vertex,
           attributes: &wgpu::vertex_attr_array![
               0 => Float32x3, // position
@@ -130,11 +555,11 @@ impl BodyRenderer
         }],
       },
       fragment: Some(wgpu::FragmentState {
-        module: &shader,
+        module: shader,
         entry_point: Some("fs_main"),
         compilation_options: Default::default(),
         targets: &[Some(wgpu::ColorTargetState {
-          format: wgpu::TextureFormat::Bgra8UnormSrgb,
+          format: shared.surface_format,
           blend: Some(wgpu::BlendState::REPLACE),
           write_mask: wgpu::ColorWrites::ALL,
         })],
@@ -145,64 +570,183 @@ impl BodyRenderer
         ..Default::default()
       },
       depth_stencil: Some(wgpu::DepthStencilState {
-        format: wgpu::TextureFormat::Depth32Float,
+        format: shared.depth_format,
         depth_write_enabled: true,
-        depth_compare: wgpu::CompareFunction::Less,
+        depth_compare,
         stencil: wgpu::StencilState::default(),
         bias: wgpu::DepthBiasState::default(),
       }),
-      multisample: wgpu::MultisampleState::default(),
+      multisample: wgpu::MultisampleState { count: shared.sample_count, ..Default::default() },
       multiview: None,
       cache: None,
-    });
+    })
+  }
 
-    // ── Per-body GPU resources ────────────────────────────────────────────
-    let mut gpu_bodies: Vec<Option<GpuBody>> = Vec::new();
+  /// Same transform as the main pipeline, writing body index + 1 to the
+  /// R32Uint pick target. Only the position attribute is read.
+  fn create_pick_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    vertex_size: u64,
+    shared: &SharedState,
+    depth_compare: wgpu::CompareFunction,
+  ) -> wgpu::RenderPipeline
+  {
+    let shader = device.create_shader_module(include_wgsl!("../shaders/body_pick.wgsl"));
 
-    for body_state in &shared.body_registry.bodies
-    {
-      let name = &body_state.manifest.name;
-
-      let body_vb = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some(&format!("Body VB ({})", name)),
-        contents: bytemuck::cast_slice(vertices),
-        usage: wgpu::BufferUsages::VERTEX,
-      });
-
-      let placeholder = BodyUniforms {
-        model_mat: Mat4::IDENTITY.to_cols_array_2d(),
-        base_color: [1.0, 1.0, 1.0, 1.0],
-        light_dir: [0.0, 1.0, 0.0],
-        is_star: 0,
-      };
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Body Pick Pipeline"),
+      layout: Some(layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers: &[wgpu::VertexBufferLayout {
+          array_stride: vertex_size,
+          step_mode: wgpu::VertexStepMode::Vertex,
+          attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+        }],
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: Some("fs_main"),
+        compilation_options: Default::default(),
+        targets: &[Some(wgpu::ColorTargetState {
+          format: PICK_FORMAT,
+          blend: None,
+          write_mask: wgpu::ColorWrites::ALL,
+        })],
+      }),
+      primitive: wgpu::PrimitiveState {
+        topology: wgpu::PrimitiveTopology::TriangleList,
+        cull_mode: Some(wgpu::Face::Back),
+        ..Default::default()
+      },
+      depth_stencil: Some(wgpu::DepthStencilState {
+        format: shared.depth_format,
+        depth_write_enabled: true,
+        depth_compare,
+        stencil: wgpu::StencilState::default(),
+        bias: wgpu::DepthBiasState::default(),
+      }),
+      multisample: wgpu::MultisampleState::default(),
+      multiview: None,
+      cache: None,
+    })
+  }
 
-      let uniforms_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some(&format!("Body Uniforms ({})", name)),
-        contents: bytemuck::bytes_of(&placeholder),
-        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-      });
+  /// Selection mask: same transform as the pick pass, writes 1.0 to an R8
+  /// target with no depth so hidden selections still get an outline.
+  fn create_mask_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    vertex_size: u64,
+  ) -> wgpu::RenderPipeline
+  {
+    let shader = device.create_shader_module(include_wgsl!("../shaders/body_pick.wgsl"));
 
-      let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        label: Some(&format!("Body BG ({})", name)),
-        layout: &body_bgl,
-        entries: &[wgpu::BindGroupEntry {
-          binding: 0,
-          resource: uniforms_buffer.as_entire_binding(),
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Body Mask Pipeline"),
+      layout: Some(layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers: &[wgpu::VertexBufferLayout {
+          array_stride: vertex_size,
+          step_mode: wgpu::VertexStepMode::Vertex,
+          attributes: &wgpu::vertex_attr_array![0 => Float32x3],
         }],
-      });
-
-      gpu_bodies.push(Some(GpuBody {
-        vertex_buffer: body_vb,
-        vertex_count: v_count as u32,
-        uniforms_buffer,
-        bind_group,
-      }));
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: Some("fs_mask"),
+        compilation_options: Default::default(),
+        targets: &[Some(wgpu::ColorTargetState {
+          format: MASK_FORMAT,
+          blend: None,
+          write_mask: wgpu::ColorWrites::ALL,
+        })],
+      }),
+      primitive: wgpu::PrimitiveState {
+        topology: wgpu::PrimitiveTopology::TriangleList,
+        cull_mode: Some(wgpu::Face::Back),
+        ..Default::default()
+      },
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default(),
+      multiview: None,
+      cache: None,
+    })
+  }
+
+  /// Drawable body indices split into (normal, on_top). On-top bodies
+  /// are ordered far to near so the nearest wins where they overlap.
+  /// Bodies flagged no_pick are left out when `picking`, and no_export
+  /// ones while exporting.
+  fn draw_order(&self, shared: &SharedState, picking: bool) -> (Vec<usize>, Vec<usize>)
+  {
+    let body_count = shared.body_registry.bodies.len().min(self.body_capacity);
+    let mut normal = Vec::new();
+    let mut on_top = Vec::new();
+    for index in 0..body_count
+    {
+      let flags = shared.body_registry.bodies[index].flags;
+      if (picking && flags.no_pick) || (shared.exporting && flags.no_export)
+      {
+        continue;
+      }
+      if flags.always_on_top
+      {
+        on_top.push(index);
+      }
+      else
+      {
+        normal.push(index);
+      }
     }
 
-    Self { pipeline, body_bgl, gpu_bodies, sun_pos_render: Vec3::ZERO }
+    let bodies = &shared.body_registry.bodies;
+    let eye = shared.eye_world;
+    on_top.sort_by(|&a, &b| {
+      let da = bodies[a].world_pos.distance_squared(eye);
+      let db = bodies[b].world_pos.distance_squared(eye);
+      db.total_cmp(&da)
+    });
+    (normal, on_top)
+  }
+
+  pub fn mesh_bvh(&self) -> Arc<Bvh>
+  {
+    self.mesh_bvh.clone()
+  }
+
+  pub fn mesh_uvs(&self) -> Arc<Vec<[f32; 2]>>
+  {
+    self.mesh_uvs.clone()
   }
 
   /// Convert world-space DVec3 (metres) to render-scale Vec3.
+  pub fn height_range(&self) -> [f32; 2]
+  {
+    self.height_range
+  }
+
+  fn height_range_of(vertices: &[BakedVertex]) -> [f32; 2]
+  {
+    let mut range = [f32::MAX, f32::MIN];
+    for vertex in vertices
+    {
+      range[0] = range[0].min(vertex.height);
+      range[1] = range[1].max(vertex.height);
+    }
+    if vertices.is_empty()
+    {
+      range = [0.0, 0.0];
+    }
+    range
+  }
+
   fn to_render_scale(pos: DVec3) -> Vec3
   {
     Vec3::new(
@@ -214,7 +758,8 @@ impl BodyRenderer
 
   /// Build the model matrix for a body, relative to the camera eye position.
   /// All arithmetic done in f64 before the final cast to f32.
-  fn build_model_matrix(body: &BodyState, eye_world: DVec3) -> Mat4
+  /// sim_alpha blends the spin between the last two sim ticks.
+  fn build_model_matrix(body: &BodyState, eye_world: DVec3, sim_alpha: f64) -> Mat4
   {
     let relative = body.world_pos - eye_world;
     let pos_render = Vec3::new(
@@ -223,8 +768,9 @@ impl BodyRenderer
       (relative.z / RENDER_SCALE) as f32,
     );
     let scale = (body.manifest.radius_m / RENDER_SCALE) as f32;
+    let rotation = body.orientation(sim_alpha).as_quat();
 
-    Mat4::from_scale_rotation_translation(Vec3::splat(scale), Quat::IDENTITY, pos_render)
+    Mat4::from_scale_rotation_translation(Vec3::splat(scale), rotation, pos_render)
   }
 
   /// Derive a base colour from BodyKind.
@@ -280,7 +826,7 @@ impl RenderModule for BodyRenderer
       {
         if let crate::world::body::BodyKind::Star { .. } = body_state.manifest.kind
         {
-          let mat = Self::build_model_matrix(body_state, shared.eye_world);
+          let mat = Self::build_model_matrix(body_state, shared.eye_world, shared.sim_alpha);
           let translation = mat.w_axis;
           let scale = body_state.manifest.radius_m / RENDER_SCALE;
           eprintln!(
@@ -309,18 +855,36 @@ impl RenderModule for BodyRenderer
       }
     }
 
+    let stride = self.uniform_stride as usize;
+    let uniforms_size = std::mem::size_of::<BodyUniforms>();
+    let field_stops = shared.field.map.stops(&shared.palette).map(|[r, g, b]| [r, g, b, 1.0]);
+
     for (index, body_state) in shared.body_registry.bodies.iter().enumerate()
     {
-      let gpu_body = match self.gpu_bodies.get(index)
+      if index >= self.body_capacity
       {
-        Some(Some(b)) => b,
-        _ => continue,
-      };
+        break;
+      }
 
-      let model_mat = Self::build_model_matrix(body_state, shared.eye_world);
+      let model_mat = Self::build_model_matrix(body_state, shared.eye_world, shared.sim_alpha);
       let base_color = Self::base_color(&body_state.manifest.kind);
       let is_star = Self::is_star(&body_state.manifest.kind);
 
+      let mut material_count = 0;
+      let mut materials = [[0.0; 4]; MAX_MATERIALS];
+      if let Some(mesh) = &self.meshes[index]
+      {
+        material_count = mesh.materials.len();
+        materials[..material_count].copy_from_slice(&mesh.materials);
+      }
+
+      let mut highlight = [0.0; 4];
+      if shared.selected.contains(&index)
+      {
+        let [r, g, b] = shared.palette.selection;
+        highlight = [r, g, b, SELECTION_TINT_STRENGTH];
+      }
+
       // Vector from this body toward the Sun in render-scale space.
       // When all bodies are at origin this falls back to Vec3::Y so the
       // lighting is at least consistent rather than black.
@@ -333,21 +897,40 @@ impl RenderModule for BodyRenderer
         base_color: base_color.into(),
         light_dir: light_dir.into(),
         is_star,
+        highlight,
+        display_mode: shared.display_mode as u32,
+        field_min: shared.field.min,
+        field_max: shared.field.max,
+        textured: self.textured[index] as u32,
+        field_stops,
+        material_count: material_count as u32,
+        unshadowed: body_state.flags.no_shadow as u32,
+        _pad: [0; 2],
+        materials,
       };
 
-      queue.write_buffer(&gpu_body.uniforms_buffer, 0, bytemuck::bytes_of(&uniforms));
+      let slot = index * stride;
+      self.staging[slot..slot + uniforms_size].copy_from_slice(bytemuck::bytes_of(&uniforms));
     }
+
+    queue.write_buffer(&self.uniforms_buffer, 0, &self.staging);
   }
 
   fn encode(&self, encoder: &mut wgpu::CommandEncoder, targets: &FrameTargets, shared: &SharedState)
   {
+    let mut clear_alpha = 1.0;
+    if shared.transparent
+    {
+      clear_alpha = 0.0;
+    }
+
     let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
       label: Some("Body Render Pass"),
       color_attachments: &[Some(wgpu::RenderPassColorAttachment {
         view: targets.surface_view,
-        resolve_target: None,
+        resolve_target: targets.resolve_view,
         ops: wgpu::Operations {
-          load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }),
+          load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.0, g: 0.0, b: 0.0, a: clear_alpha }),
           store: wgpu::StoreOp::Store,
         },
         depth_slice: None,
@@ -363,25 +946,74 @@ impl RenderModule for BodyRenderer
       ..Default::default()
     });
 
-    render_pass.set_pipeline(&self.pipeline);
     render_pass.set_bind_group(0, &shared.camera_gpu.bind_group, &[]);
 
-    for (index, _body_state) in shared.body_registry.bodies.iter().enumerate()
+    let (normal, on_top) = self.draw_order(shared, false);
+    for (pipeline, indices) in [(&self.pipeline, normal), (&self.on_top_pipeline, on_top)]
     {
-      let gpu_body = match self.gpu_bodies.get(index)
+      render_pass.set_pipeline(pipeline);
+      for index in indices
       {
-        Some(Some(b)) => b,
-        _ => continue,
-      };
+        let (vertex_buffer, vertex_count) = self.mesh_of(index);
+        if vertex_count == 0
+        {
+          continue;
+        }
+        let offset = (index as u64 * self.uniform_stride) as u32;
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.set_bind_group(1, &self.uniforms_bind_group, &[offset]);
+        render_pass.set_bind_group(2, &self.texture_bind_groups[index], &[]);
+        render_pass.draw(0..vertex_count, 0..1);
+      }
+    }
+  }
+
+  fn encode_pick(&self, pass: &mut wgpu::RenderPass<'_>, shared: &SharedState)
+  {
+    pass.set_bind_group(0, &shared.camera_gpu.bind_group, &[]);
 
-      if gpu_body.vertex_count == 0
+    // The instance index carries the body index into the shader.
+    let (normal, on_top) = self.draw_order(shared, true);
+    for (pipeline, indices) in [(&self.pick_pipeline, normal), (&self.pick_on_top_pipeline, on_top)]
+    {
+      pass.set_pipeline(pipeline);
+      for index in indices
       {
-        continue;
+        let (vertex_buffer, vertex_count) = self.mesh_of(index);
+        if vertex_count == 0
+        {
+          continue;
+        }
+        let offset = (index as u64 * self.uniform_stride) as u32;
+        let instance = index as u32;
+        pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        pass.set_bind_group(1, &self.uniforms_bind_group, &[offset]);
+        pass.draw(0..vertex_count, instance..instance + 1);
       }
+    }
+  }
 
-      render_pass.set_bind_group(1, &gpu_body.bind_group, &[]);
-      render_pass.set_vertex_buffer(0, gpu_body.vertex_buffer.slice(..));
-      render_pass.draw(0..gpu_body.vertex_count, 0..1);
+  fn encode_mask(&self, pass: &mut wgpu::RenderPass<'_>, shared: &SharedState)
+  {
+    pass.set_pipeline(&self.mask_pipeline);
+    pass.set_bind_group(0, &shared.camera_gpu.bind_group, &[]);
+
+    for &index in &shared.selected
+    {
+      if index >= self.body_capacity
+      {
+        continue;
+      }
+      let (vertex_buffer, vertex_count) = self.mesh_of(index);
+      if vertex_count == 0
+      {
+        continue;
+      }
+      let offset = (index as u64 * self.uniform_stride) as u32;
+      let instance = index as u32;
+      pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+      pass.set_bind_group(1, &self.uniforms_bind_group, &[offset]);
+      pass.draw(0..vertex_count, instance..instance + 1);
     }
   }
 
@@ -390,3 +1022,150 @@ impl RenderModule for BodyRenderer
     self
   }
 }
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+  use crate::input::state::InputState;
+  use crate::render::kernel::test_renderer;
+  use crate::render::primitives;
+  use crate::world::body::BodyManifest;
+
+  fn write_bake(path: &Path, vertices: &[BakedVertex])
+  {
+    let mut bytes = (vertices.len() as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(bytemuck::cast_slice(vertices));
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    std::fs::write(path, bytes).unwrap();
+  }
+
+  #[test]
+  fn manmade_body_draws_its_mesh_in_material_colours()
+  {
+    let mut renderer = match test_renderer()
+    {
+      Some(renderer) => renderer,
+      None => return,
+    };
+
+    // A box whose every face uses material 1 (green); the shared mesh is empty.
+    let dir = std::env::temp_dir().join("kyzu_body_mesh");
+    std::fs::create_dir_all(&dir).unwrap();
+    let (vertices, indices) = primitives::cuboid(Vec3::splat(0.5));
+    let mut flat = primitives::unweld(&vertices, &indices);
+    for vertex in &mut flat
+    {
+      vertex.hex_id = 1;
+    }
+    write_bake(&dir.join("station.bake"), &flat);
+    write_bake(&dir.join("empty.bake"), &[]);
+    let materials = r#"[{"name":"hull","diffuse":[1,0,0]},{"name":"panel","diffuse":[0,1,0]}]"#;
+    std::fs::write(dir.join("station.materials.json"), materials).unwrap();
+
+    renderer.shared.body_registry.spawn(
+      BodyManifest {
+        name: "Station".to_string(),
+        kind: BodyKind::Manmade,
+        radius_m: 1.0e6,
+        lod_max: 0,
+        position_at_epoch: DVec3::ZERO,
+        orbital_elements: None,
+        axial_tilt_rad: 0.0,
+        rotation_period_s: 3600.0,
+      },
+      false,
+    );
+
+    let mut logger = Logger::new(dir.join("test.log").to_str().unwrap());
+    let (device, queue) = (renderer.device.clone(), renderer.queue.clone());
+    let mut bodies = BodyRenderer::new(
+      &device,
+      &queue,
+      &mut renderer.shared,
+      &dir.join("empty.bake"),
+      &mut logger,
+    );
+    bodies.load_meshes(&device, &renderer.shared, &dir, &mut logger);
+    renderer.add_module(bodies);
+
+    let orbit = &mut renderer.camera_system.orbital_controller;
+    orbit.target = DVec3::ZERO;
+    orbit.altitude = 3.0e6;
+    orbit.lat = 20.0;
+    orbit.lon = 30.0;
+    renderer.update(&mut InputState::new(), 0.0).unwrap();
+
+    let texture = renderer.render_to_texture(64, 64);
+    let rgba = renderer.read_texture(&texture).unwrap();
+    let green = rgba.chunks(4).filter(|p| p[1] > 20 && p[0] == 0 && p[2] == 0).count();
+    let drawn = rgba.chunks(4).filter(|p| p[0] > 0 || p[1] > 0 || p[2] > 0).count();
+    assert!(drawn > 0);
+    assert_eq!(green, drawn);
+
+    // Flagged no_export, it is left out of saved images.
+    renderer.shared.body_registry.bodies[0].flags.no_export = true;
+    let texture = renderer.render_to_texture(64, 64);
+    let rgba = renderer.read_texture(&texture).unwrap();
+    assert!(rgba.chunks(4).all(|p| p[0] == 0 && p[1] == 0 && p[2] == 0));
+  }
+
+  #[test]
+  fn bodies_inserted_later_get_a_slot_and_their_primitive()
+  {
+    let mut renderer = match test_renderer()
+    {
+      Some(renderer) => renderer,
+      None => return,
+    };
+
+    // Built for an empty registry; the shared mesh is empty so only the
+    // primitive can draw anything.
+    let dir = std::env::temp_dir().join("kyzu_body_insert");
+    std::fs::create_dir_all(&dir).unwrap();
+    write_bake(&dir.join("empty.bake"), &[]);
+    let mut logger = Logger::new(dir.join("test.log").to_str().unwrap());
+    let (device, queue) = (renderer.device.clone(), renderer.queue.clone());
+    let bodies = BodyRenderer::new(
+      &device,
+      &queue,
+      &mut renderer.shared,
+      &dir.join("empty.bake"),
+      &mut logger,
+    );
+    renderer.add_module(bodies);
+
+    let registry = &mut renderer.shared.body_registry;
+    for name in ["Box", "Box 2"]
+    {
+      let index = registry.spawn(
+        BodyManifest {
+          name: name.to_string(),
+          kind: BodyKind::Manmade,
+          radius_m: 1.0e6,
+          lod_max: 0,
+          position_at_epoch: DVec3::ZERO,
+          orbital_elements: None,
+          axial_tilt_rad: 0.0,
+          rotation_period_s: 0.0,
+        },
+        false,
+      );
+      registry.bodies[index].primitive = Some(Primitive::Box);
+    }
+    let capacity = renderer.with_module(|bodies: &mut BodyRenderer, device, shared| {
+      bodies.add_bodies(device, shared);
+      (bodies.body_capacity, bodies.meshes.iter().filter(|m| m.is_some()).count())
+    });
+    assert_eq!(capacity, Some((2, 2)));
+
+    let orbit = &mut renderer.camera_system.orbital_controller;
+    orbit.target = DVec3::ZERO;
+    orbit.altitude = 3.0e6;
+    renderer.update(&mut InputState::new(), 0.0).unwrap();
+
+    let texture = renderer.render_to_texture(64, 64);
+    let rgba = renderer.read_texture(&texture).unwrap();
+    assert!(rgba.chunks(4).any(|p| p[0] > 0 || p[1] > 0 || p[2] > 0));
+  }
+}
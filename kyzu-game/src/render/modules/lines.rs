@@ -0,0 +1,267 @@
+use std::any::Any;
+
+use bytemuck::{Pod, Zeroable};
+use glam::DVec3;
+use wgpu::{include_wgsl, BindGroup, Buffer, Queue};
+
+use crate::core::palette::Rgb;
+use crate::render::camera::projection::RENDER_SCALE;
+use crate::render::module::{self, FrameTargets, RenderCategory, RenderModule};
+use crate::render::shared::{CameraMode, SharedState};
+
+/// Segments drawn per frame; more are dropped.
+const MAX_LINES: usize = 64;
+/// Pivot axis length as a fraction of the orbit distance, so the gizmo
+/// keeps the same size on screen.
+const AXIS_LENGTH: f64 = 0.15;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  LineInstance / LineParams — must match lines.wgsl layout exactly
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct LineInstance
+{
+  /// Endpoints relative to the eye, in render units.
+  a: [f32; 3],
+  /// Width in physical pixels.
+  width: f32,
+  b: [f32; 3],
+  _pad: f32,
+  color: [f32; 4],
+}
+
+const INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 4] = [
+  wgpu::VertexAttribute { format: wgpu::VertexFormat::Float32x3, offset: 0, shader_location: 0 },
+  wgpu::VertexAttribute { format: wgpu::VertexFormat::Float32, offset: 12, shader_location: 1 },
+  wgpu::VertexAttribute { format: wgpu::VertexFormat::Float32x3, offset: 16, shader_location: 2 },
+  wgpu::VertexAttribute { format: wgpu::VertexFormat::Float32x4, offset: 32, shader_location: 3 },
+];
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct LineParams
+{
+  viewport: [f32; 2],
+  _pad: [f32; 2],
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  LineRenderer
+//
+//  Wide antialiased lines as instanced screen-space quads; hardware line
+//  lists are stuck at one pixel. Alpha blended for the soft edge, with no
+//  depth test, so instances are sorted far to near: where lines cross,
+//  the nearer one's fringe blends over the farther one. Draws the pivot
+//  axes in the orbital camera as an overlay, since the pivot is usually
+//  the centre of a body and would otherwise be hidden.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct LineRenderer
+{
+  pipeline: wgpu::RenderPipeline,
+  instance_buffer: Buffer,
+  params_buffer: Buffer,
+  params_bind_group: BindGroup,
+  /// Line width in physical pixels.
+  width: f32,
+  line_count: u32,
+}
+
+impl LineRenderer
+{
+  pub fn new(device: &wgpu::Device, shared: &SharedState, width: f32) -> Self
+  {
+    let shader = device.create_shader_module(include_wgsl!("../shaders/lines.wgsl"));
+
+    let params_size = std::mem::size_of::<LineParams>() as u64;
+    let params_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: Some("Line Params BGL"),
+      entries: &[wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::VERTEX,
+        ty: wgpu::BindingType::Buffer {
+          ty: wgpu::BufferBindingType::Uniform,
+          has_dynamic_offset: false,
+          min_binding_size: wgpu::BufferSize::new(params_size),
+        },
+        count: None,
+      }],
+    });
+
+    let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("Line Params"),
+      size: params_size,
+      usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    });
+
+    let params_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some("Line Params BG"),
+      layout: &params_bgl,
+      entries: &[wgpu::BindGroupEntry { binding: 0, resource: params_buffer.as_entire_binding() }],
+    });
+
+    let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("Line Instances"),
+      size: (std::mem::size_of::<LineInstance>() * MAX_LINES) as u64,
+      usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("Line Pipeline Layout"),
+      bind_group_layouts: &[&shared.camera_gpu.layout, &params_bgl],
+      push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Line Pipeline"),
+      layout: Some(&pipeline_layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers: &[wgpu::VertexBufferLayout {
+          array_stride: std::mem::size_of::<LineInstance>() as u64,
+          step_mode: wgpu::VertexStepMode::Instance,
+          attributes: &INSTANCE_ATTRIBUTES,
+        }],
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: Some("fs_main"),
+        compilation_options: Default::default(),
+        targets: &[Some(wgpu::ColorTargetState {
+          format: shared.surface_format,
+          blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+          write_mask: wgpu::ColorWrites::ALL,
+        })],
+      }),
+      primitive: wgpu::PrimitiveState {
+        topology: wgpu::PrimitiveTopology::TriangleList,
+        cull_mode: None,
+        ..Default::default()
+      },
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState { count: shared.sample_count, ..Default::default() },
+      multiview: None,
+      cache: None,
+    });
+
+    Self { pipeline, instance_buffer, params_buffer, params_bind_group, width, line_count: 0 }
+  }
+
+  fn segment(&self, shared: &SharedState, a: DVec3, b: DVec3, color: Rgb) -> LineInstance
+  {
+    let to_render = |p: DVec3| ((p - shared.eye_world) / RENDER_SCALE).as_vec3().to_array();
+    LineInstance {
+      a: to_render(a),
+      width: self.width,
+      b: to_render(b),
+      _pad: 0.0,
+      color: [color[0], color[1], color[2], 1.0],
+    }
+  }
+
+  /// X, Y and Z from the orbit pivot in the palette's axis colours.
+  fn pivot_axes(&self, shared: &SharedState) -> Vec<LineInstance>
+  {
+    if shared.mode != CameraMode::Orbital || !shared.show_axes
+    {
+      return Vec::new();
+    }
+
+    let length = shared.focus_distance * AXIS_LENGTH;
+    let palette = &shared.palette;
+    let pivot = shared.pivot;
+    vec![
+      self.segment(shared, pivot, pivot + DVec3::X * length, palette.axis_x),
+      self.segment(shared, pivot, pivot + DVec3::Y * length, palette.axis_y),
+      self.segment(shared, pivot, pivot + DVec3::Z * length, palette.axis_z),
+    ]
+  }
+}
+
+/// Order segments by midpoint distance from the eye, farthest first.
+/// Instances in one draw blend in instance order.
+fn sort_far_to_near(lines: &mut [LineInstance])
+{
+  let distance = |line: &LineInstance| {
+    let (a, b) = (glam::Vec3::from(line.a), glam::Vec3::from(line.b));
+    ((a + b) * 0.5).length_squared()
+  };
+  lines.sort_by(|x, y| distance(y).total_cmp(&distance(x)));
+}
+
+impl RenderModule for LineRenderer
+{
+  fn category(&self) -> RenderCategory
+  {
+    RenderCategory::Overlay
+  }
+
+  fn update(&mut self, queue: &Queue, shared: &SharedState)
+  {
+    let params = LineParams {
+      viewport: [shared.screen_width as f32, shared.screen_height as f32],
+      _pad: [0.0; 2],
+    };
+    queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+
+    let mut lines = self.pivot_axes(shared);
+    lines.truncate(MAX_LINES);
+    sort_far_to_near(&mut lines);
+    self.line_count = lines.len() as u32;
+    if !lines.is_empty()
+    {
+      queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&lines));
+    }
+  }
+
+  fn encode(&self, encoder: &mut wgpu::CommandEncoder, targets: &FrameTargets, shared: &SharedState)
+  {
+    if self.line_count == 0
+    {
+      return;
+    }
+
+    let mut render_pass = module::begin_overlay_pass(encoder, targets, "Line Render Pass");
+
+    render_pass.set_pipeline(&self.pipeline);
+    render_pass.set_bind_group(0, &shared.camera_gpu.bind_group, &[]);
+    render_pass.set_bind_group(1, &self.params_bind_group, &[]);
+    render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+    render_pass.draw(0..6, 0..self.line_count);
+  }
+
+  fn as_any_mut(&mut self) -> &mut dyn Any
+  {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  fn line(a: [f32; 3], b: [f32; 3]) -> LineInstance
+  {
+    LineInstance { a, width: 1.0, b, _pad: 0.0, color: [1.0; 4] }
+  }
+
+  #[test]
+  fn crossing_lines_blend_nearest_last()
+  {
+    let mut lines = vec![
+      line([0.0, 0.0, -1.0], [0.0, 0.0, -3.0]),
+      line([0.0, 0.0, -9.0], [1.0, 0.0, -9.0]),
+      line([-1.0, 0.0, -5.0], [1.0, 0.0, -5.0]),
+    ];
+    sort_far_to_near(&mut lines);
+    let depths: Vec<f32> = lines.iter().map(|l| l.a[2]).collect();
+    assert_eq!(depths, [-9.0, -5.0, -1.0]);
+  }
+}
@@ -1 +1,2 @@
 pub mod body_renderer;
+pub mod lines;
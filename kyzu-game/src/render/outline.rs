@@ -0,0 +1,239 @@
+use wgpu::util::DeviceExt;
+use wgpu::{
+  BindGroup, BindGroupLayout, Buffer, CommandEncoder, Device, Queue, RenderPipeline, TextureFormat,
+  TextureView,
+};
+
+use crate::render::module::{FramePass, RenderModule};
+use crate::render::shared::SharedState;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Selection outline
+//
+//  Selected geometry is drawn into an offscreen R8 mask via
+//  RenderModule::encode_mask, then a fullscreen pass paints every pixel
+//  just outside the mask onto the finished frame. The mask has no depth
+//  test, so a selected body behind another still shows its contour. The
+//  composite is alpha blended but is one fullscreen draw that never
+//  overlaps itself, at a fixed point (after FXAA, before the UI), so it
+//  needs no sorting.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub const MASK_FORMAT: TextureFormat = TextureFormat::R8Unorm;
+
+pub struct OutlinePass
+{
+  pipeline: RenderPipeline,
+  bgl: BindGroupLayout,
+  /// Outline colour (vec4), refreshed from the palette each frame.
+  color_buffer: Buffer,
+  /// Created when something is first selected, rebuilt when the frame
+  /// size changes.
+  mask: Option<Mask>,
+}
+
+struct Mask
+{
+  view: TextureView,
+  bind_group: BindGroup,
+  width: u32,
+  height: u32,
+}
+
+impl OutlinePass
+{
+  pub fn new(device: &Device, surface_format: TextureFormat) -> Self
+  {
+    let color_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: Some("Outline Color"),
+      contents: bytemuck::cast_slice(&[1.0f32; 4]),
+      usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let bgl = Self::create_bind_group_layout(device);
+    let pipeline = Self::create_pipeline(device, &bgl, surface_format);
+
+    Self { pipeline, bgl, color_buffer, mask: None }
+  }
+
+  fn create_mask(&self, device: &Device, width: u32, height: u32) -> Mask
+  {
+    let mask_texture = device.create_texture(&wgpu::TextureDescriptor {
+      label: Some("Outline Mask Texture"),
+      size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: MASK_FORMAT,
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+      view_formats: &[],
+    });
+    let view = mask_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+      label: Some("Outline BG"),
+      layout: &self.bgl,
+      entries: &[
+        wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+        wgpu::BindGroupEntry { binding: 1, resource: self.color_buffer.as_entire_binding() },
+      ],
+    });
+
+    Mask { view, bind_group, width, height }
+  }
+
+  fn create_bind_group_layout(device: &Device) -> BindGroupLayout
+  {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: Some("Outline BGL"),
+      entries: &[
+        wgpu::BindGroupLayoutEntry {
+          binding: 0,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+          },
+          count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+          binding: 1,
+          visibility: wgpu::ShaderStages::FRAGMENT,
+          ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+          },
+          count: None,
+        },
+      ],
+    })
+  }
+
+  fn create_pipeline(
+    device: &Device,
+    bgl: &BindGroupLayout,
+    surface_format: TextureFormat,
+  ) -> RenderPipeline
+  {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/outline.wgsl"));
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("Outline Pipeline Layout"),
+      bind_group_layouts: &[bgl],
+      push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Outline Pipeline"),
+      layout: Some(&layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: Some("vs_main"),
+        compilation_options: Default::default(),
+        buffers: &[],
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: Some("fs_main"),
+        compilation_options: Default::default(),
+        targets: &[Some(wgpu::ColorTargetState {
+          format: surface_format,
+          blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+          write_mask: wgpu::ColorWrites::ALL,
+        })],
+      }),
+      primitive: wgpu::PrimitiveState::default(),
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default(),
+      multiview: None,
+      cache: None,
+    })
+  }
+}
+
+impl FramePass for OutlinePass
+{
+  /// Skipped when nothing is selected or the watchdog has degraded quality.
+  fn prepare(
+    &mut self,
+    device: &Device,
+    queue: &Queue,
+    shared: &SharedState,
+    width: u32,
+    height: u32,
+  ) -> bool
+  {
+    if shared.selected.is_empty() || shared.degraded
+    {
+      return false;
+    }
+
+    let stale = match &self.mask
+    {
+      Some(mask) => mask.width != width || mask.height != height,
+      None => true,
+    };
+    if stale
+    {
+      self.mask = Some(self.create_mask(device, width, height));
+    }
+
+    let [r, g, b] = shared.palette.selection;
+    queue.write_buffer(&self.color_buffer, 0, bytemuck::cast_slice(&[r, g, b, 1.0]));
+    true
+  }
+
+  /// Render the selection mask, then composite the outline onto `output`.
+  fn encode(
+    &self,
+    encoder: &mut CommandEncoder,
+    output: &TextureView,
+    modules: &[Box<dyn RenderModule>],
+    shared: &SharedState,
+  )
+  {
+    let mask = match &self.mask
+    {
+      Some(mask) => mask,
+      None => return,
+    };
+
+    {
+      let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Outline Mask Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+          view: &mask.view,
+          resolve_target: None,
+          ops: wgpu::Operations {
+            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+            store: wgpu::StoreOp::Store,
+          },
+          depth_slice: None,
+        })],
+        ..Default::default()
+      });
+
+      for module in modules
+      {
+        module.encode_mask(&mut pass, shared);
+      }
+    }
+
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("Outline Composite Pass"),
+      color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+        view: output,
+        resolve_target: None,
+        ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+        depth_slice: None,
+      })],
+      ..Default::default()
+    });
+
+    pass.set_pipeline(&self.pipeline);
+    pass.set_bind_group(0, &mask.bind_group, &[]);
+    pass.draw(0..3, 0..1);
+  }
+}
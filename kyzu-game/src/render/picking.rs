@@ -0,0 +1,264 @@
+use wgpu::{Buffer, Device, Texture, TextureFormat, TextureView};
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Picking
+//
+//  Modules that draw pickable things render an ID (body index + 1, 0 for
+//  nothing) into an offscreen R32Uint target via RenderModule::encode_pick.
+//  Renderer::pick() runs that pass on demand and reads back the pixels
+//  within the pick tolerance, so thin or distant things can be hit
+//  without pixel-exact aim; Renderer::pick_region() reads back a
+//  rectangle for lasso and paint selection.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub const PICK_FORMAT: TextureFormat = TextureFormat::R32Uint;
+
+pub struct PickTarget
+{
+  pub id_texture: Texture,
+  pub id_view: TextureView,
+  pub depth_view: TextureView,
+  pub width: u32,
+  pub height: u32,
+}
+
+/// A copy of part of the ID texture in flight to the CPU.
+pub struct RegionReadback
+{
+  buffer: Buffer,
+  /// copy_texture_to_buffer needs rows padded to 256 bytes.
+  padded_row: u32,
+  x: u32,
+  y: u32,
+  width: u32,
+  height: u32,
+}
+
+/// Body IDs for a rectangle of the screen, in window pixel coordinates.
+pub struct IdRegion
+{
+  pub x: u32,
+  pub y: u32,
+  pub width: u32,
+  pub height: u32,
+  /// Raw IDs, row-major: body index + 1, 0 for nothing.
+  ids: Vec<u32>,
+}
+
+impl IdRegion
+{
+  pub fn empty() -> Self
+  {
+    Self { x: 0, y: 0, width: 0, height: 0, ids: Vec::new() }
+  }
+
+  /// Body index at window pixel (x, y). None outside the region or over
+  /// empty space.
+  pub fn index_at(&self, x: u32, y: u32) -> Option<usize>
+  {
+    if x < self.x || y < self.y || x >= self.x + self.width || y >= self.y + self.height
+    {
+      return None;
+    }
+
+    let id = self.ids[((y - self.y) * self.width + (x - self.x)) as usize];
+    if id == 0
+    {
+      return None;
+    }
+    Some(id as usize - 1)
+  }
+
+  /// Body index nearest window pixel (x, y) within `radius` pixels. An
+  /// exact hit always wins.
+  pub fn nearest_index(&self, x: u32, y: u32, radius: f32) -> Option<usize>
+  {
+    let centre = glam::Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+    let radius_sq = radius * radius;
+    let mut best: Option<(f32, usize)> = None;
+
+    for py in self.y..self.y + self.height
+    {
+      for px in self.x..self.x + self.width
+      {
+        let index = match self.index_at(px, py)
+        {
+          Some(index) => index,
+          None => continue,
+        };
+        let pixel = glam::Vec2::new(px as f32 + 0.5, py as f32 + 0.5);
+        let dist_sq = pixel.distance_squared(centre);
+        if dist_sq > radius_sq
+        {
+          continue;
+        }
+        match best
+        {
+          Some((nearest, _)) if nearest <= dist_sq =>
+          {}
+          _ => best = Some((dist_sq, index)),
+        }
+      }
+    }
+    best.map(|(_, index)| index)
+  }
+
+  /// Distinct body indices over the pixels `keep` accepts.
+  pub fn indices_where(&self, keep: impl Fn(u32, u32) -> bool) -> Vec<usize>
+  {
+    let mut indices = Vec::new();
+
+    for y in self.y..self.y + self.height
+    {
+      for x in self.x..self.x + self.width
+      {
+        if let Some(index) = self.index_at(x, y)
+        {
+          if !indices.contains(&index) && keep(x, y)
+          {
+            indices.push(index);
+          }
+        }
+      }
+    }
+    indices
+  }
+}
+
+impl PickTarget
+{
+  pub fn new(device: &Device, depth_format: TextureFormat, width: u32, height: u32) -> Self
+  {
+    let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+
+    let id_texture = device.create_texture(&wgpu::TextureDescriptor {
+      label: Some("Pick ID Texture"),
+      size,
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: PICK_FORMAT,
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+      view_formats: &[],
+    });
+
+    let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+      label: Some("Pick Depth Texture"),
+      size,
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: depth_format,
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+      view_formats: &[],
+    });
+
+    Self {
+      id_view: id_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+      depth_view: depth_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+      id_texture,
+      width,
+      height,
+    }
+  }
+
+  /// Copy a `width` x `height` rectangle at (x, y) into a new readback
+  /// buffer. The rectangle must lie inside the target.
+  pub fn copy_region(
+    &self,
+    device: &Device,
+    encoder: &mut wgpu::CommandEncoder,
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+  ) -> RegionReadback
+  {
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_row = (width * 4).div_ceil(align) * align;
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("Pick Readback"),
+      size: padded_row as u64 * height as u64,
+      usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+      mapped_at_creation: false,
+    });
+
+    encoder.copy_texture_to_buffer(
+      wgpu::TexelCopyTextureInfo {
+        texture: &self.id_texture,
+        mip_level: 0,
+        origin: wgpu::Origin3d { x, y, z: 0 },
+        aspect: wgpu::TextureAspect::All,
+      },
+      wgpu::TexelCopyBufferInfo {
+        buffer: &buffer,
+        layout: wgpu::TexelCopyBufferLayout {
+          offset: 0,
+          bytes_per_row: Some(padded_row),
+          rows_per_image: None,
+        },
+      },
+      wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+    );
+
+    RegionReadback { buffer, padded_row, x, y, width, height }
+  }
+}
+
+impl RegionReadback
+{
+  /// Block until the copy is readable and unpack it.
+  pub fn read(self, device: &Device) -> anyhow::Result<IdRegion>
+  {
+    let slice = self.buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device
+      .poll(wgpu::PollType::wait_indefinitely())
+      .map_err(|e| anyhow::anyhow!("Pick readback failed: {:?}", e))?;
+
+    let mut ids = Vec::with_capacity((self.width * self.height) as usize);
+    {
+      let data = slice.get_mapped_range();
+      for row in 0..self.height
+      {
+        let start = (row * self.padded_row) as usize;
+        let bytes = &data[start..start + (self.width * 4) as usize];
+        ids.extend_from_slice(bytemuck::cast_slice::<u8, u32>(bytes));
+      }
+    }
+    self.buffer.unmap();
+
+    Ok(IdRegion { x: self.x, y: self.y, width: self.width, height: self.height, ids })
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  /// 5x5 region at (10, 10) with body 2 at (12, 10) and body 0 at (14, 14).
+  fn region() -> IdRegion
+  {
+    let mut ids = vec![0; 25];
+    ids[2] = 3;
+    ids[24] = 1;
+    IdRegion { x: 10, y: 10, width: 5, height: 5, ids }
+  }
+
+  #[test]
+  fn nearest_index_prefers_the_closest_pixel()
+  {
+    let region = region();
+    assert_eq!(region.nearest_index(12, 10, 0.0), Some(2));
+    assert_eq!(region.nearest_index(12, 12, 3.0), Some(2));
+    assert_eq!(region.nearest_index(14, 13, 3.0), Some(0));
+  }
+
+  #[test]
+  fn nearest_index_respects_the_radius()
+  {
+    let region = region();
+    assert_eq!(region.nearest_index(10, 14, 1.5), None);
+    assert_eq!(region.nearest_index(12, 14, 2.0), Some(0));
+  }
+}
@@ -0,0 +1,489 @@
+// ──────────────────────────────────────────────────────────────
+//   Parametric primitive meshes
+//
+//   Indexed triangle lists with outward normals and UVs, centred on
+//   the origin with +Y up. Front faces wind counter-clockwise. Run
+//   through unweld() for the non-indexed body pipeline: the bake step
+//   writes each Primitive to primitives/, and the Insert menu draws one
+//   as a new body.
+// ──────────────────────────────────────────────────────────────
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use glam::{Vec2, Vec3};
+
+use crate::bake::geometry::{self, BakedVertex};
+use crate::bake::subdivider::Subdivider;
+
+pub type Mesh = (Vec<BakedVertex>, Vec<u32>);
+
+/// The shapes offered by the Insert menu and baked to primitives/.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Primitive
+{
+  IcoSphere,
+  UvSphere,
+  Cylinder,
+  Cone,
+  Torus,
+  Plane,
+  Box,
+}
+
+impl Primitive
+{
+  pub const ALL: [Primitive; 7] = [
+    Primitive::IcoSphere,
+    Primitive::UvSphere,
+    Primitive::Cylinder,
+    Primitive::Cone,
+    Primitive::Torus,
+    Primitive::Plane,
+    Primitive::Box,
+  ];
+
+  pub fn name(self) -> &'static str
+  {
+    match self
+    {
+      Primitive::IcoSphere => "ico_sphere",
+      Primitive::UvSphere => "uv_sphere",
+      Primitive::Cylinder => "cylinder",
+      Primitive::Cone => "cone",
+      Primitive::Torus => "torus",
+      Primitive::Plane => "plane",
+      Primitive::Box => "box",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Primitive>
+  {
+    Primitive::ALL.into_iter().find(|p| p.name() == name)
+  }
+
+  /// Menu label.
+  pub fn label(self) -> &'static str
+  {
+    match self
+    {
+      Primitive::IcoSphere => "Ico sphere",
+      Primitive::UvSphere => "UV sphere",
+      Primitive::Cylinder => "Cylinder",
+      Primitive::Cone => "Cone",
+      Primitive::Torus => "Torus",
+      Primitive::Plane => "Plane",
+      Primitive::Box => "Box",
+    }
+  }
+
+  /// The shape sized to fit the unit sphere, like the body bakes, so a
+  /// body's radius_m scales it.
+  pub fn mesh(self) -> Mesh
+  {
+    let side = std::f32::consts::FRAC_1_SQRT_2;
+    match self
+    {
+      Primitive::IcoSphere => ico_sphere(1.0, 3),
+      Primitive::UvSphere => uv_sphere(1.0, 48, 24),
+      Primitive::Cylinder => cylinder(side, side * 2.0, 48),
+      Primitive::Cone => cone(side, side * 2.0, 48),
+      Primitive::Torus => torus(0.75, 0.25, 48, 24),
+      Primitive::Plane => plane(side * 2.0, 8),
+      Primitive::Box => cuboid(Vec3::splat(1.0 / 3.0f32.sqrt())),
+    }
+  }
+}
+
+fn vertex(pos: Vec3, normal: Vec3, uv: Vec2) -> BakedVertex
+{
+  BakedVertex {
+    pos: pos.to_array(),
+    normal: normal.to_array(),
+    uv: uv.to_array(),
+    height: 0.0,
+    hex_id: 0,
+    barycentric: [0.0, 0.0, 0.0],
+  }
+}
+
+/// Two triangles for the quad a-b-c-d (counter-clockwise seen from the front).
+fn push_quad(indices: &mut Vec<u32>, a: u32, b: u32, c: u32, d: u32)
+{
+  indices.extend_from_slice(&[a, b, c, a, c, d]);
+}
+
+/// Latitude/longitude sphere. `segments` around, `rings` pole to pole.
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Mesh
+{
+  let segments = segments.max(3);
+  let rings = rings.max(2);
+  let mut vertices = Vec::new();
+  let mut indices = Vec::new();
+
+  for ring in 0..=rings
+  {
+    let v = ring as f32 / rings as f32;
+    let lat = FRAC_PI_2 - v * PI;
+
+    for segment in 0..=segments
+    {
+      let u = segment as f32 / segments as f32;
+      let lon = u * TAU;
+      let normal = Vec3::new(lat.cos() * lon.sin(), lat.sin(), lat.cos() * lon.cos());
+      vertices.push(vertex(normal * radius, normal, Vec2::new(u, v)));
+    }
+  }
+
+  let row = segments + 1;
+  for ring in 0..rings
+  {
+    for segment in 0..segments
+    {
+      let top = ring * row + segment;
+      let bottom = top + row;
+      push_quad(&mut indices, top, bottom, bottom + 1, top + 1);
+    }
+  }
+
+  (vertices, indices)
+}
+
+/// Subdivided icosahedron: even triangle sizes, no pole pinching.
+pub fn ico_sphere(radius: f32, subdivisions: u32) -> Mesh
+{
+  let (mut vertices, base) = geometry::get_base_icosahedron();
+  let mut indices: Vec<u32> = base.into_iter().map(|i| i as u32).collect();
+  let mut subdivider = Subdivider::new(None);
+
+  for _ in 0..subdivisions
+  {
+    let mut next = Vec::with_capacity(indices.len() * 4);
+    for tri in indices.chunks(3)
+    {
+      let a = subdivider.get_midpoint(tri[0], tri[1], &mut vertices);
+      let b = subdivider.get_midpoint(tri[1], tri[2], &mut vertices);
+      let c = subdivider.get_midpoint(tri[2], tri[0], &mut vertices);
+
+      next.extend_from_slice(&[tri[0], a, c, tri[1], b, a, tri[2], c, b, a, b, c]);
+    }
+    indices = next;
+  }
+
+  for v in &mut vertices
+  {
+    v.pos = (Vec3::from_array(v.pos) * radius).to_array();
+  }
+
+  (vertices, indices)
+}
+
+/// Capped cylinder along Y, `height` tall.
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> Mesh
+{
+  let mut mesh = (Vec::new(), Vec::new());
+  let half = height * 0.5;
+
+  push_side(&mut mesh, radius, radius, -half, half, segments);
+  push_cap(&mut mesh, radius, half, Vec3::Y, segments);
+  push_cap(&mut mesh, radius, -half, Vec3::NEG_Y, segments);
+
+  mesh
+}
+
+/// Cone along Y with its base at -height/2 and apex at +height/2.
+pub fn cone(radius: f32, height: f32, segments: u32) -> Mesh
+{
+  let mut mesh = (Vec::new(), Vec::new());
+  let half = height * 0.5;
+
+  push_side(&mut mesh, radius, 0.0, -half, half, segments);
+  push_cap(&mut mesh, radius, -half, Vec3::NEG_Y, segments);
+
+  mesh
+}
+
+/// Ring in the XZ plane. `major` is the ring radius, `minor` the tube radius.
+pub fn torus(major: f32, minor: f32, segments: u32, sides: u32) -> Mesh
+{
+  let segments = segments.max(3);
+  let sides = sides.max(3);
+  let mut vertices = Vec::new();
+  let mut indices = Vec::new();
+
+  for segment in 0..=segments
+  {
+    let u = segment as f32 / segments as f32;
+    let around = u * TAU;
+    let ring_dir = Vec3::new(around.sin(), 0.0, around.cos());
+
+    for side in 0..=sides
+    {
+      let v = side as f32 / sides as f32;
+      let tube = v * TAU;
+      let normal = ring_dir * tube.cos() + Vec3::Y * tube.sin();
+      let pos = ring_dir * major + normal * minor;
+      vertices.push(vertex(pos, normal, Vec2::new(u, v)));
+    }
+  }
+
+  let row = sides + 1;
+  for segment in 0..segments
+  {
+    for side in 0..sides
+    {
+      let a = segment * row + side;
+      let b = a + row;
+      push_quad(&mut indices, a, b, b + 1, a + 1);
+    }
+  }
+
+  (vertices, indices)
+}
+
+/// Square grid in the XZ plane facing +Y, `size` wide.
+pub fn plane(size: f32, subdivisions: u32) -> Mesh
+{
+  let cells = subdivisions.max(1);
+  let mut vertices = Vec::new();
+  let mut indices = Vec::new();
+
+  for row in 0..=cells
+  {
+    let v = row as f32 / cells as f32;
+    for col in 0..=cells
+    {
+      let u = col as f32 / cells as f32;
+      let pos = Vec3::new((u - 0.5) * size, 0.0, (v - 0.5) * size);
+      vertices.push(vertex(pos, Vec3::Y, Vec2::new(u, v)));
+    }
+  }
+
+  let stride = cells + 1;
+  for row in 0..cells
+  {
+    for col in 0..cells
+    {
+      let a = row * stride + col;
+      push_quad(&mut indices, a, a + stride, a + stride + 1, a + 1);
+    }
+  }
+
+  (vertices, indices)
+}
+
+/// Axis-aligned box with separate vertices per face for flat normals.
+pub fn cuboid(half_extents: Vec3) -> Mesh
+{
+  let mut vertices = Vec::new();
+  let mut indices = Vec::new();
+
+  let faces = [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z];
+  for normal in faces
+  {
+    // Two axes spanning the face.
+    let mut tangent = Vec3::Y;
+    if normal.y != 0.0
+    {
+      tangent = Vec3::Z;
+    }
+    let bitangent = normal.cross(tangent);
+
+    let base = vertices.len() as u32;
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+    for (s, t) in corners
+    {
+      let pos = (normal + tangent * s + bitangent * t) * half_extents;
+      let uv = Vec2::new((s + 1.0) * 0.5, (t + 1.0) * 0.5);
+      vertices.push(vertex(pos, normal, uv));
+    }
+    push_quad(&mut indices, base, base + 1, base + 2, base + 3);
+  }
+
+  (vertices, indices)
+}
+
+/// Side wall between y0 (radius r0) and y1 (radius r1). r1 = 0 makes a cone.
+fn push_side(mesh: &mut Mesh, r0: f32, r1: f32, y0: f32, y1: f32, segments: u32)
+{
+  let segments = segments.max(3);
+  let (vertices, indices) = mesh;
+  let base = vertices.len() as u32;
+
+  // Slant normal: perpendicular to the side's profile line.
+  let slope = (r0 - r1) / (y1 - y0);
+
+  for segment in 0..=segments
+  {
+    let u = segment as f32 / segments as f32;
+    let angle = u * TAU;
+    let dir = Vec3::new(angle.sin(), 0.0, angle.cos());
+    let normal = (dir + Vec3::Y * slope).normalize();
+
+    vertices.push(vertex(dir * r0 + Vec3::Y * y0, normal, Vec2::new(u, 1.0)));
+    vertices.push(vertex(dir * r1 + Vec3::Y * y1, normal, Vec2::new(u, 0.0)));
+  }
+
+  for segment in 0..segments
+  {
+    let a = base + segment * 2;
+    push_quad(indices, a, a + 2, a + 3, a + 1);
+  }
+}
+
+/// Flat disc at height `y` facing `normal`, as a fan around a centre vertex.
+fn push_cap(mesh: &mut Mesh, radius: f32, y: f32, normal: Vec3, segments: u32)
+{
+  let segments = segments.max(3);
+  let (vertices, indices) = mesh;
+  let centre = vertices.len() as u32;
+  vertices.push(vertex(Vec3::Y * y, normal, Vec2::splat(0.5)));
+
+  for segment in 0..=segments
+  {
+    let angle = segment as f32 / segments as f32 * TAU;
+    let (sin, cos) = angle.sin_cos();
+    let pos = Vec3::new(sin * radius, y, cos * radius);
+    vertices.push(vertex(pos, normal, Vec2::new(0.5 + sin * 0.5, 0.5 + cos * 0.5)));
+  }
+
+  // The rim runs counter-clockwise seen from +Y, so a cap facing down
+  // takes its corners the other way round.
+  for segment in 0..segments
+  {
+    let (a, b) = (centre + 1 + segment, centre + 2 + segment);
+    let mut tri = [centre, a, b];
+    if normal.y < 0.0
+    {
+      tri = [centre, b, a];
+    }
+    indices.extend_from_slice(&tri);
+  }
+}
+
+/// Indexed mesh to a flat triangle list with per-corner barycentrics,
+/// the layout BodyRenderer draws (and the wireframe shader needs).
+pub fn unweld(vertices: &[BakedVertex], indices: &[u32]) -> Vec<BakedVertex>
+{
+  let mut flat = Vec::with_capacity(indices.len());
+
+  for (i, &idx) in indices.iter().enumerate()
+  {
+    let mut v = vertices[idx as usize];
+
+    // Assign [1,0,0], [0,1,0], or [0,0,1] based on the corner of the triangle
+    v.barycentric = match i % 3
+    {
+      0 => [1.0, 0.0, 0.0],
+      1 => [0.0, 1.0, 0.0],
+      _ => [0.0, 0.0, 1.0],
+    };
+
+    flat.push(v);
+  }
+
+  flat
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  /// Every generator with typical arguments.
+  fn all() -> Vec<(&'static str, Mesh)>
+  {
+    vec![
+      ("uv_sphere", uv_sphere(2.0, 16, 8)),
+      ("ico_sphere", ico_sphere(2.0, 2)),
+      ("cylinder", cylinder(1.0, 3.0, 12)),
+      ("cone", cone(1.0, 3.0, 12)),
+      ("torus", torus(2.0, 0.5, 16, 8)),
+      ("plane", plane(4.0, 3)),
+      ("cuboid", cuboid(Vec3::new(1.0, 2.0, 3.0))),
+    ]
+  }
+
+  fn corners(vertices: &[BakedVertex], tri: &[u32]) -> [Vec3; 3]
+  {
+    [0, 1, 2].map(|k| Vec3::from_array(vertices[tri[k] as usize].pos))
+  }
+
+  #[test]
+  fn triangles_wind_counter_clockwise_from_outside()
+  {
+    for (name, (vertices, indices)) in all()
+    {
+      assert_eq!(indices.len() % 3, 0, "{}", name);
+      for tri in indices.chunks(3)
+      {
+        let [a, b, c] = corners(&vertices, tri);
+        let face = (b - a).cross(c - a);
+        // Pole rows of the UV sphere collapse to zero area.
+        if face.length() < 1e-6
+        {
+          continue;
+        }
+
+        let centroid = (a + b + c) / 3.0;
+        let mut outward = centroid;
+        if name == "torus"
+        {
+          // Away from the tube's centre line, not the origin.
+          let ring = Vec3::new(centroid.x, 0.0, centroid.z).normalize() * 2.0;
+          outward = centroid - ring;
+        }
+        if name == "plane"
+        {
+          outward = Vec3::Y;
+        }
+        assert!(face.dot(outward) > 0.0, "{} has an inward triangle {:?}", name, tri);
+
+        // And agrees with the normals it will be lit with.
+        let normals: Vec3 =
+          tri.iter().map(|&i| Vec3::from_array(vertices[i as usize].normal)).sum();
+        assert!(face.dot(normals) > 0.0, "{} triangle {:?} faces away from its normals", name, tri);
+      }
+    }
+  }
+
+  #[test]
+  fn grids_have_the_expected_counts()
+  {
+    let (vertices, indices) = uv_sphere(1.0, 16, 8);
+    assert_eq!(vertices.len(), 17 * 9);
+    assert_eq!(indices.len(), 16 * 8 * 6);
+
+    let (vertices, indices) = torus(2.0, 0.5, 16, 8);
+    assert_eq!(vertices.len(), 17 * 9);
+    assert_eq!(indices.len(), 16 * 8 * 6);
+
+    for subdivisions in 0..3
+    {
+      let (_, indices) = ico_sphere(1.0, subdivisions);
+      assert_eq!(indices.len(), 20 * 4usize.pow(subdivisions) * 3);
+    }
+
+    // Too few segments are raised to the minimum rather than degenerating.
+    let (_, indices) = torus(2.0, 0.5, 1, 1);
+    assert_eq!(indices.len(), 3 * 3 * 6);
+  }
+
+  #[test]
+  fn unweld_gives_each_corner_its_own_barycentric()
+  {
+    let (vertices, indices) = cuboid(Vec3::ONE);
+    let flat = unweld(&vertices, &indices);
+    assert_eq!(flat.len(), indices.len());
+
+    for (tri, corners) in indices.chunks(3).zip(flat.chunks(3))
+    {
+      assert_eq!(corners[0].barycentric, [1.0, 0.0, 0.0]);
+      assert_eq!(corners[1].barycentric, [0.0, 1.0, 0.0]);
+      assert_eq!(corners[2].barycentric, [0.0, 0.0, 1.0]);
+      for k in 0..3
+      {
+        assert_eq!(corners[k].pos, vertices[tri[k] as usize].pos);
+      }
+    }
+  }
+}
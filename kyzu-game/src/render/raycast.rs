@@ -0,0 +1,460 @@
+use glam::{DVec3, Vec3};
+
+use crate::bake::geometry::BakedVertex;
+use crate::world::registry::BodyRegistry;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  CPU ray casting
+//
+//  Complements the GPU ID pass: where picking answers "which body", a ray
+//  cast gives the exact surface point, triangle and barycentrics under the
+//  cursor (snapping, measurement, orbiting about a point).
+//
+//  Every body draws the same unit mesh, so one BVH is built over that mesh
+//  in its local space. Each body is tested against its bounding sphere in
+//  world metres first; only then is the ray moved into the body's unit
+//  space for the triangle test.
+// ─────────────────────────────────────────────────────────────────────────────
+
+const LEAF_SIZE: usize = 4;
+
+/// Ray in world space. `dir` is unit length; distances are metres.
+#[derive(Debug, Clone, Copy)]
+pub struct Ray
+{
+  pub origin: DVec3,
+  pub dir: DVec3,
+}
+
+impl Ray
+{
+  pub fn at(&self, distance: f64) -> DVec3
+  {
+    self.origin + self.dir * distance
+  }
+}
+
+/// Closest hit of a ray against the scene.
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit
+{
+  /// Index into BodyRegistry::bodies.
+  pub body: usize,
+  /// Hit position in world metres.
+  pub point: DVec3,
+  /// Metres from the ray origin.
+  pub distance: f64,
+  /// Triangle index in the shared body mesh (vertices 3i..3i+3).
+  pub triangle: usize,
+  /// Weights of the triangle's three corners at the hit point.
+  pub barycentric: Vec3,
+}
+
+/// Closest hit against a single mesh, in the mesh's own units.
+#[derive(Debug, Clone, Copy)]
+pub struct TriangleHit
+{
+  pub t: f32,
+  pub triangle: usize,
+  pub barycentric: Vec3,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Bvh
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct BvhNode
+{
+  min: Vec3,
+  max: Vec3,
+  /// Leaf: first entry in `order`. Interior: index of the left child.
+  first: u32,
+  /// Triangles in a leaf; 0 for interior nodes.
+  count: u32,
+  /// Interior only: index of the right child.
+  right: u32,
+}
+
+/// Bounding volume hierarchy over a non-indexed triangle list.
+pub struct Bvh
+{
+  triangles: Vec<[Vec3; 3]>,
+  /// Triangle indices, reordered so each leaf covers a contiguous run.
+  order: Vec<u32>,
+  nodes: Vec<BvhNode>,
+}
+
+impl Bvh
+{
+  /// Build from a flat triangle list as drawn by BodyRenderer.
+  pub fn from_vertices(vertices: &[BakedVertex]) -> Self
+  {
+    let mut triangles = Vec::with_capacity(vertices.len() / 3);
+    for tri in vertices.chunks_exact(3)
+    {
+      triangles.push([
+        Vec3::from_array(tri[0].pos),
+        Vec3::from_array(tri[1].pos),
+        Vec3::from_array(tri[2].pos),
+      ]);
+    }
+    Self::build(triangles)
+  }
+
+  pub fn build(triangles: Vec<[Vec3; 3]>) -> Self
+  {
+    let mut bvh =
+      Self { order: (0..triangles.len() as u32).collect(), triangles, nodes: Vec::new() };
+
+    if !bvh.triangles.is_empty()
+    {
+      bvh.build_node(0, bvh.triangles.len());
+    }
+    bvh
+  }
+
+  pub fn triangle_count(&self) -> usize
+  {
+    self.triangles.len()
+  }
+
+  pub fn triangle(&self, index: usize) -> [Vec3; 3]
+  {
+    self.triangles[index]
+  }
+
+  /// Split `order[start..end]` at the centroid median of its longest axis.
+  fn build_node(&mut self, start: usize, end: usize) -> u32
+  {
+    let (min, max) = self.bounds(start, end);
+    let index = self.nodes.len() as u32;
+    self.nodes.push(BvhNode {
+      min,
+      max,
+      first: start as u32,
+      count: (end - start) as u32,
+      right: 0,
+    });
+
+    if end - start <= LEAF_SIZE
+    {
+      return index;
+    }
+
+    let extent = max - min;
+    let mut axis = 0;
+    if extent.y > extent.x
+    {
+      axis = 1;
+    }
+    if extent.z > extent[axis]
+    {
+      axis = 2;
+    }
+
+    let triangles = &self.triangles;
+    let centroid = |i: &u32| {
+      let tri = &triangles[*i as usize];
+      (tri[0][axis] + tri[1][axis] + tri[2][axis]) / 3.0
+    };
+    let mid = (start + end) / 2;
+    self.order[start..end]
+      .select_nth_unstable_by(mid - start, |a, b| centroid(a).total_cmp(&centroid(b)));
+
+    let left = self.build_node(start, mid);
+    let right = self.build_node(mid, end);
+
+    let node = &mut self.nodes[index as usize];
+    node.first = left;
+    node.count = 0;
+    node.right = right;
+    index
+  }
+
+  fn bounds(&self, start: usize, end: usize) -> (Vec3, Vec3)
+  {
+    let mut min = Vec3::splat(f32::MAX);
+    let mut max = Vec3::splat(f32::MIN);
+
+    for &i in &self.order[start..end]
+    {
+      for corner in self.triangles[i as usize]
+      {
+        min = min.min(corner);
+        max = max.max(corner);
+      }
+    }
+    (min, max)
+  }
+
+  /// Closest triangle hit along origin + dir * t, t in [0, max_t].
+  pub fn intersect(&self, origin: Vec3, dir: Vec3, max_t: f32) -> Option<TriangleHit>
+  {
+    if self.nodes.is_empty()
+    {
+      return None;
+    }
+
+    let inv_dir = dir.recip();
+    let mut closest: Option<TriangleHit> = None;
+    let mut limit = max_t;
+    let mut stack = vec![0u32];
+
+    while let Some(index) = stack.pop()
+    {
+      let node = &self.nodes[index as usize];
+      if !slab_test(node.min, node.max, origin, inv_dir, limit)
+      {
+        continue;
+      }
+
+      if node.count == 0
+      {
+        stack.push(node.right);
+        stack.push(node.first);
+        continue;
+      }
+
+      let start = node.first as usize;
+      for &triangle in &self.order[start..start + node.count as usize]
+      {
+        let corners = &self.triangles[triangle as usize];
+        if let Some((t, barycentric)) = intersect_triangle(origin, dir, corners)
+        {
+          if t <= limit
+          {
+            limit = t;
+            closest = Some(TriangleHit { t, triangle: triangle as usize, barycentric });
+          }
+        }
+      }
+    }
+
+    closest
+  }
+}
+
+/// Ray vs axis-aligned box, accepting hits between 0 and `max_t`.
+fn slab_test(min: Vec3, max: Vec3, origin: Vec3, inv_dir: Vec3, max_t: f32) -> bool
+{
+  let t0 = (min - origin) * inv_dir;
+  let t1 = (max - origin) * inv_dir;
+  let near = t0.min(t1).max_element().max(0.0);
+  let far = t0.max(t1).min_element().min(max_t);
+  near <= far
+}
+
+/// Möller–Trumbore, double-sided. Returns (t, barycentric weights of a, b, c).
+fn intersect_triangle(origin: Vec3, dir: Vec3, [a, b, c]: &[Vec3; 3]) -> Option<(f32, Vec3)>
+{
+  let edge1 = *b - *a;
+  let edge2 = *c - *a;
+  let p = dir.cross(edge2);
+  let det = edge1.dot(p);
+  if det.abs() < f32::EPSILON
+  {
+    return None;
+  }
+
+  let inv_det = 1.0 / det;
+  let s = origin - *a;
+  let u = s.dot(p) * inv_det;
+  if !(0.0..=1.0).contains(&u)
+  {
+    return None;
+  }
+
+  let q = s.cross(edge1);
+  let v = dir.dot(q) * inv_det;
+  if v < 0.0 || u + v > 1.0
+  {
+    return None;
+  }
+
+  let t = edge2.dot(q) * inv_det;
+  if t < 0.0
+  {
+    return None;
+  }
+  Some((t, Vec3::new(1.0 - u - v, u, v)))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Scene cast
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Closest body surface hit by `ray`. `mesh` is the unit-radius mesh every
+/// body is drawn with; `sim_alpha` matches the spin the renderer used.
+pub fn cast(ray: &Ray, bodies: &BodyRegistry, mesh: &Bvh, sim_alpha: f64) -> Option<RayHit>
+{
+  let mut closest: Option<RayHit> = None;
+
+  for (index, body) in bodies.bodies.iter().enumerate()
+  {
+    if body.flags.no_pick
+    {
+      continue;
+    }
+    let radius = body.manifest.radius_m;
+    let entry = match sphere_entry(ray, body.world_pos, radius)
+    {
+      Some(d) => d,
+      None => continue,
+    };
+    if let Some(hit) = &closest
+    {
+      if entry > hit.distance
+      {
+        continue;
+      }
+    }
+
+    // Start the local ray at the sphere entry so its origin sits near the
+    // unit sphere, where f32 has precision to spare.
+    let inv_rotation = body.orientation(sim_alpha).inverse();
+    let local_origin = (inv_rotation * ((ray.at(entry) - body.world_pos) / radius)).as_vec3();
+    let local_dir = (inv_rotation * ray.dir).as_vec3();
+
+    // The mesh fits inside the unit sphere, so nothing lies beyond its diameter.
+    let hit = match mesh.intersect(local_origin, local_dir, 2.0)
+    {
+      Some(h) => h,
+      None => continue,
+    };
+
+    let distance = entry + hit.t as f64 * radius;
+    let mut nearer = true;
+    if let Some(best) = &closest
+    {
+      nearer = distance < best.distance;
+    }
+    if nearer
+    {
+      closest = Some(RayHit {
+        body: index,
+        point: ray.at(distance),
+        distance,
+        triangle: hit.triangle,
+        barycentric: hit.barycentric,
+      });
+    }
+  }
+
+  closest
+}
+
+/// The hit moved to the nearest corner of its triangle, in world metres.
+pub fn snap_to_vertex(hit: &RayHit, bodies: &BodyRegistry, mesh: &Bvh, sim_alpha: f64) -> DVec3
+{
+  let body = &bodies.bodies[hit.body];
+  let corner = mesh.triangle(hit.triangle)[nearest_corner(hit.barycentric)];
+  let local = corner.as_dvec3() * body.manifest.radius_m;
+  body.world_pos + body.orientation(sim_alpha) * local
+}
+
+/// Index of the largest barycentric weight: the corner nearest the point.
+fn nearest_corner(barycentric: Vec3) -> usize
+{
+  let mut nearest = 0;
+  for i in 1..3
+  {
+    if barycentric[i] > barycentric[nearest]
+    {
+      nearest = i;
+    }
+  }
+  nearest
+}
+
+/// Distance along the ray to where it enters the sphere, 0 if it starts
+/// inside. None if it misses or the sphere is behind.
+fn sphere_entry(ray: &Ray, centre: DVec3, radius: f64) -> Option<f64>
+{
+  let to_centre = centre - ray.origin;
+  let along = to_centre.dot(ray.dir);
+  let miss_sq = to_centre.length_squared() - along * along;
+  let radius_sq = radius * radius;
+  if miss_sq > radius_sq
+  {
+    return None;
+  }
+
+  let half_chord = (radius_sq - miss_sq).sqrt();
+  if along + half_chord < 0.0
+  {
+    return None;
+  }
+  Some((along - half_chord).max(0.0))
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  fn quad() -> Bvh
+  {
+    // Unit square in the XY plane at z = 0, as two triangles.
+    let a = Vec3::new(-1.0, -1.0, 0.0);
+    let b = Vec3::new(1.0, -1.0, 0.0);
+    let c = Vec3::new(1.0, 1.0, 0.0);
+    let d = Vec3::new(-1.0, 1.0, 0.0);
+    Bvh::build(vec![[a, b, c], [a, c, d]])
+  }
+
+  #[test]
+  fn hits_front_and_reports_barycentrics()
+  {
+    let hit = quad().intersect(Vec3::new(0.5, -0.5, 5.0), Vec3::NEG_Z, f32::MAX).unwrap();
+    assert!((hit.t - 5.0).abs() < 1e-5);
+    assert_eq!(hit.triangle, 0);
+    assert!((hit.barycentric.x + hit.barycentric.y + hit.barycentric.z - 1.0).abs() < 1e-5);
+  }
+
+  #[test]
+  fn snaps_to_the_heaviest_corner()
+  {
+    assert_eq!(nearest_corner(Vec3::new(0.6, 0.3, 0.1)), 0);
+    assert_eq!(nearest_corner(Vec3::new(0.2, 0.5, 0.3)), 1);
+    assert_eq!(nearest_corner(Vec3::new(0.1, 0.1, 0.8)), 2);
+  }
+
+  #[test]
+  fn misses_outside_and_behind()
+  {
+    let bvh = quad();
+    assert!(bvh.intersect(Vec3::new(2.0, 0.0, 5.0), Vec3::NEG_Z, f32::MAX).is_none());
+    assert!(bvh.intersect(Vec3::new(0.0, 0.0, 5.0), Vec3::Z, f32::MAX).is_none());
+    assert!(bvh.intersect(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z, 4.0).is_none());
+  }
+
+  #[test]
+  fn deep_tree_finds_nearest()
+  {
+    // A stack of parallel quads: the ray must stop at the closest one.
+    let mut triangles = Vec::new();
+    for layer in 0..64
+    {
+      let z = layer as f32;
+      let a = Vec3::new(-1.0, -1.0, z);
+      let b = Vec3::new(1.0, -1.0, z);
+      let c = Vec3::new(0.0, 1.0, z);
+      triangles.push([a, b, c]);
+    }
+    let bvh = Bvh::build(triangles);
+
+    let hit = bvh.intersect(Vec3::new(0.0, 0.0, 100.0), Vec3::NEG_Z, f32::MAX).unwrap();
+    assert_eq!(hit.triangle, 63);
+    assert!((hit.t - 37.0).abs() < 1e-4);
+  }
+
+  #[test]
+  fn sphere_entry_cases()
+  {
+    let ray = Ray { origin: DVec3::ZERO, dir: DVec3::X };
+    assert_eq!(sphere_entry(&ray, DVec3::new(10.0, 0.0, 0.0), 2.0), Some(8.0));
+    assert_eq!(sphere_entry(&ray, DVec3::new(1.0, 0.0, 0.0), 2.0), Some(0.0));
+    assert!(sphere_entry(&ray, DVec3::new(-10.0, 0.0, 0.0), 2.0).is_none());
+    assert!(sphere_entry(&ray, DVec3::new(10.0, 5.0, 0.0), 2.0).is_none());
+  }
+}
@@ -1,9 +1,11 @@
 // ─────────────────────────────────────────────────────────────────────────────
 //  Kyzu — body.wgsl
 //
-//  Renders a single solar system body as a smooth-shaded sphere.
+//  Renders a single solar system body: the shared sphere, or for Manmade
+//  bodies their own mesh coloured per material.
 //  Group 0: camera  (shared across all draw calls this frame)
 //  Group 1: body    (per-body — model matrix, base colour, light direction)
+//  Group 2: albedo  (per-body texture; 1×1 white when the body has none)
 // ─────────────────────────────────────────────────────────────────────────────
 
 struct Camera
@@ -22,10 +24,30 @@ struct BodyUniforms
     // Unused when is_star == 1.
     light_dir:  vec3<f32>,
     is_star:    u32,
+    // Selection tint added after lighting: rgb colour, a strength.
+    highlight:  vec4<f32>,
+    // 0 = shaded, 1 = checker (UV grid in place of base_color),
+    // 2 = field (elevation through the colour map below).
+    display_mode: u32,
+    // Elevation clamp range in metres.
+    field_min:  f32,
+    field_max:  f32,
+    // 1 = take the shaded base colour from the albedo texture.
+    textured:   u32,
+    // Colour map stops, evenly spaced from field_min to field_max.
+    field_stops: array<vec4<f32>, 5>,
+    // Entries of materials in use; 0 = base_color throughout.
+    material_count: u32,
+    // 1 = never in shadow: lit as if facing the sun everywhere.
+    unshadowed: u32,
+    // Diffuse colour per material, indexed by the vertex hex_id.
+    materials: array<vec4<f32>, 16>,
 };
 
 @group(0) @binding(0) var<uniform> camera: Camera;
 @group(1) @binding(0) var<uniform> body:   BodyUniforms;
+@group(2) @binding(0) var albedo:         texture_2d<f32>;
+@group(2) @binding(1) var albedo_sampler: sampler;
 
 struct VertexInput
 {
@@ -41,8 +63,33 @@ struct VertexOutput
 {
     @builtin(position) clip_pos:   vec4<f32>,
     @location(0)       world_norm: vec3<f32>,
+    @location(1)       uv:         vec2<f32>,
+    @location(2)       height:     f32,
+    @location(3) @interpolate(flat) material: u32,
 };
 
+// Checks of 1/16 x 1/8 in UV, tinted red along u and green along v so
+// flipped or mirrored islands stand out.
+const CHECKS: vec2<f32> = vec2<f32>(16.0, 8.0);
+
+fn checker(uv: vec2<f32>) -> vec3<f32>
+{
+    let cell   = floor(uv * CHECKS);
+    let parity = (i32(cell.x) + i32(cell.y)) & 1;
+    let grey   = select(0.25, 0.85, parity == 0);
+    let tint   = vec3<f32>(fract(uv.x), fract(uv.y), 0.5);
+    return grey * mix(vec3<f32>(1.0), tint, 0.6);
+}
+
+fn field_color(height: f32) -> vec3<f32>
+{
+    let span   = max(body.field_max - body.field_min, 1e-6);
+    let scaled = clamp((height - body.field_min) / span, 0.0, 1.0) * 4.0;
+    let index  = min(u32(scaled), 3u);
+    let f      = scaled - f32(index);
+    return mix(body.field_stops[index].rgb, body.field_stops[index + 1u].rgb, f);
+}
+
 @vertex
 fn vs_main(v: VertexInput) -> VertexOutput
 {
@@ -56,6 +103,9 @@ fn vs_main(v: VertexInput) -> VertexOutput
     let m         = body.model_mat;
     let norm_mat  = mat3x3<f32>(m[0].xyz, m[1].xyz, m[2].xyz);
     out.world_norm = normalize(norm_mat * v.normal);
+    out.uv         = v.uv;
+    out.height     = v.height;
+    out.material   = v.hex_id;
 
     return out;
 }
@@ -63,20 +113,44 @@ fn vs_main(v: VertexInput) -> VertexOutput
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>
 {
-    let base = body.base_color.rgb;
+    // Sampled unconditionally: textureSample needs uniform control flow.
+    let texel = textureSample(albedo, albedo_sampler, in.uv);
+
+    var base = body.base_color.rgb;
+    if body.material_count > 0u
+    {
+        base = body.materials[min(in.material, body.material_count - 1u)].rgb;
+    }
+    if body.textured == 1u
+    {
+        base = texel.rgb;
+    }
+    if body.display_mode == 1u
+    {
+        base = checker(in.uv);
+    }
+    if body.display_mode == 2u
+    {
+        base = field_color(in.height);
+    }
 
     // Stars are self-luminous — return flat colour, no lighting.
     if body.is_star == 1u
     {
-        return vec4<f32>(base, 1.0);
+        return vec4<f32>(base + body.highlight.rgb * body.highlight.a, 1.0);
     }
 
     // Diffuse + ambient
     let ambient  = 0.08;
     let n        = normalize(in.world_norm);
     let l        = normalize(body.light_dir);
-    let diffuse  = max(dot(n, l), 0.0);
+    var diffuse  = max(dot(n, l), 0.0);
+    if body.unshadowed == 1u
+    {
+        diffuse = 1.0;
+    }
     let light    = ambient + (1.0 - ambient) * diffuse;
 
-    return vec4<f32>(base * light, 1.0);
+    let tint = body.highlight.rgb * body.highlight.a;
+    return vec4<f32>(base * light + tint, 1.0);
 }
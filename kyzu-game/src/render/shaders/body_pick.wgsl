@@ -0,0 +1,60 @@
+// ─────────────────────────────────────────────────────────────────────────────
+//  Kyzu — body_pick.wgsl
+//
+//  ID pass for picking. Same transform as body.wgsl; writes the body index
+//  + 1 (passed in as the instance index) to an R32Uint target. 0 = nothing.
+//  fs_mask reuses the transform to write the selection outline mask.
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct Camera
+{
+    view_proj:     mat4x4<f32>,
+    inv_view_proj: mat4x4<f32>,
+    eye_rel:       vec3<f32>,
+    _pad:          f32,
+};
+
+struct BodyUniforms
+{
+    model_mat:  mat4x4<f32>,
+    base_color: vec4<f32>,
+    light_dir:  vec3<f32>,
+    is_star:    u32,
+    highlight:  vec4<f32>,
+    display_mode: u32,
+    field_min:  f32,
+    field_max:  f32,
+    _pad0:      u32,
+    field_stops: array<vec4<f32>, 5>,
+};
+
+@group(0) @binding(0) var<uniform> camera: Camera;
+@group(1) @binding(0) var<uniform> body:   BodyUniforms;
+
+struct VertexOutput
+{
+    @builtin(position)              clip_pos: vec4<f32>,
+    @location(0) @interpolate(flat) pick_id:  u32,
+};
+
+@vertex
+fn vs_main(@location(0) position: vec3<f32>, @builtin(instance_index) instance: u32) -> VertexOutput
+{
+    var out: VertexOutput;
+    out.clip_pos = camera.view_proj * body.model_mat * vec4<f32>(position, 1.0);
+    out.pick_id  = instance + 1u;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) u32
+{
+    return in.pick_id;
+}
+
+// Selection mask for the outline pass (see outline.rs).
+@fragment
+fn fs_mask(in: VertexOutput) -> @location(0) f32
+{
+    return 1.0;
+}
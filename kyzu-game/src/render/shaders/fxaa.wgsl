@@ -0,0 +1,83 @@
+// ─────────────────────────────────────────────────────────────────────────────
+//  Kyzu — fxaa.wgsl
+//
+//  Fullscreen FXAA (after Lottes' FXAA 3 "PC console" variant). Reads the
+//  offscreen scene, finds the local edge direction from the luma of the
+//  four diagonal neighbours and blends along it. Alpha is passed through
+//  so a transparent window stays transparent.
+// ─────────────────────────────────────────────────────────────────────────────
+
+@group(0) @binding(0) var scene:         texture_2d<f32>;
+@group(0) @binding(1) var scene_sampler: sampler;
+
+// Contrast below max(EDGE_MIN, EDGE_THRESHOLD * local max) is left alone.
+const EDGE_THRESHOLD: f32 = 0.125;
+const EDGE_MIN:       f32 = 0.0312;
+const REDUCE_MUL:     f32 = 0.125;
+const REDUCE_MIN:     f32 = 0.0078125;
+// Longest blend, in pixels.
+const SPAN_MAX:       f32 = 8.0;
+
+struct VertexOutput
+{
+    @builtin(position) clip_pos: vec4<f32>,
+};
+
+// One triangle covering the screen: (-1,-1), (3,-1), (-1,3).
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput
+{
+    var out: VertexOutput;
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    out.clip_pos = vec4<f32>(x, y, 0.0, 1.0);
+    return out;
+}
+
+// Perceptual luma; the scene texture holds linear colour.
+fn luma(rgb: vec3<f32>) -> f32
+{
+    return dot(sqrt(max(rgb, vec3<f32>(0.0))), vec3<f32>(0.299, 0.587, 0.114));
+}
+
+fn fetch(uv: vec2<f32>) -> vec3<f32>
+{
+    return textureSampleLevel(scene, scene_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>
+{
+    let texel = 1.0 / vec2<f32>(textureDimensions(scene));
+    let uv    = in.clip_pos.xy * texel;
+
+    let centre = textureSampleLevel(scene, scene_sampler, uv, 0.0);
+    let l_m  = luma(centre.rgb);
+    let l_nw = luma(fetch(uv + vec2<f32>(-1.0, -1.0) * texel));
+    let l_ne = luma(fetch(uv + vec2<f32>( 1.0, -1.0) * texel));
+    let l_sw = luma(fetch(uv + vec2<f32>(-1.0,  1.0) * texel));
+    let l_se = luma(fetch(uv + vec2<f32>( 1.0,  1.0) * texel));
+
+    let l_min = min(l_m, min(min(l_nw, l_ne), min(l_sw, l_se)));
+    let l_max = max(l_m, max(max(l_nw, l_ne), max(l_sw, l_se)));
+    if l_max - l_min < max(EDGE_MIN, l_max * EDGE_THRESHOLD)
+    {
+        return centre;
+    }
+
+    var dir = vec2<f32>(-((l_nw + l_ne) - (l_sw + l_se)), (l_nw + l_sw) - (l_ne + l_se));
+    let reduce = max((l_nw + l_ne + l_sw + l_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    let scale  = 1.0 / (min(abs(dir.x), abs(dir.y)) + reduce);
+    dir = clamp(dir * scale, vec2<f32>(-SPAN_MAX), vec2<f32>(SPAN_MAX)) * texel;
+
+    let near = 0.5 * (fetch(uv + dir * (1.0 / 3.0 - 0.5)) + fetch(uv + dir * (2.0 / 3.0 - 0.5)));
+    let far  = near * 0.5 + 0.25 * (fetch(uv - dir * 0.5) + fetch(uv + dir * 0.5));
+
+    // The wide blend overshoots when it crosses a second edge.
+    let l_far = luma(far);
+    if l_far < l_min || l_far > l_max
+    {
+        return vec4<f32>(near, centre.a);
+    }
+    return vec4<f32>(far, centre.a);
+}
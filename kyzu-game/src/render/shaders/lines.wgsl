@@ -0,0 +1,111 @@
+// ─────────────────────────────────────────────────────────────────────────────
+//  Kyzu — lines.wgsl
+//
+//  Wide antialiased lines. Each instance is one segment; the vertex shader
+//  expands it into a screen-space quad `width` pixels across plus a pixel
+//  of feather, and the fragment shader fades the feather out.
+//  Group 0: camera  (shared across all draw calls this frame)
+//  Group 1: line parameters (viewport size in pixels)
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct Camera
+{
+    view_proj:     mat4x4<f32>,
+    inv_view_proj: mat4x4<f32>,
+    eye_rel:       vec3<f32>,
+    _pad:          f32,
+};
+
+struct LineParams
+{
+    viewport: vec2<f32>,
+    _pad:     vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> camera: Camera;
+@group(1) @binding(0) var<uniform> params: LineParams;
+
+struct LineInstance
+{
+    // Endpoints relative to the eye, in render units.
+    @location(0) a:     vec3<f32>,
+    @location(1) width: f32,
+    @location(2) b:     vec3<f32>,
+    @location(3) color: vec4<f32>,
+};
+
+struct VertexOutput
+{
+    @builtin(position) clip_pos:   vec4<f32>,
+    @location(0)       color:      vec4<f32>,
+    // Signed distance from the centre line, in pixels.
+    @location(1)       across:     f32,
+    @location(2)       half_width: f32,
+};
+
+// Extra pixels either side for the antialiased edge.
+const FEATHER: f32 = 1.0;
+// Segments are clipped this far in front of the eye (clip-space w).
+const NEAR_W: f32 = 1e-5;
+
+@vertex
+fn vs_main(@builtin(vertex_index) corner: u32, line: LineInstance) -> VertexOutput
+{
+    var out: VertexOutput;
+    out.color      = line.color;
+    out.half_width = line.width * 0.5;
+
+    var a = camera.view_proj * vec4<f32>(line.a, 1.0);
+    var b = camera.view_proj * vec4<f32>(line.b, 1.0);
+
+    // Wholly behind the eye: collapse the quad so nothing is drawn.
+    if a.w < NEAR_W && b.w < NEAR_W
+    {
+        out.clip_pos = vec4<f32>(2.0, 2.0, 2.0, 1.0);
+        out.across   = 0.0;
+        return out;
+    }
+
+    // Keep the visible part of a segment that passes behind the eye.
+    if a.w < NEAR_W
+    {
+        a = mix(a, b, (NEAR_W - a.w) / (b.w - a.w));
+    }
+    if b.w < NEAR_W
+    {
+        b = mix(b, a, (NEAR_W - b.w) / (a.w - b.w));
+    }
+
+    let half_viewport = params.viewport * 0.5;
+    let screen_a = a.xy / a.w * half_viewport;
+    let screen_b = b.xy / b.w * half_viewport;
+
+    var dir = vec2<f32>(1.0, 0.0);
+    let span = screen_b - screen_a;
+    if length(span) > 1e-4
+    {
+        dir = normalize(span);
+    }
+    let normal = vec2<f32>(-dir.y, dir.x);
+
+    // Two triangles: (a-, b-, a+) and (a+, b-, b+). Ends get square caps.
+    var ends  = array<f32, 6>(0.0, 1.0, 0.0, 0.0, 1.0, 1.0);
+    var sides = array<f32, 6>(-1.0, -1.0, 1.0, 1.0, -1.0, 1.0);
+    let end  = ends[corner];
+    let side = sides[corner];
+
+    let half   = out.half_width + FEATHER;
+    let offset = normal * side * half + dir * (end * 2.0 - 1.0) * half;
+    let p      = mix(a, b, end);
+
+    out.clip_pos = vec4<f32>(p.xy + offset / half_viewport * p.w, p.zw);
+    out.across   = side * half;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>
+{
+    let coverage = clamp(in.half_width + 0.5 - abs(in.across), 0.0, 1.0);
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
@@ -0,0 +1,35 @@
+// ─────────────────────────────────────────────────────────────────────────────
+//  Kyzu — mipmap.wgsl
+//
+//  Downsamples one mip level into the next. The target is half the size of
+//  the source, so a bilinear tap at each target pixel's centre lands on the
+//  corner shared by four source texels and averages them (a 2×2 box filter).
+// ─────────────────────────────────────────────────────────────────────────────
+
+@group(0) @binding(0) var source:         texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+struct VertexOutput
+{
+    @builtin(position) clip_pos: vec4<f32>,
+    @location(0)       uv:       vec2<f32>,
+};
+
+// One triangle covering the target: (-1,-1), (3,-1), (-1,3).
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput
+{
+    var out: VertexOutput;
+    let x = f32((index << 1u) & 2u);
+    let y = f32(index & 2u);
+    out.clip_pos = vec4<f32>(x * 2.0 - 1.0, y * 2.0 - 1.0, 0.0, 1.0);
+    // Texture v runs down while clip y runs up.
+    out.uv = vec2<f32>(x, 1.0 - y);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>
+{
+    return textureSample(source, source_sampler, in.uv);
+}
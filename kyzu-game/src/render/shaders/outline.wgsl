@@ -0,0 +1,59 @@
+// ─────────────────────────────────────────────────────────────────────────────
+//  Kyzu — outline.wgsl
+//
+//  Fullscreen selection outline. Reads the R8 selection mask and colours
+//  pixels that are outside the mask but within RADIUS pixels of it.
+// ─────────────────────────────────────────────────────────────────────────────
+
+@group(0) @binding(0) var mask: texture_2d<f32>;
+// Palette selection colour, alpha 1.
+@group(0) @binding(1) var<uniform> outline_color: vec4<f32>;
+
+const RADIUS: i32 = 2;
+
+struct VertexOutput
+{
+    @builtin(position) clip_pos: vec4<f32>,
+};
+
+// One triangle covering the screen: (-1,-1), (3,-1), (-1,3).
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput
+{
+    var out: VertexOutput;
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    out.clip_pos = vec4<f32>(x, y, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>
+{
+    let size  = vec2<i32>(textureDimensions(mask));
+    let pixel = vec2<i32>(in.clip_pos.xy);
+
+    // Inside the selection: leave the body's own shading alone.
+    if textureLoad(mask, pixel, 0).r > 0.5
+    {
+        discard;
+    }
+
+    for (var dy = -RADIUS; dy <= RADIUS; dy++)
+    {
+        for (var dx = -RADIUS; dx <= RADIUS; dx++)
+        {
+            if dx * dx + dy * dy > RADIUS * RADIUS
+            {
+                continue;
+            }
+            let p = clamp(pixel + vec2<i32>(dx, dy), vec2<i32>(0), size - 1);
+            if textureLoad(mask, p, 0).r > 0.5
+            {
+                return outline_color;
+            }
+        }
+    }
+
+    discard;
+}
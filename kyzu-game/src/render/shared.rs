@@ -1,6 +1,13 @@
 use bytemuck::{Pod, Zeroable};
 use wgpu::*;
 
+use crate::core::math::Viewport;
+use crate::core::palette::{ColorMap, Palette};
+use crate::render::camera::projection::{self, Projection};
+use crate::render::capabilities::GpuCapabilities;
+use crate::render::mipmap::MipGenerator;
+use crate::render::raycast::{self, Bvh, Ray, RayHit};
+use crate::render::texture::{SamplerCache, TextureFiltering};
 use crate::world::registry::BodyRegistry;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -86,63 +93,253 @@ pub struct DepthTexture
   pub view: TextureView,
 }
 
+/// How bodies are coloured. Checker replaces the base colour with a UV
+/// grid for spotting stretched or flipped texture coordinates; Field
+/// colours each vertex's elevation through the FieldView colour map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode
+{
+  Shaded,
+  Checker,
+  Field,
+}
+
+impl DisplayMode
+{
+  pub fn name(self) -> &'static str
+  {
+    match self
+    {
+      DisplayMode::Shaded => "shaded",
+      DisplayMode::Checker => "checker",
+      DisplayMode::Field => "field",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<DisplayMode>
+  {
+    match name
+    {
+      "shaded" => Some(DisplayMode::Shaded),
+      "checker" => Some(DisplayMode::Checker),
+      "field" => Some(DisplayMode::Field),
+      _ => None,
+    }
+  }
+}
+
+/// Colour map and clamp range for DisplayMode::Field. Values are vertex
+/// elevations in metres; anything outside [min, max] takes the end colour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldView
+{
+  pub map: ColorMap,
+  pub min: f32,
+  pub max: f32,
+  /// Elevation range of the loaded mesh, for fitting the clamp to the data.
+  pub extent: [f32; 2],
+}
+
+impl Default for FieldView
+{
+  fn default() -> Self
+  {
+    Self { map: ColorMap::Heat, min: 0.0, max: 1.0, extent: [0.0, 1.0] }
+  }
+}
+
 pub struct SharedState
 {
   pub mode: CameraMode,
+  /// Adapter capabilities; modules pick reduced variants from these.
+  pub caps: GpuCapabilities,
   pub camera: CameraMatrices,
   pub camera_gpu: CameraGpu,
+  /// Perspective built by the active camera this frame (render units).
+  pub projection: Projection,
+  /// Distance in metres from the eye to what the camera is looking at.
+  pub focus_distance: f64,
+  /// Negotiated swapchain format; every pipeline drawing to the surface uses it.
   pub surface_format: TextureFormat,
+  /// Window composited with alpha; the scene clears to transparent.
+  pub transparent: bool,
   pub depth_format: TextureFormat,
   pub depth_view: TextureView,
+  /// Samples per pixel for scene pipelines and targets (1 = no MSAA).
+  pub sample_count: u32,
+  /// Multisampled colour target, resolved into the swapchain image.
+  pub msaa_view: Option<TextureView>,
+  /// Filter the finished scene with FXAA before the outline and UI.
+  pub fxaa: bool,
+  /// One sampler per filtering kind, shared by every textured module.
+  pub samplers: SamplerCache,
+  /// How surface textures are sampled; set before modules are added.
+  pub filtering: TextureFiltering,
+  /// Builds mip chains for textures as they are uploaded.
+  pub mipmaps: MipGenerator,
   pub screen_width: u32,
   pub screen_height: u32,
   pub target_body_pos: glam::DVec3,
+  /// Point the orbital camera turns around, in metres.
+  pub pivot: glam::DVec3,
+  /// Draw the X/Y/Z axes at the pivot in the orbital camera.
+  pub show_axes: bool,
   pub eye_world: glam::DVec3,
   pub body_registry: BodyRegistry,
+  /// Meaningful colours (selection, axes, markers), from the config.
+  pub palette: Palette,
+  /// Selected body indices, copied from App's Selection when it changes.
+  pub selected: Vec<usize>,
+  /// Shared body mesh for CPU ray casts, set once BodyRenderer has loaded it.
+  pub body_mesh: Option<std::sync::Arc<Bvh>>,
+  /// UVs of the shared body mesh, three per triangle, for the UV panel.
+  pub body_mesh_uvs: Option<std::sync::Arc<Vec<[f32; 2]>>>,
+  pub display_mode: DisplayMode,
+  pub field: FieldView,
+  /// Interpolation factor between the last two sim ticks (see SimClock).
+  pub sim_alpha: f64,
+  /// Set by the GPU watchdog. Expensive passes check this and skip themselves.
+  pub degraded: bool,
+  /// True while drawing an image to save (stills, headless renders);
+  /// bodies flagged no_export are left out.
+  pub exporting: bool,
 }
 
 impl SharedState
 {
-  pub fn new(device: &Device, width: u32, height: u32) -> Self
+  pub fn viewport(&self) -> Viewport
+  {
+    Viewport { width: self.screen_width as f32, height: self.screen_height as f32 }
+  }
+
+  /// World position (metres) to screen pixels plus NDC depth, using this
+  /// frame's camera. None if the point is behind the eye.
+  pub fn project(&self, world: glam::DVec3) -> Option<glam::Vec3>
+  {
+    let view_proj = glam::Mat4::from_cols_array_2d(&self.camera.view_proj);
+    projection::project(&view_proj, self.eye_world, &self.viewport(), world)
+  }
+
+  /// Screen pixels at NDC depth (0 near, 1 far) back to world metres.
+  pub fn unproject(&self, screen: glam::Vec2, depth: f32) -> glam::DVec3
+  {
+    let inv_view_proj = glam::Mat4::from_cols_array_2d(&self.camera.inv_view_proj);
+    projection::unproject(&inv_view_proj, self.eye_world, &self.viewport(), screen, depth)
+  }
+
+  /// Ray from the eye through a screen pixel.
+  pub fn ray_through(&self, screen: glam::Vec2) -> Ray
+  {
+    let through = self.unproject(screen, 0.5);
+    Ray { origin: self.eye_world, dir: (through - self.eye_world).normalize() }
+  }
+
+  /// Exact body surface point under a screen pixel. None over empty space
+  /// or before the body mesh is loaded.
+  pub fn raycast(&self, screen: glam::Vec2) -> Option<RayHit>
+  {
+    let mesh = self.body_mesh.as_ref()?;
+    raycast::cast(&self.ray_through(screen), &self.body_registry, mesh, self.sim_alpha)
+  }
+
+  pub fn new(
+    device: &Device,
+    caps: GpuCapabilities,
+    surface_format: TextureFormat,
+    width: u32,
+    height: u32,
+  ) -> Self
   {
-    let surface_format = TextureFormat::Bgra8UnormSrgb;
     let depth_format = TextureFormat::Depth32Float;
 
     let camera = CameraMatrices::default();
     let camera_gpu = CameraGpu::create(device);
 
-    // Basic depth texture for 3D rendering
-    let depth_texture = device.create_texture(&TextureDescriptor {
-      label: Some("Depth Texture"),
-      size: Extent3d { width, height, depth_or_array_layers: 1 },
-      mip_level_count: 1,
-      sample_count: 1,
-      dimension: TextureDimension::D2,
-      format: depth_format,
-      usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
-      view_formats: &[],
-    });
-
-    let depth_view = depth_texture.create_view(&TextureViewDescriptor::default());
+    let depth_view = create_target(device, "Depth Texture", depth_format, width, height, 1);
     let body_registry = BodyRegistry::new();
     Self {
       mode: CameraMode::Orbital,
+      caps,
       camera,
       camera_gpu,
+      projection: Projection::default(),
+      focus_distance: 0.0,
       surface_format,
+      transparent: false,
       depth_format,
       depth_view,
+      sample_count: 1,
+      msaa_view: None,
+      fxaa: false,
+      samplers: SamplerCache::default(),
+      filtering: TextureFiltering::default(),
+      mipmaps: MipGenerator::new(device),
       screen_width: width,
       screen_height: height,
       target_body_pos: glam::DVec3::ZERO,
+      pivot: glam::DVec3::ZERO,
+      show_axes: true,
       eye_world: glam::DVec3::new(0.0, 0.0, 5.0),
       body_registry,
+      palette: Palette::default(),
+      selected: Vec::new(),
+      body_mesh: None,
+      body_mesh_uvs: None,
+      display_mode: DisplayMode::Shaded,
+      field: FieldView::default(),
+      sim_alpha: 0.0,
+      degraded: false,
+      exporting: false,
+    }
+  }
+
+  /// Rebuild the depth and multisampled colour targets after a resize or
+  /// a change of sample_count.
+  pub fn rebuild_targets(&mut self, device: &Device, width: u32, height: u32)
+  {
+    let samples = self.sample_count;
+    self.screen_width = width;
+    self.screen_height = height;
+    self.depth_view =
+      create_target(device, "Depth Texture", self.depth_format, width, height, samples);
+
+    self.msaa_view = None;
+    if samples > 1
+    {
+      let format = self.surface_format;
+      self.msaa_view = Some(create_target(device, "MSAA Colour", format, width, height, samples));
     }
   }
 }
 
+pub fn create_target(
+  device: &Device,
+  label: &str,
+  format: TextureFormat,
+  width: u32,
+  height: u32,
+  sample_count: u32,
+) -> TextureView
+{
+  let texture = device.create_texture(&TextureDescriptor {
+    label: Some(label),
+    size: Extent3d { width, height, depth_or_array_layers: 1 },
+    mip_level_count: 1,
+    sample_count,
+    dimension: TextureDimension::D2,
+    format,
+    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+    view_formats: &[],
+  });
+  texture.create_view(&TextureViewDescriptor::default())
+}
+
 pub struct FrameTargets<'a>
 {
+  /// Colour target for scene and overlay passes: the multisampled texture
+  /// when MSAA is on, otherwise the swapchain image.
   pub surface_view: &'a TextureView,
+  /// The swapchain image when MSAA is on; passes resolve into it.
+  pub resolve_view: Option<&'a TextureView>,
   pub depth_view: &'a TextureView,
 }
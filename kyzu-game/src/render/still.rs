@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+
+use glam::Vec2;
+
+use crate::core::task::TaskHandle;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Still render
+//
+//  A presentation image at any resolution, built up over many frames: each
+//  sample renders the scene offscreen with the projection nudged by a
+//  sub-pixel jitter (Halton 2,3), and the samples are averaged in linear
+//  light. The App drives it a few samples per frame and reports progress
+//  through a task.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Size and sample count chosen in the settings window.
+#[derive(Debug, Clone, Copy)]
+pub struct StillSettings
+{
+  pub width: u32,
+  pub height: u32,
+  pub samples: u32,
+}
+
+impl Default for StillSettings
+{
+  fn default() -> Self
+  {
+    Self { width: 1920, height: 1080, samples: 64 }
+  }
+}
+
+pub struct StillRender
+{
+  pub width: u32,
+  pub height: u32,
+  pub samples: u32,
+  pub output: PathBuf,
+  pub task: TaskHandle,
+  done: u32,
+  /// Running sum of every sample, RGBA in linear light.
+  accum: Vec<f32>,
+}
+
+impl StillRender
+{
+  pub fn new(settings: StillSettings, output: PathBuf, task: TaskHandle) -> Self
+  {
+    let (width, height) = (settings.width.max(1), settings.height.max(1));
+    Self {
+      width,
+      height,
+      samples: settings.samples.max(1),
+      output,
+      task,
+      done: 0,
+      accum: vec![0.0; (width * height * 4) as usize],
+    }
+  }
+
+  pub fn is_done(&self) -> bool
+  {
+    self.done >= self.samples
+  }
+
+  /// Offset of the next sample within its pixel, in [-0.5, 0.5).
+  pub fn next_jitter(&self) -> Vec2
+  {
+    let index = self.done + 1;
+    Vec2::new(halton(index, 2) - 0.5, halton(index, 3) - 0.5)
+  }
+
+  /// Add one sample: sRGB-encoded RGBA8, rows top to bottom.
+  pub fn accumulate(&mut self, rgba: &[u8])
+  {
+    for (i, (sum, byte)) in self.accum.iter_mut().zip(rgba).enumerate()
+    {
+      let value = *byte as f32 / 255.0;
+      let mut linear = value;
+      if i % 4 != 3
+      {
+        linear = srgb_to_linear(value);
+      }
+      *sum += linear;
+    }
+
+    self.done += 1;
+    let message = format!("{}/{} samples", self.done, self.samples);
+    self.task.set_progress(self.done as f32 / self.samples as f32, &message);
+  }
+
+  /// Throw the samples so far away, for when the view changed under them;
+  /// accumulation starts again from the first jitter.
+  pub fn restart(&mut self)
+  {
+    self.done = 0;
+    self.accum.fill(0.0);
+    let message = format!("0/{} samples (view changed)", self.samples);
+    self.task.set_progress(0.0, &message);
+  }
+
+  /// The average so far, sRGB-encoded RGBA8.
+  pub fn resolve(&self) -> Vec<u8>
+  {
+    let scale = 1.0 / self.done.max(1) as f32;
+    let mut rgba = Vec::with_capacity(self.accum.len());
+    for (i, sum) in self.accum.iter().enumerate()
+    {
+      let mut value = sum * scale;
+      if i % 4 != 3
+      {
+        value = linear_to_srgb(value);
+      }
+      rgba.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+    }
+    rgba
+  }
+}
+
+/// Low-discrepancy sequence: `index` written in `base`, mirrored about the
+/// radix point. Fills [0, 1) evenly for any prefix length.
+fn halton(mut index: u32, base: u32) -> f32
+{
+  let mut result = 0.0;
+  let mut fraction = 1.0 / base as f32;
+  while index > 0
+  {
+    result += (index % base) as f32 * fraction;
+    index /= base;
+    fraction /= base as f32;
+  }
+  result
+}
+
+fn srgb_to_linear(c: f32) -> f32
+{
+  if c <= 0.04045
+  {
+    return c / 12.92;
+  }
+  ((c + 0.055) / 1.055).powf(2.4)
+}
+
+fn linear_to_srgb(c: f32) -> f32
+{
+  if c <= 0.0031308
+  {
+    return c * 12.92;
+  }
+  1.055 * c.powf(1.0 / 2.4) - 0.055
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn halton_base_two_bisects()
+  {
+    let values: Vec<f32> = (1..=4).map(|i| halton(i, 2)).collect();
+    assert_eq!(values, vec![0.5, 0.25, 0.75, 0.125]);
+  }
+
+  #[test]
+  fn averaging_identical_samples_is_lossless()
+  {
+    let settings = StillSettings { width: 2, height: 1, samples: 3 };
+    let mut still = StillRender::new(settings, PathBuf::new(), TaskHandle::new("Still"));
+    let pixels = [10, 128, 250, 255, 0, 64, 200, 100];
+    for _ in 0..3
+    {
+      still.accumulate(&pixels);
+    }
+    assert!(still.is_done());
+    assert_eq!(still.resolve(), pixels.to_vec());
+  }
+
+  #[test]
+  fn restart_drops_earlier_samples()
+  {
+    let settings = StillSettings { width: 1, height: 1, samples: 2 };
+    let mut still = StillRender::new(settings, PathBuf::new(), TaskHandle::new("Still"));
+    let first_jitter = still.next_jitter();
+    still.accumulate(&[255, 255, 255, 255]);
+    still.restart();
+
+    assert_eq!(still.next_jitter(), first_jitter);
+    assert_eq!(still.task.progress(), 0.0);
+    still.accumulate(&[0, 0, 0, 255]);
+    still.accumulate(&[0, 0, 0, 255]);
+    assert!(still.is_done());
+    assert_eq!(still.resolve(), vec![0, 0, 0, 255]);
+  }
+}
@@ -0,0 +1,105 @@
+use wgpu::{CompositeAlphaMode, SurfaceCapabilities, TextureFormat};
+
+use crate::core::config::PresentMode;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Surface negotiation
+//
+//  Picks the swapchain format and alpha mode from what the surface offers,
+//  rather than trusting whatever the driver happens to list first.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Preferred formats in order. sRGB first so shaders can output linear colour.
+const PREFERRED_FORMATS: [TextureFormat; 4] = [
+  TextureFormat::Bgra8UnormSrgb,
+  TextureFormat::Rgba8UnormSrgb,
+  TextureFormat::Bgra8Unorm,
+  TextureFormat::Rgba8Unorm,
+];
+
+/// Alpha modes that let the desktop show through transparent pixels.
+const TRANSPARENT_ALPHA_MODES: [CompositeAlphaMode; 2] =
+  [CompositeAlphaMode::PreMultiplied, CompositeAlphaMode::PostMultiplied];
+
+pub struct SurfaceChoice
+{
+  pub format: TextureFormat,
+  pub alpha_mode: CompositeAlphaMode,
+  /// True only if transparency was asked for and the surface supports it.
+  pub transparent: bool,
+}
+
+pub fn negotiate(caps: &SurfaceCapabilities, want_transparent: bool) -> SurfaceChoice
+{
+  SurfaceChoice {
+    format: choose_format(&caps.formats),
+    alpha_mode: choose_alpha_mode(&caps.alpha_modes, want_transparent),
+    transparent: want_transparent && supports_transparency(&caps.alpha_modes),
+  }
+}
+
+pub fn wgpu_present_mode(mode: PresentMode) -> wgpu::PresentMode
+{
+  match mode
+  {
+    PresentMode::Fifo => wgpu::PresentMode::Fifo,
+    PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+    PresentMode::Immediate => wgpu::PresentMode::Immediate,
+  }
+}
+
+fn choose_format(available: &[TextureFormat]) -> TextureFormat
+{
+  for preferred in PREFERRED_FORMATS
+  {
+    if available.contains(&preferred)
+    {
+      return preferred;
+    }
+  }
+
+  // Any sRGB format beats a linear one; failing that, take the first.
+  for format in available
+  {
+    if format.is_srgb()
+    {
+      return *format;
+    }
+  }
+
+  available[0]
+}
+
+fn choose_alpha_mode(available: &[CompositeAlphaMode], want_transparent: bool)
+  -> CompositeAlphaMode
+{
+  if want_transparent
+  {
+    for mode in TRANSPARENT_ALPHA_MODES
+    {
+      if available.contains(&mode)
+      {
+        return mode;
+      }
+    }
+  }
+
+  if available.contains(&CompositeAlphaMode::Opaque)
+  {
+    return CompositeAlphaMode::Opaque;
+  }
+
+  available[0]
+}
+
+fn supports_transparency(available: &[CompositeAlphaMode]) -> bool
+{
+  for mode in TRANSPARENT_ALPHA_MODES
+  {
+    if available.contains(&mode)
+    {
+      return true;
+    }
+  }
+  false
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use wgpu::*;
+
+use crate::core::png::RgbaImage;
+use crate::render::mipmap::{self, MipGenerator};
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Textures
+//
+//  Upload path for decoded images. Colour images (albedo) are sRGB encoded
+//  and use an Rgba8UnormSrgb format so sampling returns linear values;
+//  data images (heights, masks) use Rgba8Unorm and are read as stored.
+//
+//  Samplers depend only on their filtering, so one of each kind is created
+//  on first use and shared through SharedState. Surface textures follow the
+//  configured TextureFiltering (anisotropy, trilinear).
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct GpuTexture
+{
+  pub texture: Texture,
+  pub view: TextureView,
+}
+
+/// Upload a decoded image. `srgb` marks colour data.
+/// With a generator the full mip chain is built on the GPU; without one
+/// the texture has a single level.
+pub fn upload_rgba8(
+  device: &Device,
+  queue: &Queue,
+  mips: Option<&mut MipGenerator>,
+  label: &str,
+  image: &RgbaImage,
+  srgb: bool,
+) -> GpuTexture
+{
+  let (width, height, rgba) = (image.0, image.1, &image.2);
+  let mut mip_level_count = 1;
+  let mut usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST;
+  if mips.is_some()
+  {
+    mip_level_count = mipmap::mip_level_count(width, height);
+    usage |= TextureUsages::RENDER_ATTACHMENT;
+  }
+
+  let mut format = TextureFormat::Rgba8Unorm;
+  if srgb
+  {
+    format = TextureFormat::Rgba8UnormSrgb;
+  }
+
+  let size = Extent3d { width, height, depth_or_array_layers: 1 };
+  let texture = device.create_texture(&TextureDescriptor {
+    label: Some(label),
+    size,
+    mip_level_count,
+    sample_count: 1,
+    dimension: TextureDimension::D2,
+    format,
+    usage,
+    view_formats: &[],
+  });
+
+  queue.write_texture(
+    TexelCopyTextureInfo {
+      texture: &texture,
+      mip_level: 0,
+      origin: Origin3d::ZERO,
+      aspect: TextureAspect::All,
+    },
+    rgba,
+    TexelCopyBufferLayout {
+      offset: 0,
+      bytes_per_row: Some(width * 4),
+      rows_per_image: Some(height),
+    },
+    size,
+  );
+  if let Some(mips) = mips
+  {
+    mips.generate(device, queue, &texture);
+  }
+
+  let view = texture.create_view(&TextureViewDescriptor::default());
+  GpuTexture { texture, view }
+}
+
+/// 1×1 white, bound where a body has no texture of its own.
+pub fn white(device: &Device, queue: &Queue) -> GpuTexture
+{
+  upload_rgba8(device, queue, None, "White Texture", &(1, 1, vec![255; 4]), true)
+}
+
+/// Quality settings for surface texture sampling, from the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureFiltering
+{
+  /// Maximum anisotropy, 1 (off) to 16. wgpu drops it to 1 on adapters
+  /// without anisotropic filtering.
+  pub anisotropy: u16,
+  /// Blend between mip levels; off picks the nearest level.
+  pub trilinear: bool,
+}
+
+impl Default for TextureFiltering
+{
+  fn default() -> Self
+  {
+    Self { anisotropy: 1, trilinear: true }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SamplerKind
+{
+  /// Linear with repeat addressing, for surface textures.
+  Linear(TextureFiltering),
+  /// Point sampling with clamped addressing, for data lookups.
+  Nearest,
+}
+
+#[derive(Default)]
+pub struct SamplerCache
+{
+  samplers: HashMap<SamplerKind, Sampler>,
+}
+
+impl SamplerCache
+{
+  pub fn get(&mut self, device: &Device, kind: SamplerKind) -> Sampler
+  {
+    self.samplers.entry(kind).or_insert_with(|| create_sampler(device, kind)).clone()
+  }
+}
+
+fn create_sampler(device: &Device, kind: SamplerKind) -> Sampler
+{
+  device.create_sampler(&sampler_descriptor(kind))
+}
+
+fn sampler_descriptor(kind: SamplerKind) -> SamplerDescriptor<'static>
+{
+  match kind
+  {
+    SamplerKind::Linear(filtering) =>
+    {
+      // Anisotropy needs every filter linear, so it goes with trilinear.
+      let mut mipmap_filter = FilterMode::Nearest;
+      let mut anisotropy_clamp = 1;
+      if filtering.trilinear
+      {
+        mipmap_filter = FilterMode::Linear;
+        anisotropy_clamp = filtering.anisotropy.clamp(1, 16);
+      }
+      SamplerDescriptor {
+        label: Some("Linear Sampler"),
+        address_mode_u: AddressMode::Repeat,
+        address_mode_v: AddressMode::Repeat,
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        mipmap_filter,
+        anisotropy_clamp,
+        ..Default::default()
+      }
+    }
+    SamplerKind::Nearest =>
+    {
+      SamplerDescriptor { label: Some("Nearest Sampler"), ..Default::default() }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  #[test]
+  fn anisotropy_only_applies_with_trilinear()
+  {
+    let sharp =
+      sampler_descriptor(SamplerKind::Linear(TextureFiltering { anisotropy: 32, trilinear: true }));
+    assert_eq!(sharp.anisotropy_clamp, 16);
+    assert_eq!(sharp.mipmap_filter, FilterMode::Linear);
+
+    let nearest_mip =
+      sampler_descriptor(SamplerKind::Linear(TextureFiltering { anisotropy: 8, trilinear: false }));
+    assert_eq!(nearest_mip.anisotropy_clamp, 1);
+    assert_eq!(nearest_mip.mipmap_filter, FilterMode::Nearest);
+
+    let off =
+      sampler_descriptor(SamplerKind::Linear(TextureFiltering { anisotropy: 0, trilinear: true }));
+    assert_eq!(off.anisotropy_clamp, 1);
+  }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  GpuWatchdog
+//
+//  Watches for a GPU that is not keeping up: frames far over the threshold
+//  several times in a row, or the surface timing out on acquire. Trips once;
+//  the app then switches the renderer to degraded quality and tells the
+//  player, rather than letting a weak GPU lock up the desktop.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Consecutive slow frames needed to trip. One slow frame is usually a
+/// loading hitch or the window being dragged, not a struggling GPU.
+const SLOW_FRAME_STRIKES: u32 = 3;
+
+pub struct GpuWatchdog
+{
+  threshold: Duration,
+  slow_frames: u32,
+  timeouts_seen: u32,
+  pub tripped: bool,
+}
+
+impl GpuWatchdog
+{
+  pub fn new(threshold: Duration) -> Self
+  {
+    Self { threshold, slow_frames: 0, timeouts_seen: 0, tripped: false }
+  }
+
+  /// Call once per frame with the frame time and the renderer's running
+  /// surface timeout count. Returns the reason the first time it trips.
+  pub fn check(&mut self, frame_time: Duration, surface_timeouts: u32) -> Option<String>
+  {
+    if self.tripped
+    {
+      return None;
+    }
+
+    if surface_timeouts > self.timeouts_seen
+    {
+      self.timeouts_seen = surface_timeouts;
+      self.tripped = true;
+      return Some("GPU timed out acquiring a frame".to_string());
+    }
+
+    if frame_time > self.threshold
+    {
+      self.slow_frames += 1;
+    }
+    else
+    {
+      self.slow_frames = 0;
+    }
+
+    if self.slow_frames >= SLOW_FRAME_STRIKES
+    {
+      self.tripped = true;
+      let ms = frame_time.as_millis();
+      return Some(format!(
+        "{} frames in a row over the limit (last took {} ms)",
+        SLOW_FRAME_STRIKES, ms
+      ));
+    }
+
+    None
+  }
+}
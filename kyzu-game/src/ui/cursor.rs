@@ -0,0 +1,296 @@
+use winit::event::MouseButton;
+use winit::event_loop::ActiveEventLoop;
+use winit::keyboard::KeyCode;
+use winit::window::CustomCursor;
+
+use crate::input::state::InputState;
+use crate::render::shared::CameraMode;
+use crate::world::selection::SelectMode;
+
+/// Side of the custom cursor images, in pixels.
+const CUSTOM_SIZE: i32 = 32;
+/// Where the crosshair in the custom images is centred.
+const HOTSPOT: i32 = 15;
+/// Centre and radius of the +/- badge in the custom images.
+const BADGE: i32 = 24;
+const BADGE_RADIUS: i32 = 6;
+
+/// Icon egui is given while a custom cursor shows. The viewport never uses
+/// it otherwise, so leaving the custom cursor is a change egui acts on.
+const CUSTOM_STAND_IN: egui::CursorIcon = egui::CursorIcon::Alias;
+
+/// Cursors drawn in code, for what the system set has no shape for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomShape
+{
+  /// A click adds the body under the cursor to the selection (Shift).
+  PickAdd,
+  /// A click removes it (Ctrl).
+  PickSubtract,
+}
+
+impl CustomShape
+{
+  /// Nearest system cursor, when custom cursors could not be created.
+  fn fallback(self) -> egui::CursorIcon
+  {
+    match self
+    {
+      CustomShape::PickAdd => egui::CursorIcon::Copy,
+      CustomShape::PickSubtract => egui::CursorIcon::NotAllowed,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewportCursor
+{
+  System(egui::CursorIcon),
+  Custom(CustomShape),
+}
+
+/// Cursor for the viewport given the camera mode and what is held.
+/// Set through egui (which owns the window cursor) and only when the
+/// pointer is not over a panel, so egui's own cursors still win there.
+pub fn viewport_cursor(mode: CameraMode, input: &InputState) -> ViewportCursor
+{
+  // The pointer is grabbed and hidden while dragging the view.
+  if input.look_grab.is_some()
+  {
+    return ViewportCursor::System(egui::CursorIcon::None);
+  }
+
+  let dragging = input.mouse_buttons_down.contains(&MouseButton::Right);
+  if dragging
+  {
+    let mut icon = egui::CursorIcon::Crosshair;
+    if mode == CameraMode::Orbital
+    {
+      icon = egui::CursorIcon::Grabbing;
+    }
+    return ViewportCursor::System(icon);
+  }
+
+  // A left drag is sweeping out a selection.
+  if input.marquee().is_some()
+  {
+    return ViewportCursor::System(egui::CursorIcon::Crosshair);
+  }
+
+  let held = |keys: &[KeyCode]| keys.iter().any(|&key| input.is_key_down(key));
+
+  match (mode, input.select_mode())
+  {
+    (_, SelectMode::Subtract) => ViewportCursor::Custom(CustomShape::PickSubtract),
+    // Shift+scroll changes the flight speed gear.
+    (CameraMode::Free, SelectMode::Add) => ViewportCursor::System(egui::CursorIcon::ResizeVertical),
+    (CameraMode::Orbital, SelectMode::Add) => ViewportCursor::Custom(CustomShape::PickAdd),
+    (CameraMode::Free, SelectMode::Replace) => ViewportCursor::System(egui::CursorIcon::Default),
+    (CameraMode::Orbital, SelectMode::Replace) =>
+    {
+      let mut icon = egui::CursorIcon::Grab;
+      if held(&[KeyCode::KeyW, KeyCode::KeyA, KeyCode::KeyS, KeyCode::KeyD])
+      {
+        icon = egui::CursorIcon::AllScroll;
+      }
+      if held(&[KeyCode::Equal, KeyCode::NumpadAdd])
+      {
+        icon = egui::CursorIcon::ZoomIn;
+      }
+      if held(&[KeyCode::Minus, KeyCode::NumpadSubtract])
+      {
+        icon = egui::CursorIcon::ZoomOut;
+      }
+      ViewportCursor::System(icon)
+    }
+  }
+}
+
+/// The custom cursors, created once the event loop is running.
+pub struct CustomCursors
+{
+  pick_add: CustomCursor,
+  pick_subtract: CustomCursor,
+}
+
+impl CustomCursors
+{
+  pub fn new(event_loop: &ActiveEventLoop) -> anyhow::Result<Self>
+  {
+    let create = |shape: CustomShape| -> anyhow::Result<CustomCursor> {
+      let size = CUSTOM_SIZE as u16;
+      let hotspot = HOTSPOT as u16;
+      let source = CustomCursor::from_rgba(custom_pixels(shape), size, size, hotspot, hotspot)?;
+      Ok(event_loop.create_custom_cursor(source))
+    };
+    Ok(Self {
+      pick_add: create(CustomShape::PickAdd)?,
+      pick_subtract: create(CustomShape::PickSubtract)?,
+    })
+  }
+
+  fn get(&self, shape: CustomShape) -> &CustomCursor
+  {
+    match shape
+    {
+      CustomShape::PickAdd => &self.pick_add,
+      CustomShape::PickSubtract => &self.pick_subtract,
+    }
+  }
+}
+
+/// Set this frame's viewport cursor. A custom one is returned rather than
+/// set: it must go on the window after egui has handled its output
+/// (UiSystem::end_frame), which would otherwise replace it. Without
+/// `custom` the nearest system cursor stands in.
+pub fn apply(
+  ctx: &egui::Context,
+  mode: CameraMode,
+  input: &InputState,
+  custom: Option<&CustomCursors>,
+) -> Option<CustomCursor>
+{
+  if ctx.is_pointer_over_area()
+  {
+    return None;
+  }
+
+  match (viewport_cursor(mode, input), custom)
+  {
+    (ViewportCursor::System(icon), _) =>
+    {
+      ctx.set_cursor_icon(icon);
+      None
+    }
+    (ViewportCursor::Custom(shape), Some(cursors)) =>
+    {
+      ctx.set_cursor_icon(CUSTOM_STAND_IN);
+      Some(cursors.get(shape).clone())
+    }
+    (ViewportCursor::Custom(shape), None) =>
+    {
+      ctx.set_cursor_icon(shape.fallback());
+      None
+    }
+  }
+}
+
+/// RGBA pixels for a custom cursor: a crosshair with a gap at the hotspot,
+/// and a + or - badge below right. White on a black outline so it shows
+/// over both the dark sky and lit bodies.
+pub fn custom_pixels(shape: CustomShape) -> Vec<u8>
+{
+  const OUTLINE: [u8; 4] = [0, 0, 0, 255];
+  const FILL: [u8; 4] = [255, 255, 255, 255];
+
+  let mut rgba = vec![0; (CUSTOM_SIZE * CUSTOM_SIZE * 4) as usize];
+  for y in 0..CUSTOM_SIZE
+  {
+    for x in 0..CUSTOM_SIZE
+    {
+      let (dx, dy) = ((x - HOTSPOT).abs(), (y - HOTSPOT).abs());
+      let mut colour = None;
+
+      let outline_arm = |along: i32, across: i32| (3..=10).contains(&along) && across <= 1;
+      if outline_arm(dx, dy) || outline_arm(dy, dx)
+      {
+        colour = Some(OUTLINE);
+      }
+      let arm = |along: i32, across: i32| (4..=9).contains(&along) && across == 0;
+      if arm(dx, dy) || arm(dy, dx)
+      {
+        colour = Some(FILL);
+      }
+
+      let (bx, by) = (x - BADGE, y - BADGE);
+      if bx * bx + by * by <= BADGE_RADIUS * BADGE_RADIUS
+      {
+        colour = Some(OUTLINE);
+        let bar = bx.abs() <= 3 && by == 0;
+        let stem = shape == CustomShape::PickAdd && by.abs() <= 3 && bx == 0;
+        if bar || stem
+        {
+          colour = Some(FILL);
+        }
+      }
+
+      if let Some(colour) = colour
+      {
+        let at = ((y * CUSTOM_SIZE + x) * 4) as usize;
+        rgba[at..at + 4].copy_from_slice(&colour);
+      }
+    }
+  }
+  rgba
+}
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+
+  fn press(input: &mut InputState, key: KeyCode)
+  {
+    input.keys_down.insert(key);
+  }
+
+  #[test]
+  fn modifiers_show_what_a_click_will_do()
+  {
+    let mut input = InputState::new();
+    assert_eq!(
+      viewport_cursor(CameraMode::Orbital, &input),
+      ViewportCursor::System(egui::CursorIcon::Grab)
+    );
+
+    press(&mut input, KeyCode::ShiftLeft);
+    assert_eq!(
+      viewport_cursor(CameraMode::Orbital, &input),
+      ViewportCursor::Custom(CustomShape::PickAdd)
+    );
+    // In free flight Shift is the speed gear.
+    assert_eq!(
+      viewport_cursor(CameraMode::Free, &input),
+      ViewportCursor::System(egui::CursorIcon::ResizeVertical)
+    );
+
+    press(&mut input, KeyCode::ControlLeft);
+    assert_eq!(
+      viewport_cursor(CameraMode::Free, &input),
+      ViewportCursor::Custom(CustomShape::PickSubtract)
+    );
+  }
+
+  #[test]
+  fn orbit_keys_show_pan_and_zoom()
+  {
+    let mut input = InputState::new();
+    press(&mut input, KeyCode::KeyW);
+    assert_eq!(
+      viewport_cursor(CameraMode::Orbital, &input),
+      ViewportCursor::System(egui::CursorIcon::AllScroll)
+    );
+    press(&mut input, KeyCode::Minus);
+    assert_eq!(
+      viewport_cursor(CameraMode::Orbital, &input),
+      ViewportCursor::System(egui::CursorIcon::ZoomOut)
+    );
+  }
+
+  #[test]
+  fn custom_images_leave_the_hotspot_clear()
+  {
+    let add = custom_pixels(CustomShape::PickAdd);
+    let subtract = custom_pixels(CustomShape::PickSubtract);
+    assert_eq!(add.len(), (CUSTOM_SIZE * CUSTOM_SIZE * 4) as usize);
+    assert_ne!(add, subtract);
+
+    let alpha = |rgba: &[u8], x: i32, y: i32| rgba[((y * CUSTOM_SIZE + x) * 4 + 3) as usize];
+    assert_eq!(alpha(&add, HOTSPOT, HOTSPOT), 0);
+    assert_eq!(alpha(&add, HOTSPOT + 6, HOTSPOT), 255);
+    // The + stem is what tells the two apart.
+    assert_eq!(alpha(&add, BADGE, BADGE - 2), 255);
+    assert_eq!(add[((BADGE - 2) * CUSTOM_SIZE + BADGE) as usize * 4], 255);
+    assert_eq!(subtract[((BADGE - 2) * CUSTOM_SIZE + BADGE) as usize * 4], 0);
+  }
+}
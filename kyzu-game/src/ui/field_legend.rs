@@ -0,0 +1,68 @@
+use crate::command::Command;
+use crate::core::palette::{self, ColorMap};
+use crate::render::shared::SharedState;
+
+/// Size of the colour bar, in points.
+const BAR_SIZE: egui::Vec2 = egui::vec2(220.0, 14.0);
+/// Flat slices the bar is drawn with.
+const BAR_SLICES: usize = 64;
+
+/// Legend for the elevation field in the bottom-right corner: the colour
+/// bar with its clamp range, a colour map choice and the range itself.
+pub fn draw(ctx: &egui::Context, shared: &SharedState) -> Option<Command>
+{
+  let field = shared.field;
+  let mut command = None;
+
+  egui::Area::new(egui::Id::new("field_legend"))
+    .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -40.0))
+    .show(ctx, |ui| {
+      egui::Frame::popup(ui.style()).show(ui, |ui| {
+        ui.label("Elevation (m)");
+
+        let (rect, _) = ui.allocate_exact_size(BAR_SIZE, egui::Sense::hover());
+        let slice = rect.width() / BAR_SLICES as f32;
+        for i in 0..BAR_SLICES
+        {
+          let t = (i as f32 + 0.5) / BAR_SLICES as f32;
+          let left = rect.left() + slice * i as f32;
+          let cell = egui::Rect::from_min_max(
+            egui::pos2(left, rect.top()),
+            egui::pos2(left + slice + 0.5, rect.bottom()),
+          );
+          ui.painter().rect_filled(
+            cell,
+            0.0,
+            palette::to_color32(field.map.color(&shared.palette, t), 255),
+          );
+        }
+
+        let (mut min, mut max) = (field.min, field.max);
+        ui.horizontal(|ui| {
+          let speed = ((field.extent[1] - field.extent[0]).abs() / 200.0).max(1.0);
+          let min_changed = ui.add(egui::DragValue::new(&mut min).speed(speed)).changed();
+          let max_changed = ui.add(egui::DragValue::new(&mut max).speed(speed)).changed();
+          if min_changed || max_changed
+          {
+            command = Some(Command::SetFieldRange { min, max });
+          }
+          if ui.small_button("Fit").clicked()
+          {
+            command = Some(Command::SetFieldRange { min: field.extent[0], max: field.extent[1] });
+          }
+        });
+
+        ui.horizontal(|ui| {
+          for map in ColorMap::ALL
+          {
+            if ui.selectable_label(field.map == map, map.name()).clicked() && field.map != map
+            {
+              command = Some(Command::SetColorMap(map));
+            }
+          }
+        });
+      });
+    });
+
+  command
+}
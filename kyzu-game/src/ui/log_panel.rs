@@ -0,0 +1,25 @@
+use crate::core::log::{LogLevel, Logger};
+
+/// Scrolling window over the logger's in-memory ring buffer.
+pub fn draw(ctx: &egui::Context, logger: &Logger, open: &mut bool)
+{
+  egui::Window::new("Log").open(open).default_width(520.0).show(ctx, |ui| {
+    egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+      for entry in &logger.buffer
+      {
+        ui.colored_label(level_color(&entry.level), &entry.message);
+      }
+    });
+  });
+}
+
+fn level_color(level: &LogLevel) -> egui::Color32
+{
+  match level
+  {
+    LogLevel::Info => egui::Color32::LIGHT_GRAY,
+    LogLevel::Warning => egui::Color32::from_rgb(240, 190, 60),
+    LogLevel::Error | LogLevel::Critical => egui::Color32::from_rgb(235, 80, 70),
+    LogLevel::Debug => egui::Color32::GRAY,
+  }
+}
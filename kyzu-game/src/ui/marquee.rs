@@ -0,0 +1,58 @@
+use glam::Vec2;
+
+use crate::core::palette::{self, Palette};
+use crate::input::state::InputState;
+use crate::world::selection::SelectTool;
+
+const FILL_ALPHA: u8 = 40;
+const EDGE_WIDTH: f32 = 1.0;
+
+/// Feedback for the active selection tool: the rubber band or lasso trail
+/// while dragging, and the paint brush circle under the cursor.
+pub fn draw(
+  ctx: &egui::Context,
+  tool: SelectTool,
+  input: &InputState,
+  brush_radius: f32,
+  palette: &Palette,
+)
+{
+  let edge = palette::to_color32(palette.selection, 255);
+  let fill = palette::to_color32(palette.selection, FILL_ALPHA);
+  let scale = ctx.pixels_per_point();
+  let to_point = |p: Vec2| egui::pos2(p.x / scale, p.y / scale);
+  let painter = ctx.layer_painter(egui::LayerId::background());
+  let stroke = egui::Stroke::new(EDGE_WIDTH, edge);
+  let dragging = input.marquee().is_some();
+
+  match tool
+  {
+    SelectTool::Box =>
+    {
+      if let Some((min, max)) = input.marquee()
+      {
+        let area = egui::Rect::from_min_max(to_point(min), to_point(max));
+        painter.rect_filled(area, 0.0, fill);
+        painter.rect_stroke(area, 0.0, stroke, egui::StrokeKind::Inside);
+      }
+    }
+    SelectTool::Lasso =>
+    {
+      if dragging && input.drag_path.len() > 1
+      {
+        let points: Vec<egui::Pos2> = input.drag_path.iter().map(|p| to_point(*p)).collect();
+        painter.add(egui::Shape::closed_line(points, stroke));
+      }
+    }
+    SelectTool::Paint =>
+    {
+      let centre = to_point(input.mouse_pos);
+      let radius = brush_radius / scale;
+      if dragging
+      {
+        painter.circle_filled(centre, radius, fill);
+      }
+      painter.circle_stroke(centre, radius, stroke);
+    }
+  }
+}
@@ -0,0 +1,26 @@
+use crate::command::Command;
+use crate::render::primitives::Primitive;
+
+/// Insert menu in the top-left corner. Returns the chosen shape as a
+/// command that adds it to the scene.
+pub fn draw(ctx: &egui::Context) -> Option<Command>
+{
+  let mut chosen = None;
+
+  egui::Area::new(egui::Id::new("insert_menu"))
+    .anchor(egui::Align2::LEFT_TOP, egui::vec2(8.0, 8.0))
+    .show(ctx, |ui| {
+      ui.menu_button("Insert", |ui| {
+        for primitive in Primitive::ALL
+        {
+          if ui.button(primitive.label()).clicked()
+          {
+            chosen = Some(primitive);
+            ui.close();
+          }
+        }
+      });
+    });
+
+  chosen.map(Command::InsertPrimitive)
+}
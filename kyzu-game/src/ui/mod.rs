@@ -1,16 +1,65 @@
-use std::any::Any;
+pub mod cursor;
+pub mod field_legend;
+pub mod log_panel;
+pub mod marquee;
+pub mod menu;
+pub mod overlay;
+pub mod pivot_marker;
+pub mod probe_panel;
+pub mod search;
+pub mod settings;
+pub mod status_bar;
+pub mod toasts;
+pub mod uv_panel;
+pub mod view_bar;
 
-use wgpu::{CommandEncoder, Device, Queue, TextureFormat};
+use wgpu::{CommandBuffer, CommandEncoder, Device, Queue, TextureFormat, TextureView};
+use winit::event::WindowEvent;
 use winit::window::Window;
 
-use crate::render::module::RenderModule;
-use crate::render::shared::{FrameTargets, SharedState};
+use crate::render::still::StillSettings;
+use crate::ui::cursor::CustomCursors;
+use crate::ui::pivot_marker::PivotMarker;
+use crate::ui::search::SearchDialog;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  UiSystem
+//
+//  Owns the egui context, its winit glue and its wgpu renderer. Each frame:
+//    begin_frame()  — gather input, start an egui pass
+//    (panels draw into context)
+//    end_frame()    — finish the pass and tessellate
+//    paint()        — called by Renderer::render after the scene modules
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct UiFrame
+{
+  paint_jobs: Vec<egui::ClippedPrimitive>,
+  textures_delta: egui::TexturesDelta,
+  pixels_per_point: f32,
+}
 
 pub struct UiSystem
 {
   pub context: egui::Context,
   pub state: egui_winit::State,
   pub renderer: egui_wgpu::Renderer,
+  pub show_log: bool,
+  pub show_telemetry: bool,
+  pub show_uv: bool,
+  pub show_settings: bool,
+  pub pivot_marker: PivotMarker,
+  pub search: SearchDialog,
+  /// Size and samples for the next still render.
+  pub still: StillSettings,
+  /// Pick cursors with a +/- badge; None until created (or if the
+  /// platform refused them), when system cursors stand in.
+  pub cursors: Option<CustomCursors>,
+  /// How soon egui wants another frame (Duration::MAX: not until input).
+  pub repaint_delay: std::time::Duration,
+  pending: Option<UiFrame>,
+  /// Textures egui asked to free; released once the frame using them is submitted.
+  to_free: Vec<egui::TextureId>,
 }
 
 impl UiSystem
@@ -30,18 +79,133 @@ impl UiSystem
 
     let renderer = egui_wgpu::Renderer::new(device, format, egui_wgpu::RendererOptions::default());
 
-    Self { context, state, renderer }
+    Self {
+      context,
+      state,
+      renderer,
+      show_log: false,
+      show_telemetry: false,
+      show_uv: false,
+      show_settings: false,
+      pivot_marker: PivotMarker::default(),
+      search: SearchDialog::default(),
+      still: StillSettings::default(),
+      cursors: None,
+      repaint_delay: std::time::Duration::ZERO,
+      pending: None,
+      to_free: Vec::new(),
+    }
   }
-}
 
-impl RenderModule for UiSystem
-{
-  fn update(&mut self, _queue: &Queue, _shared: &SharedState) {}
+  /// Start over on a new device after the old one was lost. The egui
+  /// context is replaced too so it re-uploads its font atlas; panel
+  /// toggles and dialogs are kept.
+  pub fn rebuild_gpu(&mut self, device: &Device, format: TextureFormat, window: &Window)
+  {
+    let fresh = UiSystem::new(device, format, window);
+    self.context = fresh.context;
+    self.state = fresh.state;
+    self.renderer = fresh.renderer;
+    self.pending = None;
+    self.to_free.clear();
+  }
+
+  /// Feed a window event to egui. Returns true if egui claimed it and the
+  /// game should not react (typing in a field, clicking a panel).
+  /// Button releases always reach the game so held buttons never stick.
+  pub fn on_window_event(&mut self, window: &Window, event: &WindowEvent) -> bool
+  {
+    let _ = self.state.on_window_event(window, event);
 
-  fn encode(&self, _encoder: &mut CommandEncoder, _targets: &FrameTargets, _shared: &SharedState) {}
+    match event
+    {
+      WindowEvent::KeyboardInput { event: key_event, .. } =>
+      {
+        key_event.state == winit::event::ElementState::Pressed
+          && self.context.wants_keyboard_input()
+      }
+      WindowEvent::MouseInput { state: winit::event::ElementState::Pressed, .. } =>
+      {
+        self.context.is_pointer_over_area()
+      }
+      WindowEvent::MouseWheel { .. } => self.context.is_pointer_over_area(),
+      _ => false,
+    }
+  }
 
-  fn as_any_mut(&mut self) -> &mut dyn Any
+  pub fn begin_frame(&mut self, window: &Window)
   {
-    self
+    let raw_input = self.state.take_egui_input(window);
+    self.context.begin_pass(raw_input);
+  }
+
+  pub fn end_frame(&mut self, window: &Window)
+  {
+    let output = self.context.end_pass();
+    self.state.handle_platform_output(window, output.platform_output);
+
+    self.repaint_delay = std::time::Duration::MAX;
+    if let Some(viewport) = output.viewport_output.get(&egui::viewport::ViewportId::ROOT)
+    {
+      self.repaint_delay = viewport.repaint_delay;
+    }
+
+    let paint_jobs = self.context.tessellate(output.shapes, output.pixels_per_point);
+    self.pending = Some(UiFrame {
+      paint_jobs,
+      textures_delta: output.textures_delta,
+      pixels_per_point: output.pixels_per_point,
+    });
+  }
+
+  /// Draw the pending frame over `view`. Returns extra command buffers that
+  /// must be submitted before `encoder`.
+  pub fn paint(
+    &mut self,
+    device: &Device,
+    queue: &Queue,
+    encoder: &mut CommandEncoder,
+    view: &TextureView,
+    size_in_pixels: [u32; 2],
+  ) -> Vec<CommandBuffer>
+  {
+    for id in self.to_free.drain(..)
+    {
+      self.renderer.free_texture(&id);
+    }
+
+    let frame = match self.pending.take()
+    {
+      Some(f) => f,
+      None => return Vec::new(),
+    };
+
+    for (id, delta) in &frame.textures_delta.set
+    {
+      self.renderer.update_texture(device, queue, *id, delta);
+    }
+
+    let screen =
+      egui_wgpu::ScreenDescriptor { size_in_pixels, pixels_per_point: frame.pixels_per_point };
+    let command_buffers =
+      self.renderer.update_buffers(device, queue, encoder, &frame.paint_jobs, &screen);
+
+    let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("UI Render Pass"),
+      color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+        view,
+        resolve_target: None,
+        ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+        depth_slice: None,
+      })],
+      ..Default::default()
+    });
+
+    let mut render_pass = render_pass.forget_lifetime();
+    self.renderer.render(&mut render_pass, &frame.paint_jobs, &screen);
+    drop(render_pass);
+
+    self.to_free = frame.textures_delta.free;
+    command_buffers
   }
 }
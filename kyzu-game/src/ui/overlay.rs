@@ -0,0 +1,56 @@
+use crate::core::math::format_distance;
+use crate::core::time::TimeState;
+use crate::render::camera::projection::RENDER_SCALE;
+use crate::render::shared::SharedState;
+
+/// Telemetry window: frame timing plus practical camera metrics derived
+/// from this frame's projection, measured at the camera's focus distance.
+pub fn draw(ctx: &egui::Context, shared: &SharedState, time: &TimeState, open: &mut bool)
+{
+  egui::Window::new("Telemetry").open(open).default_width(280.0).show(ctx, |ui| {
+    egui::Grid::new("telemetry_grid").num_columns(2).show(ui, |ui| {
+      row(ui, "FPS", &format!("{:.0}", time.fps));
+      row(ui, "Frame", &format!("{:.2} ms", time.delta.as_secs_f64() * 1000.0));
+      let latency = time.input_latency.as_secs_f64() * 1000.0;
+      row(ui, "Input to present", &format!("{:.2} ms", latency));
+      row(ui, "Frame index", &time.frame_count.to_string());
+      row(ui, "Uptime", &format!("{:.1} s", time.total_time.as_secs_f64()));
+      row(ui, "Camera", &format!("{:?}", shared.mode));
+
+      let eye = shared.eye_world;
+      row(ui, "Eye", &format!("{:.0}, {:.0}, {:.0} m", eye.x, eye.y, eye.z));
+      row(ui, "Focus distance", &format_distance(shared.focus_distance));
+
+      let projection = &shared.projection;
+      let depth = (shared.focus_distance / RENDER_SCALE) as f32;
+      let per_pixel = projection.units_per_pixel(depth, shared.screen_height) as f64;
+      let frustum = projection.frustum_size_at(depth);
+
+      row(ui, "One pixel", &format_distance(per_pixel * RENDER_SCALE));
+      row(
+        ui,
+        "Frustum at focus",
+        &format!(
+          "{} x {}",
+          format_distance(frustum.x as f64 * RENDER_SCALE),
+          format_distance(frustum.y as f64 * RENDER_SCALE)
+        ),
+      );
+      row(ui, "Near plane", &format_distance(projection.z_near as f64 * RENDER_SCALE));
+      row(ui, "Far plane", &format_distance(projection.z_far as f64 * RENDER_SCALE));
+      row(ui, "FOV", &format!("{:.1} deg", projection.fov_y_rad.to_degrees()));
+
+      if shared.degraded
+      {
+        row(ui, "Quality", "Reduced (GPU watchdog)");
+      }
+    });
+  });
+}
+
+fn row(ui: &mut egui::Ui, label: &str, value: &str)
+{
+  ui.label(label);
+  ui.monospace(value);
+  ui.end_row();
+}
@@ -0,0 +1,60 @@
+use glam::Vec3;
+
+use crate::core::palette::{self, Rgb};
+
+/// Seconds for the ring to fade out after the orbit drag is released.
+const FADE_SECONDS: f32 = 0.6;
+const RING_RADIUS: f32 = 14.0;
+const RING_WIDTH: f32 = 2.0;
+
+/// Small ring drawn over the orbit pivot while orbiting, so it is clear
+/// what the camera is turning around. Fades out after release.
+#[derive(Default)]
+pub struct PivotMarker
+{
+  opacity: f32,
+}
+
+impl PivotMarker
+{
+  pub fn update(&mut self, orbiting: bool, dt: f32)
+  {
+    if orbiting
+    {
+      self.opacity = 1.0;
+      return;
+    }
+    self.opacity = (self.opacity - dt / FADE_SECONDS).max(0.0);
+  }
+
+  /// `screen` is the pivot in pixels (from SharedState::project), or None
+  /// if it is behind the camera.
+  pub fn draw(&self, ctx: &egui::Context, screen: Option<Vec3>, colour: Rgb)
+  {
+    if self.opacity <= 0.0
+    {
+      return;
+    }
+
+    let screen = match screen
+    {
+      Some(s) => s,
+      None => return,
+    };
+
+    let scale = ctx.pixels_per_point();
+    let centre = egui::pos2(screen.x / scale, screen.y / scale);
+    let alpha = (self.opacity * 255.0) as u8;
+    let colour = palette::to_color32(colour, alpha);
+
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    painter.circle_stroke(centre, RING_RADIUS, egui::Stroke::new(RING_WIDTH, colour));
+    painter.circle_filled(centre, 2.5, colour);
+
+    // Keep frames coming until the fade finishes.
+    if self.opacity < 1.0
+    {
+      ctx.request_repaint();
+    }
+  }
+}
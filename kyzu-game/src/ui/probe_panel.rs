@@ -0,0 +1,62 @@
+use crate::command::Command;
+use crate::core::math::format_distance;
+use crate::world::probe::ProbeLog;
+
+/// Rows beyond this scroll.
+const TABLE_HEIGHT: f32 = 240.0;
+
+/// Recorded probe points with copy, export and clear. `export_path` is
+/// where the Export button writes the CSV.
+pub fn draw(ctx: &egui::Context, probe: &ProbeLog, export_path: &str) -> Option<Command>
+{
+  let mut command = None;
+  let mut open = probe.active;
+
+  egui::Window::new("Point probe").open(&mut open).default_width(420.0).show(ctx, |ui| {
+    ui.label("Click a surface to record the nearest vertex.");
+
+    egui::ScrollArea::vertical().max_height(TABLE_HEIGHT).show(ui, |ui| {
+      egui::Grid::new("probe_grid").num_columns(4).striped(true).show(ui, |ui| {
+        ui.strong("#");
+        ui.strong("Body");
+        ui.strong("Lat / lon");
+        ui.strong("Distance from first");
+        ui.end_row();
+
+        for (index, point) in probe.points.iter().enumerate()
+        {
+          ui.label((index + 1).to_string());
+          ui.label(&point.body);
+          ui.label(format!("{:.4}, {:.4}", point.lat_deg, point.lon_deg)).on_hover_text(format!(
+            "{:.1}, {:.1}, {:.1} m",
+            point.position.x, point.position.y, point.position.z
+          ));
+          ui.label(format_distance((point.position - probe.points[0].position).length()));
+          ui.end_row();
+        }
+      });
+    });
+
+    ui.separator();
+    ui.horizontal(|ui| {
+      if ui.button("Copy CSV").clicked()
+      {
+        ui.ctx().copy_text(probe.to_csv());
+      }
+      if ui.button("Export CSV").clicked()
+      {
+        command = Some(Command::ExportProbe(export_path.to_string()));
+      }
+      if ui.button("Clear").clicked()
+      {
+        command = Some(Command::ClearProbe);
+      }
+    });
+  });
+
+  if !open
+  {
+    command = Some(Command::ToggleProbe);
+  }
+  command
+}
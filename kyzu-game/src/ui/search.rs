@@ -0,0 +1,141 @@
+use crate::command::Command;
+use crate::world::body::BodyKind;
+use crate::world::registry::BodyRegistry;
+
+/// Most matches listed at once; narrow the query to see the rest.
+const MAX_RESULTS: usize = 50;
+
+/// "Go to body" quick-jump dialog (Ctrl+F). Matches the query against body
+/// names and kinds; Enter or a click frames the chosen body.
+#[derive(Default)]
+pub struct SearchDialog
+{
+  pub open: bool,
+  query: String,
+  selected: usize,
+  /// Focus the text field on the first frame after opening.
+  just_opened: bool,
+}
+
+impl SearchDialog
+{
+  pub fn toggle(&mut self)
+  {
+    self.open = !self.open;
+    self.just_opened = self.open;
+    self.query.clear();
+    self.selected = 0;
+  }
+
+  /// Returns a FocusBody command when the player picks a result.
+  pub fn draw(&mut self, ctx: &egui::Context, bodies: &BodyRegistry) -> Option<Command>
+  {
+    if !self.open
+    {
+      return None;
+    }
+
+    let matches = find_matches(bodies, &self.query);
+    if self.selected >= matches.len()
+    {
+      self.selected = matches.len().saturating_sub(1);
+    }
+
+    let mut chosen = None;
+    let mut open = self.open;
+
+    egui::Window::new("Go to body")
+      .open(&mut open)
+      .collapsible(false)
+      .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+      .default_width(320.0)
+      .show(ctx, |ui| {
+        let field = ui.text_edit_singleline(&mut self.query);
+        if self.just_opened
+        {
+          field.request_focus();
+          self.just_opened = false;
+        }
+
+        let (up, down, enter) = ui.input(|i| {
+          (
+            i.key_pressed(egui::Key::ArrowUp),
+            i.key_pressed(egui::Key::ArrowDown),
+            i.key_pressed(egui::Key::Enter),
+          )
+        });
+        if down && self.selected + 1 < matches.len()
+        {
+          self.selected += 1;
+        }
+        if up && self.selected > 0
+        {
+          self.selected -= 1;
+        }
+        if enter && !matches.is_empty()
+        {
+          chosen = Some(matches[self.selected].0.clone());
+        }
+
+        ui.separator();
+
+        if matches.is_empty()
+        {
+          ui.weak("No matching bodies");
+        }
+
+        for (row, (name, kind)) in matches.iter().enumerate()
+        {
+          let label = format!("{}  ({})", name, kind);
+          if ui.selectable_label(row == self.selected, label).clicked()
+          {
+            chosen = Some(name.clone());
+          }
+        }
+      });
+
+    self.open = open;
+
+    let name = chosen?;
+    self.open = false;
+    Some(Command::FocusBody(name))
+  }
+}
+
+/// (name, kind) of bodies whose name or kind contains the query, ignoring case.
+fn find_matches(bodies: &BodyRegistry, query: &str) -> Vec<(String, String)>
+{
+  let needle = query.trim().to_lowercase();
+  let mut matches = Vec::new();
+
+  for body in &bodies.bodies
+  {
+    let name = &body.manifest.name;
+    let kind = kind_label(&body.manifest.kind);
+
+    if name.to_lowercase().contains(&needle) || kind.to_lowercase().contains(&needle)
+    {
+      matches.push((name.clone(), kind.to_string()));
+    }
+
+    if matches.len() >= MAX_RESULTS
+    {
+      break;
+    }
+  }
+
+  matches
+}
+
+fn kind_label(kind: &BodyKind) -> &'static str
+{
+  match kind
+  {
+    BodyKind::Terrestrial => "terrestrial",
+    BodyKind::Rocky { .. } => "rocky",
+    BodyKind::GasGiant { .. } => "gas giant",
+    BodyKind::SmallBody { .. } => "small body",
+    BodyKind::Star { .. } => "star",
+    BodyKind::Manmade => "manmade",
+  }
+}
@@ -0,0 +1,88 @@
+use crate::command::Command;
+use crate::core::config::{AppConfig, PresentMode};
+use crate::render::still::StillSettings;
+
+/// Highest cap the slider offers; 0 means no cap.
+const MAX_FPS_CAP: u32 = 240;
+/// Largest still render edge, in pixels.
+const MAX_STILL_SIZE: u32 = 16384;
+const MAX_STILL_SAMPLES: u32 = 1024;
+
+/// Display settings (present mode, latency, frame-rate cap, FXAA),
+/// whether camera switches are undoable, and the still render. Changes
+/// are returned as commands and applied to the running config.
+pub fn draw(
+  ctx: &egui::Context,
+  config: &AppConfig,
+  still: &mut StillSettings,
+  open: &mut bool,
+) -> Option<Command>
+{
+  let mut command = None;
+
+  egui::Window::new("Settings").open(open).resizable(false).show(ctx, |ui| {
+    ui.label("Present mode");
+    ui.horizontal(|ui| {
+      let current = config.present_mode();
+      for mode in PresentMode::ALL
+      {
+        if ui.selectable_label(current == mode, mode.name()).clicked() && current != mode
+        {
+          command = Some(Command::SetPresentMode(mode));
+        }
+      }
+    });
+
+    ui.horizontal(|ui| {
+      ui.label("Frame latency");
+      for frames in 1..=3
+      {
+        let current = config.max_frame_latency;
+        let label = ui.selectable_label(current == frames, frames.to_string());
+        if label.clicked() && current != frames
+        {
+          command = Some(Command::SetFrameLatency(frames));
+        }
+      }
+    });
+
+    let mut cap = config.fps_cap;
+    let slider = egui::Slider::new(&mut cap, 0..=MAX_FPS_CAP).text("FPS cap (0 = off)");
+    if ui.add(slider).changed()
+    {
+      command = Some(Command::SetFpsCap(cap));
+    }
+
+    let mut fxaa = config.fxaa.unwrap_or(false);
+    if ui.checkbox(&mut fxaa, "FXAA").on_hover_text("Cheaper antialiasing than MSAA").changed()
+    {
+      command = Some(Command::SetFxaa(fxaa));
+    }
+
+    ui.separator();
+    let mut undo_camera = config.undo_camera;
+    let hint = "Views are always kept in the view history (Alt+Left / Alt+Right)";
+    if ui.checkbox(&mut undo_camera, "Undo camera mode switches").on_hover_text(hint).changed()
+    {
+      command = Some(Command::SetUndoCamera(undo_camera));
+    }
+
+    ui.separator();
+    ui.label("Still render");
+    ui.horizontal(|ui| {
+      ui.add(egui::DragValue::new(&mut still.width).range(1..=MAX_STILL_SIZE));
+      ui.label("x");
+      ui.add(egui::DragValue::new(&mut still.height).range(1..=MAX_STILL_SIZE));
+      ui.add(
+        egui::DragValue::new(&mut still.samples).range(1..=MAX_STILL_SAMPLES).suffix(" samples"),
+      );
+    });
+    if ui.button("Render").on_hover_text("Saved as still.png in the save folder").clicked()
+    {
+      let (width, height, samples) = (still.width, still.height, still.samples);
+      command = Some(Command::RenderStill { width, height, samples });
+    }
+  });
+
+  command
+}
@@ -0,0 +1,37 @@
+use crate::core::task::{TaskHandle, TaskList};
+
+/// Bottom bar listing running tasks with progress and a cancel button.
+/// Hidden entirely when nothing is running.
+pub fn draw(ctx: &egui::Context, tasks: &TaskList)
+{
+  if tasks.tasks.is_empty()
+  {
+    return;
+  }
+
+  egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+    for task in &tasks.tasks
+    {
+      draw_task(ui, task);
+    }
+  });
+}
+
+fn draw_task(ui: &mut egui::Ui, task: &TaskHandle)
+{
+  ui.horizontal(|ui| {
+    ui.label(task.name());
+
+    let bar = egui::ProgressBar::new(task.progress()).text(task.message()).desired_width(240.0);
+    ui.add(bar);
+
+    if task.is_cancelled()
+    {
+      ui.label("Cancelling...");
+    }
+    else if ui.button("Cancel").clicked()
+    {
+      task.cancel();
+    }
+  });
+}
@@ -0,0 +1,71 @@
+use crate::command::Command;
+use crate::core::notify::{NotificationQueue, NotifyLevel};
+
+/// Stack of toasts in the bottom-right corner. Returns the command of any
+/// action button clicked this frame; the caller executes it.
+pub fn draw(ctx: &egui::Context, queue: &mut NotificationQueue) -> Option<Command>
+{
+  if queue.items.is_empty()
+  {
+    return None;
+  }
+
+  let mut clicked = None;
+  let mut dismissed = None;
+
+  egui::Area::new(egui::Id::new("toasts"))
+    .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -40.0))
+    .show(ctx, |ui| {
+      for (index, item) in queue.items.iter().enumerate()
+      {
+        egui::Frame::popup(ui.style()).show(ui, |ui| {
+          ui.horizontal(|ui| {
+            ui.colored_label(
+              level_color(item.level),
+              message_text(&item.message, item.repeat_count),
+            );
+
+            if let Some(action) = &item.action
+            {
+              if ui.button(&action.label).clicked()
+              {
+                clicked = Some(action.command.clone());
+                dismissed = Some(index);
+              }
+            }
+
+            if ui.small_button("x").clicked()
+            {
+              dismissed = Some(index);
+            }
+          });
+        });
+      }
+    });
+
+  if let Some(index) = dismissed
+  {
+    queue.dismiss(index);
+  }
+
+  clicked
+}
+
+fn message_text(message: &str, repeat_count: u32) -> String
+{
+  if repeat_count > 1
+  {
+    return format!("{} (x{})", message, repeat_count);
+  }
+  message.to_string()
+}
+
+fn level_color(level: NotifyLevel) -> egui::Color32
+{
+  match level
+  {
+    NotifyLevel::Info => egui::Color32::LIGHT_GRAY,
+    NotifyLevel::Warning => egui::Color32::from_rgb(240, 190, 60),
+    NotifyLevel::Error => egui::Color32::from_rgb(235, 80, 70),
+  }
+}
@@ -0,0 +1,74 @@
+use crate::command::Command;
+use crate::render::shared::{DisplayMode, SharedState};
+
+/// Triangles beyond this are left out of the layout to keep the panel cheap.
+const MAX_DRAWN_TRIANGLES: usize = 20_000;
+/// Side of the square UV plot, in points.
+const PLOT_SIZE: f32 = 256.0;
+
+/// UV inspector: display mode switch and the 0..1 UV layout of the body
+/// mesh. Every body shares the same mesh, so the selection only names it.
+pub fn draw(ctx: &egui::Context, shared: &SharedState, open: &mut bool) -> Option<Command>
+{
+  let mut command = None;
+
+  egui::Window::new("UV layout").open(open).resizable(false).show(ctx, |ui| {
+    ui.horizontal(|ui| {
+      for mode in [DisplayMode::Shaded, DisplayMode::Checker, DisplayMode::Field]
+      {
+        if ui.selectable_label(shared.display_mode == mode, mode.name()).clicked()
+          && shared.display_mode != mode
+        {
+          command = Some(Command::SetDisplayMode(mode));
+        }
+      }
+    });
+
+    let mut subject = "No selection".to_string();
+    if let Some(&index) = shared.selected.first()
+    {
+      if let Some(body) = shared.body_registry.bodies.get(index)
+      {
+        subject = format!("{} (shared body mesh)", body.manifest.name);
+      }
+    }
+    ui.label(subject);
+
+    let uvs = match &shared.body_mesh_uvs
+    {
+      Some(uvs) => uvs,
+      None =>
+      {
+        ui.label("Mesh not loaded");
+        return;
+      }
+    };
+
+    let triangle_count = uvs.len() / 3;
+    let drawn = triangle_count.min(MAX_DRAWN_TRIANGLES);
+    let mut caption = format!("{} triangles", triangle_count);
+    if drawn < triangle_count
+    {
+      caption = format!("{} ({} drawn)", caption, drawn);
+    }
+    ui.label(caption);
+
+    let (response, painter) =
+      ui.allocate_painter(egui::vec2(PLOT_SIZE, PLOT_SIZE), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 0.0, egui::Color32::from_gray(20));
+
+    // v runs up the plot, as in most UV editors.
+    let to_screen = |uv: [f32; 2]| rect.left_bottom() + egui::vec2(uv[0], -uv[1]) * PLOT_SIZE;
+    let stroke = egui::Stroke::new(0.5_f32, egui::Color32::from_rgb(120, 200, 255));
+    for triangle in uvs.chunks_exact(3).take(drawn)
+    {
+      let points = [to_screen(triangle[0]), to_screen(triangle[1]), to_screen(triangle[2])];
+      painter.line_segment([points[0], points[1]], stroke);
+      painter.line_segment([points[1], points[2]], stroke);
+      painter.line_segment([points[2], points[0]], stroke);
+    }
+  });
+
+  command
+}
@@ -0,0 +1,25 @@
+use crate::command::Command;
+use crate::render::camera::ViewPreset;
+
+/// Row of view preset buttons in the top-right corner. Returns the chosen
+/// preset as a command.
+pub fn draw(ctx: &egui::Context) -> Option<Command>
+{
+  let mut chosen = None;
+
+  egui::Area::new(egui::Id::new("view_bar"))
+    .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+    .show(ctx, |ui| {
+      ui.horizontal(|ui| {
+        for preset in ViewPreset::ALL
+        {
+          if ui.small_button(preset.name()).clicked()
+          {
+            chosen = Some(preset);
+          }
+        }
+      });
+    });
+
+  chosen.map(Command::SetView)
+}
@@ -153,6 +153,7 @@ impl BodyManifest
 /// Rules (in priority order):
 ///   is_star                → Star
 ///   use_real_data          → Terrestrial
+///   mesh_path              → Manmade
 ///   radius_km >= 24_000    → GasGiant  (Jupiter ~71k, Saturn ~58k, Uranus ~25k, Neptune ~24k)
 ///   radius_km >= 1_500     → Rocky     (Mars ~3.4k, Venus ~6k, Moon ~1.7k, Pluto ~1.2k)
 ///   radius_km <  1_500     → SmallBody (Ceres ~473, Vesta ~263, Charon ~606)
@@ -168,6 +169,11 @@ fn derive_kind(c: &BodyConfig) -> BodyKind
     return BodyKind::Terrestrial;
   }
 
+  if c.mesh_path.is_some()
+  {
+    return BodyKind::Manmade;
+  }
+
   if c.radius_km >= 24_000.0
   {
     return BodyKind::GasGiant {
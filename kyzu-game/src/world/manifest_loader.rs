@@ -53,7 +53,7 @@ pub fn load_all_manifests(
   Ok(manifests)
 }
 
-fn load_single_manifest(path: &Path) -> anyhow::Result<BodyManifest>
+pub fn load_single_manifest(path: &Path) -> anyhow::Result<BodyManifest>
 {
   let bytes = std::fs::read(path).map_err(|e| anyhow::anyhow!("Read error: {}", e))?;
 
@@ -1,3 +1,5 @@
 pub mod body;
 pub mod manifest_loader;
+pub mod probe;
 pub mod registry;
+pub mod selection;
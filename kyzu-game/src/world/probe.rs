@@ -0,0 +1,61 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use glam::DVec3;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Point probe
+//
+//  While the probe is active a click records the surface point under the
+//  cursor, snapped to the nearest mesh vertex, instead of picking. The
+//  list is shown in the probe panel and exported as CSV.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbePoint
+{
+  pub body: String,
+  /// World position in metres.
+  pub position: DVec3,
+  /// Body-fixed latitude and longitude in degrees, as in the bake.
+  pub lat_deg: f64,
+  pub lon_deg: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct ProbeLog
+{
+  /// Clicks record points instead of picking.
+  pub active: bool,
+  pub points: Vec<ProbePoint>,
+}
+
+impl ProbeLog
+{
+  /// Header plus one row per point, metres and degrees.
+  pub fn to_csv(&self) -> String
+  {
+    let mut csv = String::from("index,body,x_m,y_m,z_m,lat_deg,lon_deg\n");
+    for (index, p) in self.points.iter().enumerate()
+    {
+      let _ = writeln!(
+        csv,
+        "{},{},{:.3},{:.3},{:.3},{:.6},{:.6}",
+        index + 1,
+        p.body,
+        p.position.x,
+        p.position.y,
+        p.position.z,
+        p.lat_deg,
+        p.lon_deg
+      );
+    }
+    csv
+  }
+
+  pub fn export(&self, path: &Path) -> anyhow::Result<()>
+  {
+    std::fs::write(path, self.to_csv())?;
+    Ok(())
+  }
+}
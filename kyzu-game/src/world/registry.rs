@@ -1,5 +1,9 @@
-use glam::DVec3;
+use std::collections::BTreeMap;
 
+use glam::{DQuat, DVec3};
+use serde::{Deserialize, Serialize};
+
+use crate::render::primitives::Primitive;
 use crate::world::body::BodyManifest;
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -49,6 +53,87 @@ pub enum CameraFocus
   Freepoint,
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+//  RenderFlags
+//
+//  Per-body display switches, set with the body.flag command and kept in
+//  the save by body name. Always-on-top bodies draw over everything else
+//  (reference markers); no-pick bodies are invisible to picking, ray casts
+//  and region select; no-shadow bodies are lit all round, never falling
+//  into their own night side; no-export bodies are left out of stills and
+//  headless renders.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFlag
+{
+  AlwaysOnTop,
+  NoPick,
+  NoShadow,
+  NoExport,
+}
+
+impl BodyFlag
+{
+  pub fn name(self) -> &'static str
+  {
+    match self
+    {
+      BodyFlag::AlwaysOnTop => "on_top",
+      BodyFlag::NoPick => "no_pick",
+      BodyFlag::NoShadow => "no_shadow",
+      BodyFlag::NoExport => "no_export",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<BodyFlag>
+  {
+    match name
+    {
+      "on_top" => Some(BodyFlag::AlwaysOnTop),
+      "no_pick" => Some(BodyFlag::NoPick),
+      "no_shadow" => Some(BodyFlag::NoShadow),
+      "no_export" => Some(BodyFlag::NoExport),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderFlags
+{
+  pub always_on_top: bool,
+  pub no_pick: bool,
+  pub no_shadow: bool,
+  pub no_export: bool,
+}
+
+impl RenderFlags
+{
+  pub fn get(&self, flag: BodyFlag) -> bool
+  {
+    match flag
+    {
+      BodyFlag::AlwaysOnTop => self.always_on_top,
+      BodyFlag::NoPick => self.no_pick,
+      BodyFlag::NoShadow => self.no_shadow,
+      BodyFlag::NoExport => self.no_export,
+    }
+  }
+
+  pub fn set(&mut self, flag: BodyFlag, on: bool)
+  {
+    match flag
+    {
+      BodyFlag::AlwaysOnTop => self.always_on_top = on,
+      BodyFlag::NoPick => self.no_pick = on,
+      BodyFlag::NoShadow => self.no_shadow = on,
+      BodyFlag::NoExport => self.no_export = on,
+    }
+  }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 //  BodyState
 //
@@ -57,6 +142,7 @@ pub enum CameraFocus
 //  can be immutable once loaded.
 // ─────────────────────────────────────────────────────────────────────────────
 
+#[derive(Clone)]
 pub struct BodyState
 {
   /// Immutable description loaded from disk.
@@ -68,11 +154,21 @@ pub struct BodyState
   pub world_pos: DVec3,
 
   /// Current rotation angle around the axial tilt axis, in radians.
-  /// Incremented every frame by (2π / rotation_period_s) * dt.
+  /// Incremented every sim tick by (2π / rotation_period_s) * tick_dt.
   pub rotation_angle: f64,
 
+  /// rotation_angle as of the previous sim tick, kept for interpolation.
+  pub prev_rotation_angle: f64,
+
   /// What the streaming system currently has resident on the GPU.
   pub streaming: StreamingStatus,
+
+  /// Draw order and picking switches; see RenderFlags.
+  pub flags: RenderFlags,
+
+  /// Generated shape for a body added from the Insert menu; None for
+  /// bodies from the world, which draw their bake.
+  pub primitive: Option<Primitive>,
 }
 
 impl BodyState
@@ -80,7 +176,63 @@ impl BodyState
   pub fn new(manifest: BodyManifest) -> Self
   {
     let world_pos = manifest.position_at_epoch;
-    Self { manifest, world_pos, rotation_angle: 0.0, streaming: StreamingStatus::Pending }
+    Self {
+      manifest,
+      world_pos,
+      rotation_angle: 0.0,
+      prev_rotation_angle: 0.0,
+      streaming: StreamingStatus::Pending,
+      flags: RenderFlags::default(),
+      primitive: None,
+    }
+  }
+
+  /// Advance the spin by one fixed sim step.
+  pub fn tick(&mut self, tick_dt: f64)
+  {
+    self.prev_rotation_angle = self.rotation_angle;
+
+    if self.manifest.rotation_period_s == 0.0
+    {
+      return;
+    }
+
+    let angular_speed = std::f64::consts::TAU / self.manifest.rotation_period_s;
+    self.rotation_angle = (self.rotation_angle + angular_speed * tick_dt) % std::f64::consts::TAU;
+  }
+
+  /// Jump the spin ahead by `dt` in one go, with nothing to interpolate
+  /// across: used for time the loop slept through.
+  pub fn skip(&mut self, dt: f64)
+  {
+    self.tick(dt);
+    self.prev_rotation_angle = self.rotation_angle;
+  }
+
+  /// Axial tilt about Z, then spin about the tilted Y (north pole) axis.
+  pub fn orientation(&self, alpha: f64) -> DQuat
+  {
+    let tilt = DQuat::from_rotation_z(self.manifest.axial_tilt_rad);
+    let spin = DQuat::from_rotation_y(self.interpolated_rotation(alpha));
+    tilt * spin
+  }
+
+  /// Spin angle blended between the last two ticks. alpha comes from SimClock.
+  pub fn interpolated_rotation(&self, alpha: f64) -> f64
+  {
+    let mut delta = self.rotation_angle - self.prev_rotation_angle;
+
+    // Undo the wrap at TAU so we don't sweep backwards through a full turn
+    if delta < -std::f64::consts::PI
+    {
+      delta += std::f64::consts::TAU;
+    }
+    else if delta > std::f64::consts::PI
+    {
+      delta -= std::f64::consts::TAU;
+    }
+
+    self.prev_rotation_angle + delta * alpha
   }
 }
 
@@ -95,6 +247,7 @@ impl BodyState
 //  added. spawn_body() pushes to the end; existing indices never change.
 // ─────────────────────────────────────────────────────────────────────────────
 
+#[derive(Clone)]
 pub struct BodyRegistry
 {
   pub bodies: Vec<BodyState>,
@@ -123,6 +276,65 @@ impl BodyRegistry
     index
   }
 
+  /// Index of the body with this name, ignoring case.
+  pub fn find(&self, name: &str) -> Option<usize>
+  {
+    self.bodies.iter().position(|b| b.manifest.name.eq_ignore_ascii_case(name))
+  }
+
+  /// Give bodies the flags saved under their names (SaveConfig::body_flags).
+  pub fn apply_flags(&mut self, saved: &BTreeMap<String, RenderFlags>)
+  {
+    for body in &mut self.bodies
+    {
+      if let Some(flags) = saved.get(&body.manifest.name)
+      {
+        body.flags = *flags;
+      }
+    }
+  }
+
+  /// Run one fixed simulation step for every body.
+  pub fn tick(&mut self, tick_dt: f64)
+  {
+    for body in &mut self.bodies
+    {
+      body.tick(tick_dt);
+    }
+  }
+
+  /// Jump every body ahead by `dt` of sim time. See BodyState::skip.
+  pub fn skip(&mut self, dt: f64)
+  {
+    for body in &mut self.bodies
+    {
+      body.skip(dt);
+    }
+  }
+
+  /// How fast the fastest spinning body's equator crosses the screen, in
+  /// pixels per second, seen from `eye`. Zero when nothing spins. A planet
+  /// turning once a day is far below a pixel per second from orbit, so
+  /// spin alone rarely needs every frame drawn.
+  pub fn spin_pixels_per_second(&self, eye: DVec3, pixels_per_radian: f64) -> f64
+  {
+    let mut fastest = 0.0_f64;
+    for body in &self.bodies
+    {
+      let period = body.manifest.rotation_period_s;
+      if period == 0.0
+      {
+        continue;
+      }
+      let radius = body.manifest.radius_m;
+      let surface_speed = radius * std::f64::consts::TAU / period.abs();
+      // Measured to the nearest surface; from inside, as if just above it.
+      let distance = ((body.world_pos - eye).length() - radius).max(radius * 1e-3);
+      fastest = fastest.max(surface_speed / distance * pixels_per_radian);
+    }
+    fastest
+  }
+
   /// The body the camera is currently anchored to, if any.
   pub fn focal_body(&self) -> Option<&BodyState>
   {
@@ -153,3 +365,70 @@ impl BodyRegistry
       .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
   }
 }
+
+#[cfg(test)]
+mod tests
+{
+  use super::*;
+  use crate::world::body::BodyKind;
+
+  fn manifest(radius_m: f64, rotation_period_s: f64) -> BodyManifest
+  {
+    BodyManifest {
+      name: "Body".to_string(),
+      kind: BodyKind::SmallBody { base_color: [0.5; 3] },
+      radius_m,
+      lod_max: 0,
+      position_at_epoch: DVec3::ZERO,
+      orbital_elements: None,
+      axial_tilt_rad: 0.0,
+      rotation_period_s,
+    }
+  }
+
+  #[test]
+  fn spin_speed_falls_off_with_distance()
+  {
+    let mut registry = BodyRegistry::new();
+    assert_eq!(registry.spin_pixels_per_second(DVec3::X * 10.0, 1000.0), 0.0);
+
+    registry.spawn(manifest(1.0, 0.0), false);
+    assert_eq!(registry.spin_pixels_per_second(DVec3::X * 10.0, 1000.0), 0.0);
+
+    // Equator at TAU m/s, seen from 1 m above it: one radian a second.
+    registry.spawn(manifest(1.0, 1.0), false);
+    let near = registry.spin_pixels_per_second(DVec3::X * 2.0, 1000.0);
+    assert!((near - std::f64::consts::TAU * 1000.0).abs() < 1e-6);
+
+    let far = registry.spin_pixels_per_second(DVec3::X * 1001.0, 1000.0);
+    assert!((far * 1000.0 - near).abs() < 1e-6);
+  }
+
+  #[test]
+  fn saved_flags_apply_by_name()
+  {
+    let mut registry = BodyRegistry::new();
+    let mut moon = manifest(1.0, 0.0);
+    moon.name = "Moon".to_string();
+    registry.spawn(moon, false);
+    registry.spawn(manifest(1.0, 0.0), false);
+
+    let json = r#"{ "Moon": { "no_shadow": true, "no_export": true } }"#;
+    let saved: BTreeMap<String, RenderFlags> = serde_json::from_str(json).unwrap();
+    registry.apply_flags(&saved);
+
+    let moon = registry.bodies[0].flags;
+    assert!(moon.get(BodyFlag::NoShadow) && moon.get(BodyFlag::NoExport));
+    assert!(!moon.get(BodyFlag::NoPick));
+    assert_eq!(registry.bodies[1].flags, RenderFlags::default());
+  }
+
+  #[test]
+  fn skipping_jumps_the_spin_without_a_sweep()
+  {
+    let mut body = BodyState::new(manifest(1.0, 4.0));
+    body.skip(1.0);
+    assert!((body.rotation_angle - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    assert_eq!(body.interpolated_rotation(0.0), body.rotation_angle);
+  }
+}
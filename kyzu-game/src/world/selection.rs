@@ -0,0 +1,87 @@
+// ─────────────────────────────────────────────────────────────────────────────
+//  Selection
+//
+//  Bodies the player has clicked, as indices into BodyRegistry::bodies.
+//  Owned by App and fed from BodyPicked events; the renderer gets a copy
+//  each time it changes so BodyRenderer can tint selected bodies.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// How a click or marquee combines with the current selection.
+/// Chosen from the held modifiers by InputState::select_mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectMode
+{
+  Replace,
+  /// Shift held.
+  Add,
+  /// Ctrl held.
+  Subtract,
+}
+
+/// What a left drag in the viewport does. A plain click always picks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectTool
+{
+  /// Rubber-band rectangle over body centres.
+  Box,
+  /// Freehand outline; bodies visible inside it in the ID buffer.
+  Lasso,
+  /// Circular brush swept over the ID buffer; scroll sets the radius.
+  Paint,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Selection
+{
+  /// In selection order; the last entry is the primary (most recent) body.
+  pub bodies: Vec<usize>,
+}
+
+impl Selection
+{
+  /// Plain click: replace the selection with `index`, or clear it on
+  /// empty space.
+  pub fn select_only(&mut self, index: Option<usize>)
+  {
+    self.bodies.clear();
+    if let Some(i) = index
+    {
+      self.bodies.push(i);
+    }
+  }
+
+  /// Combine a group of bodies (e.g. everything inside a marquee).
+  pub fn apply(&mut self, mode: SelectMode, indices: &[usize])
+  {
+    match mode
+    {
+      SelectMode::Replace =>
+      {
+        self.bodies.clear();
+        self.bodies.extend_from_slice(indices);
+      }
+      SelectMode::Add =>
+      {
+        for &index in indices
+        {
+          if !self.bodies.contains(&index)
+          {
+            self.bodies.push(index);
+          }
+        }
+      }
+      SelectMode::Subtract => self.bodies.retain(|i| !indices.contains(i)),
+    }
+  }
+
+  /// Shift-click: add `index` if it isn't selected, remove it if it is.
+  pub fn toggle(&mut self, index: usize)
+  {
+    if let Some(pos) = self.bodies.iter().position(|&i| i == index)
+    {
+      self.bodies.remove(pos);
+      return;
+    }
+    self.bodies.push(index);
+  }
+}